@@ -3,8 +3,32 @@
 use shared::prelude::*;
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<&'static str> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"));
+    static ref RAW_INPUT: String = puzzle_input::load(0).unwrap();
+    static ref PUZZLE_INPUT: Vec<&'static str> = puzzle_input::lines(&RAW_INPUT);
+    static ref TEST_INPUT: Vec<&'static str> = puzzle_input::lines(include_str!("test_input.txt"));
+}
+
+pub struct Day00;
+
+impl Puzzle for Day00 {
+    const DAY: u8 = 0;
+    const TITLE: &'static str = "Template";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<&'a str>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(puzzle_input::lines(input))
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(input.len())
+    }
+
+    fn part_two(_: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Err(anyhow!("Not solved yet"))
+    }
 }
 
 #[cfg(test)]
@@ -13,11 +37,12 @@ mod part_one {
 
     #[test]
     fn test_cases() {
-        assert_eq!(1 + 1, 2);
+        assert_eq!(Day00::part_one(&TEST_INPUT).unwrap(), 0);
     }
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(0);
         assert_eq!(*PUZZLE_INPUT, Vec::<String>::new());
     }
 }
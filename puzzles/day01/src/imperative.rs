@@ -1,20 +1,18 @@
 use shared::prelude::*;
-use std::num::ParseIntError;
-use std::str::FromStr;
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<i32> =
-        parse_input(&puzzle_input::lines(include_str!("puzzle_input.txt"))).unwrap();
+    static ref RAW_INPUT: String = puzzle_input::load(1).unwrap();
+    static ref PUZZLE_INPUT: Vec<i32> = parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
 }
 
-fn parse_input(input: &[&str]) -> Result<Vec<i32>, ParseIntError> {
-    input.iter().map(|x| i32::from_str(x)).collect()
+fn parse_input(input: &[&str]) -> Result<Vec<i32>, ParseError> {
+    parse_error::parse_lines(input, |x| parse_error::parse_at(x, x, "a number"))
 }
 
 pub fn correct_expense_report(entries: &[i32]) -> Option<i32> {
-    for i in entries.iter().copied() {
-        for i2 in entries.iter().copied().filter(move |i2| i2 != &i) {
-            if i + i2 == 2020 {
+    for (index, i) in entries.iter().copied().enumerate() {
+        for (index2, i2) in entries.iter().copied().enumerate() {
+            if index != index2 && i + i2 == 2020 {
                 return Some(i * i2);
             }
         }
@@ -34,6 +32,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(1);
         assert_eq!(correct_expense_report(&PUZZLE_INPUT), Some(651651));
     }
 }
@@ -1,31 +1,122 @@
 // Day 01: Report Repair
 
-use shared::prelude::*;
-use std::collections::HashSet;
-use std::num::ParseIntError;
-use std::str::FromStr;
+use shared::{
+    prelude::*,
+    puzzle_input::{Open, Stream},
+};
+use std::{collections::HashMap, convert::TryFrom, io::BufRead};
 
 pub mod imperative;
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<i32> =
-        parse_input(&puzzle_input::lines(include_str!("puzzle_input.txt"))).unwrap();
+    static ref RAW_INPUT: String = puzzle_input::load(1).unwrap();
+    static ref PUZZLE_INPUT: Vec<i32> = parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
 }
 
-pub fn parse_input(input: &[&str]) -> Result<Vec<i32>, ParseIntError> {
-    input.iter().map(|x| i32::from_str(x)).collect()
+pub fn parse_input(input: &[&str]) -> Result<Vec<i32>, ParseError> {
+    parse_error::parse_lines(input, parse_entry)
 }
 
+pub fn stream_input<'a>(
+    reader: impl BufRead + 'a,
+) -> impl Iterator<Item = anyhow::Result<i32>> + 'a {
+    puzzle_input::stream_lines(reader, parse_entry)
+}
+
+/// Anything outside 0 to 2020 could only add up to 2020 with a negative entry,
+/// which expense reports don't have
+fn parse_entry(line: &str) -> Result<i32, ParseError> {
+    let entry = parse_error::parse_at(line, line, "a number")?;
+    if (0..=2020).contains(&entry) {
+        Ok(entry)
+    } else {
+        Err(ParseError::at(line, line, "an entry from 0 to 2020"))
+    }
+}
+
+/// How many entries could be part of a sum to 2020
+const POSSIBLE: usize = 2021;
+
+fn possible(entry: i32) -> Option<usize> {
+    usize::try_from(entry).ok().filter(|&x| x < POSSIBLE)
+}
+
+/// The product of the first entry that adds up to 2020 with another one. An
+/// entry can't pair with itself, but can with another entry of the same value.
 pub fn correct_expense_report(entries: &[i32]) -> Option<i32> {
-    let set: HashSet<_> = entries.iter().cloned().collect();
+    let mut counts = HashMap::new();
+    for &i in entries {
+        *counts.entry(i).or_insert(0) += 1;
+    }
     entries
         .iter()
         .copied()
         .map(|i| (i, 2020 - i))
-        .find(|(_, missing)| set.contains(missing))
+        .find(|&(i, missing)| match counts.get(&missing) {
+            Some(&count) => missing != i || count > 1,
+            None => false,
+        })
         .map(|(i, missing)| i * missing)
 }
 
+/// Like [`correct_expense_report`] a line at a time. It has to read every
+/// entry to know which is the first with a partner, but only remembers where
+/// each value first appeared and whether it appeared again.
+pub fn stream_expense_report(
+    entries: impl Iterator<Item = anyhow::Result<i32>>,
+) -> anyhow::Result<Option<i32>> {
+    // When each value was first seen, and how many times it has been, up to
+    // the two it takes to pair with itself
+    let mut seen: Vec<Option<(usize, u8)>> = vec![None; POSSIBLE];
+    for (position, entry) in entries.enumerate() {
+        if let Some(index) = possible(entry?) {
+            let (_, count) = seen[index].get_or_insert((position, 0));
+            *count = (*count + 1).min(2);
+        }
+    }
+
+    let first_with_partner = (0..POSSIBLE)
+        .filter_map(|index| {
+            let (position, count) = seen[index]?;
+            let (_, partners) = seen[2020 - index]?;
+            let needed = if index == 1010 { 2 } else { 1 };
+            Some((position, index)).filter(|_| count.min(partners) >= needed)
+        })
+        .min();
+    Ok(first_with_partner.map(|(_, index)| (index * (2020 - index)) as i32))
+}
+
+/// Three entries that add up to 2020, a line at a time. Keeps the first pair
+/// found for each sum up to 2020, so each entry is checked in one lookup.
+pub fn stream_expense_report_mk_2(
+    entries: impl Iterator<Item = anyhow::Result<i32>>,
+) -> anyhow::Result<Option<i32>> {
+    // How many of each entry, up to the two it takes to pair with itself
+    let mut seen = vec![0u8; POSSIBLE];
+    let mut pairs: Vec<Option<(i32, i32)>> = vec![None; POSSIBLE];
+
+    for entry in entries {
+        let entry = entry?;
+        let index = match possible(entry) {
+            Some(index) => index,
+            None => continue,
+        };
+        if let Some((a, b)) = pairs[2020 - index] {
+            return Ok(Some(a * b * entry));
+        }
+        // A third copy of an entry can't make any new pairs
+        if seen[index] < 2 {
+            // `seen` doesn't count this entry yet, so it can only pair with
+            // itself if there was an earlier copy
+            for other in (0..=2020 - index).filter(|&x| seen[x] > 0) {
+                pairs[index + other].get_or_insert((other as i32, entry));
+            }
+            seen[index] += 1;
+        }
+    }
+    Ok(None)
+}
+
 pub fn correct_expense_report_mk_2(entries: &[i32]) -> Option<i32> {
     let mut options = entries.iter().copied().flat_map(|i| {
         entries
@@ -46,6 +137,41 @@ pub fn correct_expense_report_mk_2(entries: &[i32]) -> Option<i32> {
         .map(|(i, i2, i3)| i * i2 * i3)
 }
 
+pub struct Day01;
+
+impl Puzzle for Day01 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Report Repair";
+    const VERSION: &'static str = "2";
+    type Input<'a> = Vec<i32>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_input(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<i32> {
+        correct_expense_report(input).ok_or(anyhow!("No two entries add up to 2020"))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<i32> {
+        correct_expense_report_mk_2(input).ok_or(anyhow!("No three entries add up to 2020"))
+    }
+}
+
+impl Stream for Day01 {
+    fn stream_part_one(open: &mut Open<'_>) -> anyhow::Result<i32> {
+        stream_expense_report(stream_input(open()?))?
+            .ok_or(anyhow!("No two entries add up to 2020"))
+    }
+
+    fn stream_part_two(open: &mut Open<'_>) -> anyhow::Result<i32> {
+        stream_expense_report_mk_2(stream_input(open()?))?
+            .ok_or(anyhow!("No three entries add up to 2020"))
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -56,8 +182,55 @@ mod part_one {
         assert_eq!(correct_expense_report(&test_data), Some(514579));
     }
 
+    #[test]
+    fn streams() {
+        let stream = |input: &'static str| stream_expense_report(stream_input(input.as_bytes()));
+        assert_eq!(
+            stream("1721\n979\n366\n299\n675\n1456\n").unwrap(),
+            Some(514579)
+        );
+        assert_eq!(stream("1010\n2000\n").unwrap(), None);
+        assert_eq!(stream("1010\n1010\n").unwrap(), Some(1020100));
+        assert!(stream("1010\nx\n1010\n").is_err());
+        // The first entry with a partner, not the first pair to be finished
+        assert_eq!(stream("1\n10\n2010\n2019\n").unwrap(), Some(2019));
+    }
+
+    #[test]
+    fn rejects_entries_that_cant_add_up() {
+        let err = parse_input(&["-5", "2025"]).unwrap_err();
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (Some(1), "an entry from 0 to 2020")
+        );
+        let stream = stream_expense_report(stream_input("2025\n-5\n".as_bytes()));
+        assert!(stream.is_err());
+    }
+
+    #[test]
+    fn implementations_agree() {
+        use shared::differential::{self, Differential};
+
+        // Entries near 1010 so that plenty of them add up to 2020. A 1010 can
+        // only pair with another one, never itself.
+        let entries = differential::vec_of(differential::range(1000..1021), 0..8);
+        let to_i32 = |x: &Vec<i64>| x.iter().map(|&x| x as i32).collect::<Vec<_>>();
+        Differential::new(entries)
+            .example(vec![1010, 1020])
+            .example(vec![1010, 1020, 1010])
+            .implementation("functional", |x| correct_expense_report(&to_i32(x)))
+            .implementation("imperative", |x| {
+                imperative::correct_expense_report(&to_i32(x))
+            })
+            .implementation("streaming", |x| {
+                stream_expense_report(to_i32(x).into_iter().map(Ok)).unwrap()
+            })
+            .assert_agree();
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(1);
         assert_eq!(correct_expense_report(&PUZZLE_INPUT), Some(651651));
     }
 }
@@ -70,8 +243,21 @@ mod part_two {
         let test_data = vec![1721, 979, 366, 299, 675, 1456];
         assert_eq!(correct_expense_report_mk_2(&test_data), Some(241861950));
     }
+
+    #[test]
+    fn streams() {
+        let stream =
+            |input: &'static str| stream_expense_report_mk_2(stream_input(input.as_bytes()));
+        assert_eq!(
+            stream("1721\n979\n366\n299\n675\n1456\n").unwrap(),
+            Some(241861950)
+        );
+        assert_eq!(stream("1000\n20\n1000\n").unwrap(), Some(20000000));
+        assert_eq!(stream("1000\n20\n").unwrap(), None);
+    }
     #[test]
     fn answer() {
+        shared::skip_without_input!(1);
         assert_eq!(correct_expense_report_mk_2(&PUZZLE_INPUT), Some(214486272));
     }
 }
@@ -1,13 +1,16 @@
 // Day 02: Password Philosophy
 
+use std::{fmt::Display, io::BufRead};
+
 use regex::Regex;
-use shared::prelude::*;
-use std::error::Error;
-use std::str::FromStr;
+use shared::{
+    prelude::*,
+    puzzle_input::{Open, Stream},
+};
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<&'static str> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"));
+    static ref RAW_INPUT: String = puzzle_input::load(2).unwrap();
+    static ref PUZZLE_INPUT: Vec<&'static str> = puzzle_input::lines(&RAW_INPUT);
     static ref PUZZLE_INPUT_PARSED: Vec<PasswordEntry> = parse_lines(&PUZZLE_INPUT).unwrap();
     static ref REGEX: Regex = Regex::new(r"(\d+)\-(\d+) ([a-z]): ([a-z]+)").unwrap();
 }
@@ -20,23 +23,46 @@ pub struct PasswordEntry {
     password: String,
 }
 
-pub fn parse_lines(lines: &[&str]) -> Result<Vec<PasswordEntry>, Box<dyn Error>> {
-    lines.iter().map(|x| parse_line(x)).collect()
+pub fn parse_lines(lines: &[&str]) -> Result<Vec<PasswordEntry>, ParseError> {
+    parse_error::parse_lines(lines, parse_line)
+}
+
+pub fn stream_input<'a>(
+    reader: impl BufRead + 'a,
+) -> impl Iterator<Item = anyhow::Result<PasswordEntry>> + 'a {
+    puzzle_input::stream_lines(reader, parse_line)
 }
 
-pub fn parse_line(line: &str) -> Result<PasswordEntry, Box<dyn Error>> {
+pub fn parse_line(line: &str) -> Result<PasswordEntry, ParseError> {
     let result = REGEX
         .captures(line)
-        .ok_or(format!("Can't parse line: {}", line))?;
+        .ok_or_else(|| ParseError::new(line, "a policy and password, like `1-3 a: abcde`"))?;
+    let position = |i| -> Result<usize, ParseError> {
+        let part = result.get(i).unwrap().as_str();
+        match parse_error::parse_at(line, part, "a position")? {
+            0 => Err(ParseError::at(line, part, "a position of at least 1")),
+            n => Ok(n),
+        }
+    };
 
     Ok(PasswordEntry {
-        min: usize::from_str(&result[1]).unwrap(),
-        max: usize::from_str(&result[2]).unwrap(),
-        validate_char: char::from_str(&result[3]).unwrap(),
+        min: position(1)?,
+        max: position(2)?,
+        validate_char: result[3].chars().next().unwrap(),
         password: result[4].to_string(),
     })
 }
 
+impl Display for PasswordEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{} {}: {}",
+            self.min, self.max, self.validate_char, self.password
+        )
+    }
+}
+
 pub fn count_valid_passwords(input: &[PasswordEntry]) -> usize {
     input.iter().filter(|x| password_is_valid(x)).count()
 }
@@ -63,6 +89,50 @@ pub fn count_valid_passwords_mk_2(
     input.iter().filter(|x| validator(x)).count()
 }
 
+/// Like [`count_valid_passwords_mk_2`], a line at a time
+pub fn stream_valid_passwords(
+    mut entries: impl Iterator<Item = anyhow::Result<PasswordEntry>>,
+    validator: fn(&PasswordEntry) -> bool,
+) -> anyhow::Result<usize> {
+    entries.try_fold(
+        0,
+        |count, entry| Ok(count + usize::from(validator(&entry?))),
+    )
+}
+
+pub struct Day02;
+
+impl Puzzle for Day02 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Password Philosophy";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<PasswordEntry>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_lines(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(count_valid_passwords(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(count_valid_passwords_mk_2(input, password_is_valid_mk_2))
+    }
+}
+
+impl Stream for Day02 {
+    fn stream_part_one(open: &mut Open<'_>) -> anyhow::Result<usize> {
+        stream_valid_passwords(stream_input(open()?), password_is_valid)
+    }
+
+    fn stream_part_two(open: &mut Open<'_>) -> anyhow::Result<usize> {
+        stream_valid_passwords(stream_input(open()?), password_is_valid_mk_2)
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -80,6 +150,13 @@ mod part_one {
         )
     }
 
+    #[test]
+    fn round_trip() {
+        for line in &["1-3 a: abcde", "1-3 b: cdefg", "2-9 c: ccccccccc"] {
+            assert_eq!(puzzle_input::assert_round_trip(line, parse_line), *line);
+        }
+    }
+
     #[test]
     fn test_password_is_valid() {
         assert_eq!(
@@ -94,8 +171,51 @@ mod part_one {
         assert_eq!(count_valid_passwords(&input), 2);
     }
 
+    #[test]
+    fn streams() {
+        let input = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+        assert_eq!(
+            stream_valid_passwords(stream_input(input.as_bytes()), password_is_valid).unwrap(),
+            2
+        );
+        let err = stream_valid_passwords(stream_input("1-3 a\n".as_bytes()), password_is_valid);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn validator_matches_count() {
+        use shared::differential::{self, Differential};
+
+        let letters = || differential::one_of(vec!['a', 'b', 'c']);
+        let entry = (
+            differential::range(1..4),
+            differential::range(0..4),
+            letters(),
+            differential::vec_of(letters(), 0..8),
+        );
+        let to_entries = |x: &Vec<(i64, i64, char, Vec<char>)>| -> Vec<PasswordEntry> {
+            x.iter()
+                .map(|(min, extra, validate_char, password)| PasswordEntry {
+                    min: *min as usize,
+                    max: (min + extra) as usize,
+                    validate_char: *validate_char,
+                    password: password.iter().collect(),
+                })
+                .collect()
+        };
+        Differential::new(differential::vec_of(entry, 0..5))
+            .implementation("count_valid_passwords", |x| {
+                count_valid_passwords(&to_entries(x))
+            })
+            .implementation("validator", |x| {
+                count_valid_passwords_mk_2(&to_entries(x), password_is_valid)
+            })
+            .assert_agree();
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(2);
         assert_eq!(count_valid_passwords(PUZZLE_INPUT_PARSED.as_ref()), 465);
     }
 }
@@ -129,6 +249,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(2);
         assert_eq!(
             count_valid_passwords_mk_2(PUZZLE_INPUT_PARSED.as_ref(), password_is_valid_mk_2),
             294
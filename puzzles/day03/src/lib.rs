@@ -1,51 +1,61 @@
 // Day 3: Toboggan Trajectory
 
-use std::vec;
+use std::{fmt::Display, vec};
 
-use shared::prelude::*;
+use shared::{
+    grid::Point,
+    prelude::*,
+    render::{Frames, Palette, Render, Rgb},
+};
 
-pub struct TreeMap {
-    rows: Vec<Vec<bool>>,
-    row_len: usize,
-}
+#[derive(Debug, PartialEq, Eq)]
+pub struct TreeMap(Grid<bool>);
 
 pub type Slope = (usize, usize);
 
 lazy_static! {
-    static ref PUZZLE_INPUT: TreeMap =
-        parse_input(&puzzle_input::lines(include_str!("puzzle_input.txt")));
+    static ref RAW_INPUT: String = puzzle_input::load(3).unwrap();
+    static ref PUZZLE_INPUT: TreeMap = parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
     static ref TEST_INPUT: TreeMap =
-        parse_input(&puzzle_input::lines(include_str!("test_input.txt")));
-    static ref SLOPES: Vec<Slope> = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+        parse_input(&puzzle_input::lines(include_str!("test_input.txt"))).unwrap();
+    pub static ref SLOPES: Vec<Slope> = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
 }
 
-pub fn parse_input(input: &[&str]) -> TreeMap {
-    let rows: Vec<_> = input
-        .iter()
-        .map(|y| y.chars().map(|x| x == '#').collect::<Vec<_>>())
-        .collect();
-    let row_len = rows[0].len();
-    TreeMap { rows, row_len }
+pub fn parse_input(input: &[&str]) -> Result<TreeMap, ParseError> {
+    let trees = puzzle_input::char_grid(input, "`.` or `#`", |x| match x {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+    Ok(TreeMap(trees))
 }
 
 impl TreeMap {
     pub fn is_tree(&self, x: usize, y: usize) -> bool {
-        let wrapped_x = x % self.row_len;
-        self.rows[y][wrapped_x]
+        self.0.get_wrapping((x, y)).copied().unwrap_or(false)
     }
 
-    pub fn collisions_along_slope(&self, (right, down): Slope) -> usize {
-        (0..self.rows.len())
+    /// Where the toboggan stops on each row it reaches, wrapped onto the map
+    pub fn path(&self, (right, down): Slope) -> impl Iterator<Item = Point> + '_ {
+        (0..self.0.height())
             .step_by(down)
             .enumerate()
-            .filter(|(i, y)| {
-                let x = i * right;
-                self.is_tree(x, *y)
-            })
+            .map(move |(i, y)| ((i * right) % self.0.width(), y))
+    }
+
+    pub fn collisions_along_slope(&self, slope: Slope) -> usize {
+        self.path(slope)
+            .filter(|&(x, y)| self.is_tree(x, y))
             .count()
     }
 }
 
+impl Display for TreeMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display_with(|&x| if x { '#' } else { '.' }))
+    }
+}
+
 pub fn collisions_multiplied_along_slopes(tree_map: &TreeMap, slopes: &[Slope]) -> usize {
     let collisions = slopes
         .iter()
@@ -60,10 +70,77 @@ pub fn collisions_multiplied_along_slopes(tree_map: &TreeMap, slopes: &[Slope])
         .unwrap_or(0)
 }
 
+pub struct Day03;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Square {
+    Open,
+    Tree,
+    Passed,
+    Hit,
+}
+
+impl Render for Day03 {
+    /// The path down the map, with trees that get hit in red. Part two has a
+    /// frame for each slope.
+    fn render(input: &TreeMap, part: Part, frames: &mut dyn Frames) -> anyhow::Result<()> {
+        let palette = Palette::new(Rgb::WHITE)
+            .with(Square::Tree, Rgb::hex(0x2e7d32))
+            .with(Square::Passed, Rgb::hex(0x90caf9))
+            .with(Square::Hit, Rgb::hex(0xd32f2f));
+        let slopes = match part {
+            Part::One => vec![(3, 1)],
+            Part::Two => SLOPES.clone(),
+        };
+
+        for slope in slopes {
+            let mut squares = input
+                .0
+                .map(|_, &tree| if tree { Square::Tree } else { Square::Open });
+            for point in input.path(slope) {
+                squares[point] = match squares[point] {
+                    Square::Tree | Square::Hit => Square::Hit,
+                    _ => Square::Passed,
+                };
+            }
+            frames.push(palette.paint(&squares))?;
+        }
+        Ok(())
+    }
+}
+
+impl Puzzle for Day03 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Toboggan Trajectory";
+    const VERSION: &'static str = "1";
+    type Input<'a> = TreeMap;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_input(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(input.collisions_along_slope((3, 1)))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(collisions_multiplied_along_slopes(input, &SLOPES))
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
 
+    #[test]
+    fn round_trip() {
+        let input = include_str!("test_input.txt").trim();
+        let parse = |x: &str| parse_input(&puzzle_input::lines(x));
+        assert_eq!(puzzle_input::assert_round_trip(input, parse), input);
+    }
+
     #[test]
     fn test_is_tree() {
         assert!(TEST_INPUT.is_tree(3, 1) == false);
@@ -75,8 +152,20 @@ mod part_one {
         assert_eq!(TEST_INPUT.collisions_along_slope((3, 1)), 7);
     }
 
+    #[test]
+    fn render() {
+        let mut frames = Vec::new();
+        Day03::render(&TEST_INPUT, Part::One, &mut frames).unwrap();
+        let hits = frames[0]
+            .cells()
+            .filter(|&&x| x == Rgb::hex(0xd32f2f))
+            .count();
+        assert_eq!(hits, 7);
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(3);
         assert_eq!(PUZZLE_INPUT.collisions_along_slope((3, 1)), 230);
     }
 }
@@ -99,6 +188,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(3);
         assert_eq!(
             collisions_multiplied_along_slopes(&PUZZLE_INPUT, &SLOPES),
             9533698720
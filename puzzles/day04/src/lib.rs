@@ -7,14 +7,15 @@ use std::collections::HashMap;
 type PassportRecord = HashMap<String, String>;
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(4).unwrap();
     static ref PUZZLE_INPUT: Vec<PassportRecord> =
-        parse_input(&(&puzzle_input::lines(include_str!("puzzle_input.txt"))));
+        parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
     static ref PART_1_TEST_INPUT: Vec<PassportRecord> =
-        parse_input(&&puzzle_input::lines(include_str!("part_1_test_input.txt")));
+        parse_input(&puzzle_input::lines(include_str!("part_1_test_input.txt"))).unwrap();
     static ref PART_2_INVALID_TEST_INPUT: Vec<PassportRecord> =
-        parse_input(&&puzzle_input::lines(include_str!("part_2_invalid.txt")));
+        parse_input(&puzzle_input::lines(include_str!("part_2_invalid.txt"))).unwrap();
     static ref PART_2_VALID_TEST_INPUT: Vec<PassportRecord> =
-        parse_input(&&puzzle_input::lines(include_str!("part_2_valid.txt")));
+        parse_input(&puzzle_input::lines(include_str!("part_2_valid.txt"))).unwrap();
     static ref REQUIRED_ENTRIES: HashMap<&'static str, fn(&str) -> bool> = {
         let mut x = HashMap::<&str, fn(&str) -> bool>::new();
         x.insert("byr", is_valid_byr);
@@ -31,22 +32,37 @@ lazy_static! {
     static ref PID_REGEX: Regex = Regex::new(r"^[0-9]{9}$").unwrap();
 }
 
-pub fn parse_input(input_lines: &[&str]) -> Vec<PassportRecord> {
-    input_lines
-        .split(|x| x.trim().is_empty())
-        .map(|definition_lines| {
-            definition_lines
-                .join(" ")
-                .split(" ")
-                .map(|field| {
-                    let pair: Vec<_> = field.split(":").collect();
-                    (pair[0].to_string(), pair[1].to_string())
-                })
-                .collect()
+pub fn parse_input(input_lines: &[&str]) -> Result<Vec<PassportRecord>, ParseError> {
+    puzzle_input::sections(input_lines)
+        .iter()
+        .map(|section| {
+            Ok(section
+                .key_values()?
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect())
         })
         .collect()
 }
 
+/// Back to the batch file format, with each passport's fields in key order
+/// since a record doesn't remember how they were laid out
+pub fn write_passports(passports: &[PassportRecord]) -> String {
+    passports
+        .iter()
+        .map(|passport| {
+            let mut fields: Vec<_> = passport.iter().collect();
+            fields.sort();
+            fields
+                .iter()
+                .map(|(key, value)| format!("{}:{}", key, value))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 pub fn is_valid(passport: &PassportRecord) -> bool {
     REQUIRED_ENTRIES
         .keys()
@@ -101,6 +117,29 @@ pub fn is_valid_mk_2(passport: &PassportRecord) -> bool {
     })
 }
 
+pub struct Day04;
+
+impl Puzzle for Day04 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Passport Processing";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<PassportRecord>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_input(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(valid_passports(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(input.iter().filter(|x| is_valid_mk_2(x)).count())
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -112,8 +151,17 @@ mod part_one {
         assert_eq!(valid_passports(&PART_1_TEST_INPUT), 2);
     }
 
+    #[test]
+    fn round_trip() {
+        let written = write_passports(&PART_1_TEST_INPUT);
+        assert!(written.starts_with("byr:1937 cid:147 ecl:gry eyr:2020 hcl:#fffffd"));
+        let reparsed = parse_input(&puzzle_input::lines(&written)).unwrap();
+        assert_eq!(reparsed, *PART_1_TEST_INPUT);
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(4);
         assert_eq!(valid_passports(&PUZZLE_INPUT), 202);
     }
 }
@@ -154,6 +202,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(4);
         assert_eq!(
             PUZZLE_INPUT.iter().filter(|x| is_valid_mk_2(x)).count(),
             137
@@ -1,21 +1,24 @@
 // Day 5: Binary Boarding
 
-use core::fmt::Debug;
-use shared::prelude::*;
+use core::fmt::{Debug, Display};
+use shared::{
+    prelude::*,
+    puzzle_input::{Open, Stream},
+};
 use std::error::Error;
+use std::io::BufRead;
 use std::str::FromStr;
 
+#[derive(PartialEq, Eq)]
 pub struct BoardingPassSeat {
     row: u8,
     column: u8,
 }
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(5).unwrap();
     static ref PUZZLE_INPUT: Vec<BoardingPassSeat> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"))
-            .into_iter()
-            .map(BoardingPassSeat::from_str)
-            .collect::<Result<_, _>>()
+        parse_error::parse_lines(&puzzle_input::lines(&RAW_INPUT), BoardingPassSeat::from_str)
             .unwrap();
 }
 
@@ -36,35 +39,67 @@ impl Debug for BoardingPassSeat {
     }
 }
 
+impl Display for BoardingPassSeat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Each letter picks the lower or upper half, starting from the top bit
+        let half = |value: u8, bits: u32, lower: char, upper: char| -> String {
+            (0..bits)
+                .rev()
+                .map(|bit| if value >> bit & 1 == 1 { upper } else { lower })
+                .collect()
+        };
+        write!(
+            f,
+            "{}{}",
+            half(self.row, 7, 'F', 'B'),
+            half(self.column, 3, 'L', 'R')
+        )
+    }
+}
+
 impl FromStr for BoardingPassSeat {
-    type Err = Box<dyn Error>;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        if input.chars().count() != 10 {
+            return Err(ParseError::new(input, "10 characters, like `FBFBBFFRLR`"));
+        }
+
         let row = input
-            .chars()
+            .char_indices()
             .take(7)
             .try_fold(
                 (0 as u16, 127 as u16),
-                |(lower_bound, upper_bound), next| {
+                |(lower_bound, upper_bound), (i, next)| {
                     let midpoint = lower_bound + (upper_bound - lower_bound) / 2;
                     match next {
                         'F' => Ok((lower_bound, midpoint)),
                         'B' => Ok((midpoint + 1, upper_bound)),
-                        _ => Err(format!("Unexpected char: {}", next)),
+                        _ => Err(ParseError::at_column(
+                            input,
+                            i,
+                            next.len_utf8(),
+                            "`F` or `B`",
+                        )),
                     }
                 },
             )?
             .0 as u8;
 
         let column = input
-            .chars()
+            .char_indices()
             .skip(7)
-            .try_fold((0, 7), |(lower_bound, upper_bound), next| {
+            .try_fold((0, 7), |(lower_bound, upper_bound), (i, next)| {
                 let midpoint = lower_bound + (upper_bound - lower_bound) / 2;
                 match next {
                     'L' => Ok((lower_bound, midpoint)),
                     'R' => Ok((midpoint + 1, upper_bound)),
-                    _ => Err(format!("Unexpected char: {}", next)),
+                    _ => Err(ParseError::at_column(
+                        input,
+                        i,
+                        next.len_utf8(),
+                        "`L` or `R`",
+                    )),
                 }
             })?
             .0;
@@ -86,15 +121,84 @@ pub fn find_missing_seat(seats: &[BoardingPassSeat]) -> Result<u32, Box<dyn Erro
         })
         // locate the ID that breaks the pattern of prev_id = id - 1 and is actually 2 away, implying one
         // was skipped in the sequence
-        .find(|(prev_id, id)| {
-            println!("🥐 {:?}, {}", prev_id, id);
-            prev_id.map_or(false, |prev_id| *id == prev_id + 2)
-        })
+        .find(|(prev_id, id)| prev_id.is_some_and(|prev_id| *id == prev_id + 2))
         .map_or(Err("Couldn't find a missing ID".into()), |(_, id)| {
             Ok(id - 1)
         })
 }
 
+pub fn stream_input<'a>(
+    reader: impl BufRead + 'a,
+) -> impl Iterator<Item = anyhow::Result<BoardingPassSeat>> + 'a {
+    puzzle_input::stream_lines(reader, BoardingPassSeat::from_str)
+}
+
+/// Every seat ID there's room for: 128 rows of 8
+const SEAT_IDS: usize = 128 * 8;
+
+/// Which seat IDs are taken, read a line at a time
+pub fn stream_taken_seats(
+    passes: impl Iterator<Item = anyhow::Result<BoardingPassSeat>>,
+) -> anyhow::Result<Vec<bool>> {
+    let mut taken = vec![false; SEAT_IDS];
+    for pass in passes {
+        taken[pass?.seat_id() as usize] = true;
+    }
+    Ok(taken)
+}
+
+/// Like [`find_missing_seat`]: the first free seat with both neighbors taken
+pub fn find_missing_seat_in(taken: &[bool]) -> Option<u32> {
+    (1..taken.len().saturating_sub(1))
+        .find(|&id| !taken[id] && taken[id - 1] && taken[id + 1])
+        .map(|id| id as u32)
+}
+
+pub struct Day05;
+
+impl Puzzle for Day05 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Binary Boarding";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<BoardingPassSeat>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_error::parse_lines(
+            &puzzle_input::lines(input),
+            BoardingPassSeat::from_str,
+        )?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<u32> {
+        input
+            .iter()
+            .map(BoardingPassSeat::seat_id)
+            .max()
+            .ok_or(anyhow!("No boarding passes"))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<u32> {
+        find_missing_seat(input).map_err(|err| anyhow!("{}", err))
+    }
+}
+
+impl Stream for Day05 {
+    fn stream_part_one(open: &mut Open<'_>) -> anyhow::Result<u32> {
+        stream_taken_seats(stream_input(open()?))?
+            .iter()
+            .rposition(|&x| x)
+            .map(|x| x as u32)
+            .ok_or(anyhow!("No boarding passes"))
+    }
+
+    fn stream_part_two(open: &mut Open<'_>) -> anyhow::Result<u32> {
+        find_missing_seat_in(&stream_taken_seats(stream_input(open()?))?)
+            .ok_or(anyhow!("Couldn't find a missing ID"))
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -107,6 +211,16 @@ mod part_one {
         assert_eq!(pass.seat_id(), 357);
     }
 
+    #[test]
+    fn round_trip() {
+        for line in &["FBFBBFFRLR", "BFFFBBFRRR", "FFFBBBFRRR", "BBFFBBFRLL"] {
+            assert_eq!(
+                puzzle_input::assert_round_trip(line, BoardingPassSeat::from_str),
+                *line
+            );
+        }
+    }
+
     #[test]
     fn test_cases() {
         let pass = BoardingPassSeat::from_str("BFFFBBFRRR").unwrap();
@@ -127,6 +241,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(5);
         assert_eq!(
             PUZZLE_INPUT
                 .iter()
@@ -142,8 +257,31 @@ mod part_one {
 mod part_two {
     use super::*;
 
+    #[test]
+    fn streaming_matches_sorting() {
+        use shared::differential::{self, Differential};
+
+        let seats = |ids: &Vec<i64>| -> Vec<BoardingPassSeat> {
+            ids.iter()
+                .map(|&id| BoardingPassSeat {
+                    row: (id / 8) as u8,
+                    column: (id % 8) as u8,
+                })
+                .collect()
+        };
+        // A few seats clustered together, so there are gaps to find
+        Differential::new(differential::vec_of(differential::range(500..520), 0..12))
+            .implementation("sorting", |x| find_missing_seat(&seats(x)).ok())
+            .implementation("streaming", |x| {
+                let taken = stream_taken_seats(seats(x).into_iter().map(Ok)).unwrap();
+                find_missing_seat_in(&taken)
+            })
+            .assert_agree();
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(5);
         let result = find_missing_seat(&PUZZLE_INPUT).unwrap();
         assert!(result > 383); // found a wrong answer
         assert_eq!(result, 657);
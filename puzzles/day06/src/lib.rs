@@ -5,16 +5,29 @@ use std::collections::{HashMap, HashSet};
 use shared::prelude::*;
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(6).unwrap();
     static ref PUZZLE_INPUT: Vec<Vec<&'static str>> =
-        parse_groups(&puzzle_input::lines(include_str!("puzzle_input.txt")));
+        parse_groups(&puzzle_input::lines(&RAW_INPUT)).unwrap();
     static ref TEST_INPUT: Vec<Vec<&'static str>> =
-        parse_groups(&puzzle_input::lines(include_str!("test_input.txt")));
+        parse_groups(&puzzle_input::lines(include_str!("test_input.txt"))).unwrap();
 }
 
-pub fn parse_groups<'a>(inputs: &[&'a str]) -> Vec<Vec<&'a str>> {
-    inputs
-        .split(|x| x.trim().is_empty())
-        .map(|x| x.to_vec())
+pub fn parse_groups<'a>(inputs: &[&'a str]) -> Result<Vec<Vec<&'a str>>, ParseError> {
+    puzzle_input::sections(inputs)
+        .iter()
+        .map(|group| {
+            group.parse_lines(|line| {
+                match line.char_indices().find(|(_, x)| !x.is_ascii_lowercase()) {
+                    Some((i, x)) => Err(ParseError::at_column(
+                        line,
+                        i,
+                        x.len_utf8(),
+                        "a question from a to z",
+                    )),
+                    None => Ok(line),
+                }
+            })
+        })
         .collect()
 }
 
@@ -52,6 +65,29 @@ fn unanimous_answers(group: &[&str]) -> usize {
         .count()
 }
 
+pub struct Day06;
+
+impl Puzzle for Day06 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Custom Customs";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<Vec<&'a str>>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_groups(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(unique_answers_per_group(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(unanimous_answers_per_group(input))
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -68,6 +104,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(6);
         assert_eq!(unique_answers_per_group(PUZZLE_INPUT.as_ref()), 6748)
     }
 }
@@ -81,6 +118,7 @@ mod part_two {
     }
     #[test]
     fn answer() {
+        shared::skip_without_input!(6);
         assert_eq!(unanimous_answers_per_group(PUZZLE_INPUT.as_ref()), 3445)
     }
 }
@@ -1,10 +1,13 @@
 // Day 7: Handy Haversacks
 
-use anyhow::anyhow;
 use regex::Regex;
-use shared::prelude::*;
+use shared::{
+    prelude::*,
+    trace::{self, Event, Explain, NoopTracer, Tracer},
+};
 use std::collections::{HashMap, HashSet};
 use std::convert::From;
+use std::fmt::Display;
 use std::hash::Hash;
 use std::str::FromStr;
 
@@ -23,21 +26,19 @@ pub struct BagRuleGraph {
 }
 
 lazy_static! {
-    static ref TEST_INPUT: Vec<BagRule> = puzzle_input::lines(include_str!("test_input.txt"))
-        .into_iter()
-        .map(BagRule::from_str)
-        .collect::<anyhow::Result<Vec<BagRule>>>()
-        .unwrap();
-    static ref TEST_INPUT_2: Vec<BagRule> = puzzle_input::lines(include_str!("test_input_2.txt"))
-        .into_iter()
-        .map(BagRule::from_str)
-        .collect::<anyhow::Result<Vec<BagRule>>>()
-        .unwrap();
-    static ref PUZZLE_INPUT: Vec<BagRule> = puzzle_input::lines(include_str!("puzzle_input.txt"))
-        .into_iter()
-        .map(BagRule::from_str)
-        .collect::<anyhow::Result<Vec<BagRule>>>()
-        .unwrap();
+    static ref RAW_INPUT: String = puzzle_input::load(7).unwrap();
+    static ref TEST_INPUT: Vec<BagRule> = parse_error::parse_lines(
+        &puzzle_input::lines(include_str!("test_input.txt")),
+        BagRule::from_str
+    )
+    .unwrap();
+    static ref TEST_INPUT_2: Vec<BagRule> = parse_error::parse_lines(
+        &puzzle_input::lines(include_str!("test_input_2.txt")),
+        BagRule::from_str
+    )
+    .unwrap();
+    static ref PUZZLE_INPUT: Vec<BagRule> =
+        parse_error::parse_lines(&puzzle_input::lines(&RAW_INPUT), BagRule::from_str).unwrap();
 }
 
 lazy_static! {
@@ -45,18 +46,21 @@ lazy_static! {
     static ref BAG_COLLECTION_REGEX: Regex = Regex::new(r"^([0-9]+) ([a-z ]+?) bags?$").unwrap();
 }
 impl FromStr for BagRule {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let matches = match BAG_REGEX.captures(input) {
-            Some(matches) => Ok(matches),
-            None => Err(anyhow!("Didn't match regex: {}", input)),
-        }?;
+        let matches = BAG_REGEX
+            .captures(input)
+            .ok_or_else(|| ParseError::new(input, "`<color> bags contain <contents>.`"))?;
 
-        let allowed: Result<HashSet<BagCollection>, anyhow::Error> = match &matches[2] {
-            "no other bags" => Ok(HashSet::new()),
-            list => list.split(", ").map(BagCollection::from_str).collect(),
-        };
+        let allowed: Result<HashSet<BagCollection>, ParseError> =
+            match matches.get(2).unwrap().as_str() {
+                "no other bags" => Ok(HashSet::new()),
+                list => list
+                    .split(", ")
+                    .map(|x| BagCollection::from_str(x).map_err(|err| err.within(input, x)))
+                    .collect(),
+            };
 
         Ok(BagRule {
             color: matches[1].to_string(),
@@ -66,15 +70,41 @@ impl FromStr for BagRule {
 }
 
 impl FromStr for BagCollection {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let matches = match BAG_COLLECTION_REGEX.captures(input) {
-            Some(matches) => Ok(matches),
-            None => Err(anyhow!("Didn't match regex: {}", input)),
-        }?;
+        let matches = BAG_COLLECTION_REGEX
+            .captures(input)
+            .ok_or_else(|| ParseError::new(input, "`<count> <color> bag(s)`"))?;
+        let count = matches.get(1).unwrap().as_str();
+
+        Ok(BagCollection(
+            parse_error::parse_at(input, count, "a number of bags")?,
+            matches[2].into(),
+        ))
+    }
+}
+
+impl Display for BagRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} bags contain ", self.color)?;
+        if self.contains.is_empty() {
+            return write!(f, "no other bags.");
+        }
 
-        Ok(BagCollection(matches[1].parse()?, matches[2].into()))
+        // Sets have no order, so sort by color to print the same way every time
+        let mut contains: Vec<_> = self.contains.iter().collect();
+        contains.sort_by(|a, b| a.1.cmp(&b.1));
+        let contains: Vec<_> = contains.into_iter().map(|x| x.to_string()).collect();
+        write!(f, "{}.", contains.join(", "))
+    }
+}
+
+impl Display for BagCollection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let BagCollection(count, color) = self;
+        let plural = if *count == 1 { "" } else { "s" };
+        write!(f, "{} {} bag{}", count, color, plural)
     }
 }
 
@@ -113,20 +143,45 @@ pub fn get_possible_outer_bags<'a>(
     inner_bag_color: &'_ str,
     bag_rules: &'a BagRuleGraph,
     cache: &mut HashMap<String, HashSet<&'a str>>,
+) -> HashSet<&'a str> {
+    get_possible_outer_bags_traced(inner_bag_color, bag_rules, cache, &mut NoopTracer)
+}
+
+/// Like [`get_possible_outer_bags`], telling `tracer` about each parent it
+/// walks up to
+pub fn get_possible_outer_bags_traced<'a, T: Tracer + ?Sized>(
+    inner_bag_color: &'_ str,
+    bag_rules: &'a BagRuleGraph,
+    cache: &mut HashMap<String, HashSet<&'a str>>,
+    tracer: &mut T,
 ) -> HashSet<&'a str> {
     match cache.get(inner_bag_color) {
         Some(result) => result.to_owned(),
         None => {
             let result: HashSet<&str> = match bag_rules.parents.get(inner_bag_color) {
-                Some(possible_parents) => possible_parents
-                    .iter()
-                    .flat_map(|(parent_color, _)| -> Vec<&str> {
-                        get_possible_outer_bags(parent_color, bag_rules, cache)
+                Some(possible_parents) => {
+                    let traced = tracer.enabled();
+                    let mut walk = |(parent_color, count): (&'a String, &usize)| -> Vec<&str> {
+                        trace::emit(tracer, || {
+                            Event::Note(format!(
+                                "{} bags can hold {} {} bag(s)",
+                                parent_color, count, inner_bag_color
+                            ))
+                        });
+                        get_possible_outer_bags_traced(parent_color, bag_rules, cache, tracer)
                             .into_iter()
                             .chain(std::iter::once(parent_color.as_str()))
                             .collect()
-                    })
-                    .collect::<HashSet<&str>>(),
+                    };
+                    if traced {
+                        // Sorted so the trace comes out the same every time
+                        let mut possible_parents: Vec<_> = possible_parents.iter().collect();
+                        possible_parents.sort_unstable();
+                        possible_parents.into_iter().flat_map(&mut walk).collect()
+                    } else {
+                        possible_parents.iter().flat_map(&mut walk).collect()
+                    }
+                }
                 None => HashSet::new(),
             };
             cache.insert(inner_bag_color.to_owned(), result.to_owned());
@@ -159,6 +214,58 @@ pub fn get_total_contained_bags(
     }
 }
 
+pub struct Day07;
+
+impl Puzzle for Day07 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Handy Haversacks";
+    const VERSION: &'static str = "1";
+    type Input<'a> = BagRuleGraph;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        let rules = parse_error::parse_lines(&puzzle_input::lines(input), BagRule::from_str)?;
+        Ok(BagRuleGraph::from(rules.as_slice()))
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(get_possible_outer_bags("shiny gold", input, &mut HashMap::new()).len())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(get_total_contained_bags(
+            "shiny gold",
+            input,
+            &mut HashMap::new(),
+        ))
+    }
+}
+
+impl Explain for Day07 {
+    fn explain(
+        input: &BagRuleGraph,
+        part: Part,
+        tracer: &mut dyn Tracer,
+    ) -> anyhow::Result<String> {
+        match part {
+            Part::One => {
+                let bags = get_possible_outer_bags_traced(
+                    "shiny gold",
+                    input,
+                    &mut HashMap::new(),
+                    tracer,
+                );
+                trace::emit(tracer, || {
+                    Event::resolved("bags that can hold shiny gold", bags.len())
+                });
+                Ok(bags.len().to_string())
+            }
+            Part::Two => Self::part_two(input).map(|x| x.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -188,6 +295,20 @@ mod part_one {
         );
     }
 
+    #[test]
+    fn round_trip() {
+        // Contents are printed sorted by color, so only compare the parsed rules
+        let input = include_str!("test_input.txt");
+        for line in puzzle_input::lines(input) {
+            puzzle_input::assert_round_trip(line, BagRule::from_str);
+        }
+        let line = "light red bags contain 1 bright white bag, 2 muted yellow bags.";
+        assert_eq!(
+            puzzle_input::assert_round_trip(line, BagRule::from_str),
+            line
+        );
+    }
+
     #[test]
     fn test_cases() {
         let rules = BagRuleGraph::from(TEST_INPUT.as_ref());
@@ -196,8 +317,23 @@ mod part_one {
         assert_eq!(result.len(), 4);
     }
 
+    #[test]
+    fn traces_parents() {
+        let rules = BagRuleGraph::from(TEST_INPUT.as_ref());
+        let mut events = Vec::new();
+        let result =
+            get_possible_outer_bags_traced("shiny gold", &rules, &mut HashMap::new(), &mut events);
+        assert_eq!(result.len(), 4);
+        assert_eq!(events.len(), 6);
+        assert_eq!(
+            events[0],
+            Event::Note("bright white bags can hold 1 shiny gold bag(s)".to_string())
+        );
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(7);
         let rules = BagRuleGraph::from(PUZZLE_INPUT.as_ref());
         let result = get_possible_outer_bags("shiny gold", &rules, &mut HashMap::new());
 
@@ -230,6 +366,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(7);
         let result = get_total_contained_bags(
             "shiny gold",
             &BagRuleGraph::from(PUZZLE_INPUT.as_ref()),
@@ -1,6 +1,6 @@
 // Day 8: Handheld Halting
 
-use std::{collections::HashSet, str::FromStr};
+use std::{collections::HashSet, fmt::Display, str::FromStr};
 
 use shared::prelude::*;
 
@@ -18,34 +18,30 @@ pub struct Instruction {
 }
 
 lazy_static! {
-    static ref TEST_INPUT: Vec<Instruction> = puzzle_input::lines(include_str!("test_input.txt"))
-        .into_iter()
-        .map(Instruction::from_str)
-        .collect::<anyhow::Result<Vec<_>>>()
-        .unwrap();
+    static ref RAW_INPUT: String = puzzle_input::load(8).unwrap();
+    static ref TEST_INPUT: Vec<Instruction> = parse_error::parse_lines(
+        &puzzle_input::lines(include_str!("test_input.txt")),
+        Instruction::from_str
+    )
+    .unwrap();
     static ref PUZZLE_INPUT: Vec<Instruction> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"))
-            .into_iter()
-            .map(Instruction::from_str)
-            .collect::<anyhow::Result<Vec<_>>>()
-            .unwrap();
+        parse_error::parse_lines(&puzzle_input::lines(&RAW_INPUT), Instruction::from_str).unwrap();
 }
 
 impl FromStr for Instruction {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let split: Vec<_> = s.split(" ").collect();
-        let operation = match split.get(0) {
-            Some(&"nop") => Ok(OperationCode::Nop),
-            Some(&"acc") => Ok(OperationCode::Acc),
-            Some(&"jmp") => Ok(OperationCode::Jmp),
-            Some(x) => Err(anyhow!("Unrecognized operation code: {}", x)),
-            None => Err(anyhow!("Missing operation code")),
+        let operation = match split[0] {
+            "nop" => Ok(OperationCode::Nop),
+            "acc" => Ok(OperationCode::Acc),
+            "jmp" => Ok(OperationCode::Jmp),
+            x => Err(ParseError::at(s, x, "`nop`, `acc` or `jmp`")),
         }?;
         let argument = match split.get(1) {
-            Some(num) => Ok(num.parse()?),
-            None => Err(anyhow!("Missing argument")),
+            Some(num) => parse_error::parse_at(s, num, "a signed number"),
+            None => Err(ParseError::at_end(s, "an argument")),
         }?;
 
         Ok(Instruction {
@@ -55,6 +51,17 @@ impl FromStr for Instruction {
     }
 }
 
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operation = match self.operation {
+            OperationCode::Nop => "nop",
+            OperationCode::Acc => "acc",
+            OperationCode::Jmp => "jmp",
+        };
+        write!(f, "{} {:+}", operation, self.argument)
+    }
+}
+
 #[derive(Debug)]
 pub enum ExecutionResult {
     Loop(i32),
@@ -154,6 +161,32 @@ pub fn fix_program(instructions: &[Instruction]) -> anyhow::Result<i32> {
     }
 }
 
+pub struct Day08;
+
+impl Puzzle for Day08 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Handheld Halting";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<Instruction>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_error::parse_lines(
+            &puzzle_input::lines(input),
+            Instruction::from_str,
+        )?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<i32> {
+        get_accumulator_before_loop(input)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<i32> {
+        fix_program(input)
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -183,6 +216,16 @@ mod part_one {
         );
     }
 
+    #[test]
+    fn round_trip() {
+        for line in puzzle_input::lines(include_str!("test_input.txt")) {
+            assert_eq!(
+                puzzle_input::assert_round_trip(line, Instruction::from_str),
+                line
+            );
+        }
+    }
+
     #[test]
     fn test_case() {
         assert_eq!(get_accumulator_before_loop(&TEST_INPUT).unwrap(), 5);
@@ -190,6 +233,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(8);
         assert_eq!(get_accumulator_before_loop(&PUZZLE_INPUT).unwrap(), 1930);
     }
 }
@@ -204,6 +248,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(8);
         assert_eq!(fix_program(&PUZZLE_INPUT).unwrap(), 1688);
     }
 }
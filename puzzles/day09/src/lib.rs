@@ -1,8 +1,11 @@
 // Day 9: Encoding Error
 
-use std::str::FromStr;
+use std::{collections::VecDeque, io::BufRead};
 
-use shared::prelude::*;
+use shared::{
+    prelude::*,
+    puzzle_input::{Open, Stream},
+};
 
 #[derive(Debug)]
 pub struct XmasStream<'a> {
@@ -17,16 +20,23 @@ pub enum XmasStreamResult {
 }
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<i64> = puzzle_input::lines(include_str!("puzzle_input.txt"))
-        .into_iter()
-        .map(FromStr::from_str)
-        .collect::<Result<_, _>>()
-        .unwrap();
+    static ref RAW_INPUT: String = puzzle_input::load(9).unwrap();
+    static ref PUZZLE_INPUT: Vec<i64> = parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
     static ref TEST_INPUT: Vec<i64> = vec![
         35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576
     ];
 }
 
+pub fn parse_input(lines: &[&str]) -> Result<Vec<i64>, ParseError> {
+    parse_error::parse_lines(lines, |x| parse_error::parse_at(x, x, "a number"))
+}
+
+pub fn stream_input<'a>(
+    reader: impl BufRead + 'a,
+) -> impl Iterator<Item = anyhow::Result<i64>> + 'a {
+    puzzle_input::stream_lines(reader, |x| parse_error::parse_at(x, x, "a number"))
+}
+
 impl XmasStream<'_> {
     fn result(&self) -> XmasStreamResult {
         let mut possible_matches =
@@ -88,6 +98,96 @@ pub fn find_encryption_weakness(stream: &[i64], preamble: usize) -> Option<i64>
     Some(weak_range.iter().min()? + weak_range.iter().max()?)
 }
 
+/// Like [`find_first_invalid_number`], remembering only the last `preamble`
+/// numbers
+pub fn stream_first_invalid_number(
+    numbers: impl Iterator<Item = anyhow::Result<i64>>,
+    preamble: usize,
+) -> anyhow::Result<Option<i64>> {
+    let mut window = VecDeque::with_capacity(preamble + 1);
+    for number in numbers {
+        let number = number?;
+        if window.len() == preamble {
+            let valid = window
+                .iter()
+                .enumerate()
+                .any(|(i, x)| window.iter().skip(i + 1).any(|y| x + y == number));
+            if !valid {
+                return Ok(Some(number));
+            }
+            window.pop_front();
+        }
+        window.push_back(number);
+    }
+    Ok(None)
+}
+
+/// The smallest plus the largest of the first run of at least two numbers
+/// that adds up to `target`. Only the current run is kept, dropping numbers
+/// from its front while it adds up to too much, which works because every
+/// number is positive.
+pub fn stream_encryption_weakness(
+    numbers: impl Iterator<Item = anyhow::Result<i64>>,
+    target: i64,
+) -> anyhow::Result<Option<i64>> {
+    let mut run = VecDeque::new();
+    let mut sum = 0;
+    for number in numbers {
+        let number = number?;
+        run.push_back(number);
+        sum += number;
+        while sum > target {
+            match run.pop_front() {
+                Some(front) => sum -= front,
+                None => break,
+            }
+        }
+        if sum == target && run.len() >= 2 {
+            let min = run.iter().min().unwrap();
+            let max = run.iter().max().unwrap();
+            return Ok(Some(min + max));
+        }
+    }
+    Ok(None)
+}
+
+pub struct Day09;
+
+impl Puzzle for Day09 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Encoding Error";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<i64>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_input(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        find_first_invalid_number(input, 25).ok_or(anyhow!("Every number in the stream is valid"))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        find_encryption_weakness(input, 25).ok_or(anyhow!("Couldn't find an encryption weakness"))
+    }
+}
+
+/// Part two reads the input twice, once to find the invalid number
+impl Stream for Day09 {
+    fn stream_part_one(open: &mut Open<'_>) -> anyhow::Result<i64> {
+        stream_first_invalid_number(stream_input(open()?), 25)?
+            .ok_or(anyhow!("Every number in the stream is valid"))
+    }
+
+    fn stream_part_two(open: &mut Open<'_>) -> anyhow::Result<i64> {
+        let target = Self::stream_part_one(open)?;
+        stream_encryption_weakness(stream_input(open()?), target)?
+            .ok_or(anyhow!("Couldn't find an encryption weakness"))
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -100,8 +200,15 @@ mod part_one {
         );
     }
 
+    #[test]
+    fn streams() {
+        let numbers = TEST_INPUT.iter().map(|&x| Ok(x));
+        assert_eq!(stream_first_invalid_number(numbers, 5).unwrap(), Some(127));
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(9);
         assert_eq!(
             find_first_invalid_number(PUZZLE_INPUT.as_slice(), 25).unwrap(),
             1212510616
@@ -120,8 +227,18 @@ mod part_two {
         );
     }
 
+    #[test]
+    fn streams() {
+        let numbers = TEST_INPUT.iter().map(|&x| Ok(x));
+        assert_eq!(stream_encryption_weakness(numbers, 127).unwrap(), Some(62));
+        // A number on its own isn't a run
+        let numbers = vec![Ok(5), Ok(127), Ok(100), Ok(27)].into_iter();
+        assert_eq!(stream_encryption_weakness(numbers, 127).unwrap(), Some(127));
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(9);
         assert_eq!(
             find_encryption_weakness(PUZZLE_INPUT.as_slice(), 25).unwrap(),
             171265123
@@ -1,23 +1,35 @@
-// Day 00: Template
+// Day 10: Adapter Array
 
 use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::fmt::{self, Display};
 use std::hash::{Hash, Hasher};
-use std::str::FromStr;
 
-use shared::prelude::*;
+use shared::{
+    extras::{json, Extras, Value},
+    prelude::*,
+    puzzle::Answer,
+};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
 pub struct Differences {
-    one_jolt: usize,
-    three_jolt: usize,
+    pub one_jolt: usize,
+    pub three_jolt: usize,
+}
+
+/// Part one's answer multiplies the two counts
+impl Display for Differences {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.one_jolt * self.three_jolt)
+    }
 }
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<u16> = puzzle_input::lines(include_str!("puzzle_input.txt"))
-        .into_iter()
-        .map(FromStr::from_str)
-        .collect::<Result<_, _>>()
-        .unwrap();
+    static ref RAW_INPUT: String = puzzle_input::load(10).unwrap();
+    static ref PUZZLE_INPUT: Vec<u16> = parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
+}
+
+pub fn parse_input(lines: &[&str]) -> Result<Vec<u16>, ParseError> {
+    parse_error::parse_lines(lines, |x| parse_error::parse_at(x, x, "a joltage rating"))
 }
 
 pub fn get_differences(adapters: &[u16]) -> anyhow::Result<Differences> {
@@ -99,6 +111,45 @@ pub fn get_valid_combinations(adapters: &[u16]) -> u64 {
     slice_valid_combinations(&full_collection, &mut HashMap::new())
 }
 
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Adapter Array";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<u16>;
+    type PartOne = Differences;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_input(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<Differences> {
+        get_differences(input)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Ok(get_valid_combinations(input))
+    }
+}
+
+impl Extras for Day10 {
+    fn extras(_: &Vec<u16>, answer: &Answer<Self>) -> anyhow::Result<Value> {
+        Ok(match answer {
+            Answer::One(differences) => {
+                json!({
+                    "differences": {
+                        "one_jolt": differences.one_jolt,
+                        "three_jolt": differences.three_jolt,
+                    }
+                })
+            }
+            Answer::Two(_) => Value::Null,
+        })
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -131,6 +182,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(10);
         let result = get_differences(PUZZLE_INPUT.as_slice()).unwrap();
         assert_eq!(result.one_jolt * result.three_jolt, 1625);
     }
@@ -160,6 +212,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(10);
         assert_eq!(
             get_valid_combinations(PUZZLE_INPUT.as_slice()),
             3_100_448_333_024
@@ -1,8 +1,17 @@
 // Day 11: Seating System
 
-use std::{convert::TryFrom, convert::TryInto, fmt::Debug, str::FromStr};
-
-use shared::prelude::*;
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use shared::{
+    automaton::{Automaton, BoundedGrid, Live, Neighborhood, Rule},
+    grid::Point,
+    prelude::*,
+    render::{Frames, Palette, Render, Rgb},
+    simulation::{Animate, Simulation},
+};
 
 #[derive(Eq, PartialEq, Copy, Clone)]
 pub enum SeatState {
@@ -12,251 +21,244 @@ pub enum SeatState {
 }
 
 #[derive(Eq, PartialEq, Clone)]
-pub struct SeatLayout {
-    seats: Vec<SeatState>,
-    row_len: usize,
-}
+pub struct SeatLayout(Grid<SeatState>);
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(11).unwrap();
     static ref TEST_INPUT: SeatLayout = include_str!("test_input.txt").parse().unwrap();
-    static ref PUZZLE_INPUT: SeatLayout = include_str!("puzzle_input.txt").parse().unwrap();
+    static ref PUZZLE_INPUT: SeatLayout = RAW_INPUT.parse().unwrap();
+    static ref PALETTE: Palette<SeatState> = Palette::new(Rgb::hex(0xbdbdbd))
+        .with(SeatState::Empty, Rgb::hex(0x43a047))
+        .with(SeatState::Occupied, Rgb::hex(0xe53935));
 }
 
 impl FromStr for SeatLayout {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lines = puzzle_input::lines(s);
-
-        let rows: Vec<Vec<SeatState>> = lines
-            .into_iter()
-            .map(|l| {
-                l.chars()
-                    .map(|char| match char {
-                        '.' => Ok(SeatState::Floor),
-                        'L' => Ok(SeatState::Empty),
-                        '#' => Ok(SeatState::Occupied),
-                        other => Err(anyhow!("Unrecognized char code: {}", other)),
-                    })
-                    .collect::<Result<_, _>>()
-            })
-            .collect::<anyhow::Result<_>>()?;
-
-        let row_len = rows[0].len();
-        let seats: Vec<SeatState> = rows.iter().flatten().cloned().collect();
-
-        Ok(SeatLayout { seats, row_len })
+        let seats = puzzle_input::char_grid(&lines, "`.`, `L` or `#`", |char| match char {
+            '.' => Some(SeatState::Floor),
+            'L' => Some(SeatState::Empty),
+            '#' => Some(SeatState::Occupied),
+            _ => None,
+        })?;
+
+        Ok(SeatLayout(seats))
     }
 }
 
 impl Debug for SeatLayout {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let result = (0..(self.seats.len() / self.row_len))
-            .map(move |y| -> String {
-                (0..self.row_len)
-                    .map(move |x| {
-                        let coordinate = (x, y);
-                        let seat = self.seat_at(&coordinate);
-                        match seat {
-                            SeatState::Floor => '.',
-                            SeatState::Empty => 'L',
-                            SeatState::Occupied => '#',
-                        }
-                    })
-                    .collect()
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        f.write_str(&result)
+        Display::fmt(self, f)
     }
 }
 
-impl SeatLayout {
-    fn map(&self, f: impl Fn(&(usize, usize), &SeatState) -> SeatState + Copy) -> SeatLayout {
-        let new_seats = (0..(self.seats.len() / self.row_len)).flat_map(move |y| {
-            (0..self.row_len).map(move |x| {
-                let coordinate = (x, y);
-                let seat = self.seat_at(&coordinate);
-                f(&coordinate, seat)
-            })
+impl Display for SeatLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let grid = self.0.display_with(|seat| match seat {
+            SeatState::Floor => '.',
+            SeatState::Empty => 'L',
+            SeatState::Occupied => '#',
         });
 
-        SeatLayout {
-            row_len: self.row_len,
-            seats: new_seats.collect(),
-        }
+        write!(f, "{}", grid)
     }
+}
 
-    fn seat_at(&self, (x, y): &(usize, usize)) -> &SeatState {
-        let index = y * self.row_len + x;
-        return &self.seats[index];
+impl Live for SeatState {
+    fn is_live(&self) -> bool {
+        *self == SeatState::Occupied
     }
+}
 
-    fn adjacent_seats(&self, (x, y): &(usize, usize)) -> impl Iterator<Item = &SeatState> {
-        let directions = {
-            let max_x = self.row_len - 1;
-            let max_y = (self.seats.len() / self.row_len) - 1;
-            let x_left = if x <= &0 { None } else { Some(x - 1_usize) };
-            let x_right = if x >= &max_x { None } else { Some(x + 1_usize) };
-            let y_up = if y <= &0 { None } else { Some(y - 1_usize) };
-            let y_down = if y >= &max_y { None } else { Some(y + 1_usize) };
-            let x = Some(*x);
-            let y = Some(*y);
-            vec![
-                (x, y_up),         // up
-                (x_right, y_up),   // up-right
-                (x_right, y),      // right
-                (x_right, y_down), // down-right
-                (x, y_down),       // down
-                (x_left, y_down),  // down-left
-                (x_left, y),       // left
-                (x_left, y_up),    // up-left
-            ]
-        };
+/// People leave once `tolerance` of the seats they pay attention to are taken
+fn seat_rule(tolerance: usize) -> impl Fn(&SeatState, usize) -> SeatState + Sync {
+    move |&seat, occupied| match seat {
+        SeatState::Empty if occupied == 0 => SeatState::Occupied,
+        SeatState::Occupied if occupied >= tolerance => SeatState::Empty,
+        _ => seat,
+    }
+}
 
-        directions.into_iter().filter_map(move |(x, y)| {
-            if let (Some(x), Some(y)) = (x, y) {
-                Some(self.seat_at(&(x, y)))
-            } else {
-                None
-            }
-        })
+impl SeatLayout {
+    /// The first seat in each direction, looking past the floor
+    fn visible_from(&self, coordinate: Point) -> Vec<Point> {
+        grid::NEIGHBORS8
+            .iter()
+            .filter_map(|&direction| {
+                self.0
+                    .ray(coordinate, direction)
+                    .find(|(_, &seat)| seat != SeatState::Floor)
+                    .map(|(x, _)| x)
+            })
+            .collect()
     }
 
-    fn visible_occupied_seats_from(&self, (x, y): &(usize, usize)) -> usize {
-        let directions: Vec<(i32, i32)> = (-1..2)
-            .flat_map(|x| (-1..2).map(move |y| (x, y)))
-            .filter(|x| x != &(0, 0)) // make sure 0,0 isn't in the list of directions!!
-            .collect();
+    pub fn visible_occupied_seats_from(&self, coordinate: &Point) -> usize {
+        self.visible_from(*coordinate)
+            .into_iter()
+            .filter(|&x| self.0[x].is_live())
+            .count()
+    }
 
-        let row_len: i32 = self.row_len.try_into().unwrap();
-        let max_x: i32 = row_len - 1;
-        let max_y: i32 = (i32::try_from(self.seats.len()).unwrap() / row_len) - 1;
-        let x: i32 = x.to_owned().try_into().unwrap();
-        let y: i32 = y.to_owned().try_into().unwrap();
+    fn adjacent_automaton(&self) -> Automaton<BoundedGrid, impl Rule<SeatState>> {
+        Automaton::new(
+            BoundedGrid::around(&self.0, &grid::NEIGHBORS8),
+            seat_rule(4),
+        )
+    }
 
-        directions
-            .into_iter()
-            .map(|(dx, dy)| {
-                struct Context<'a> {
-                    dx: i32,
-                    dy: i32,
-                    max_x: i32,
-                    max_y: i32,
-                    layout: &'a SeatLayout,
-                }
-
-                fn look(x: i32, y: i32, ctx: &Context) -> i32 {
-                    let (next_x, next_y) = (x + ctx.dx, y + ctx.dy);
-                    if next_x < 0 || next_x > ctx.max_x || next_y < 0 || next_y > ctx.max_y {
-                        0 // out of bounds; haven't found an occupied seat
-                    } else {
-                        let seat = ctx
-                            .layout
-                            .seat_at(&(next_x.try_into().unwrap(), next_y.try_into().unwrap()));
-
-                        match seat {
-                            SeatState::Empty => 0,
-                            SeatState::Occupied => 1,
-                            SeatState::Floor => look(next_x, next_y, ctx), // Keep looking
-                        }
-                    }
-                };
-
-                look(
-                    x,
-                    y,
-                    &Context {
-                        dx,
-                        dy,
-                        max_x,
-                        max_y,
-                        layout: self,
-                    },
-                )
-            })
-            .sum::<i32>()
-            .try_into()
-            .unwrap()
+    /// Which seats each seat can see never changes, since the floor doesn't
+    fn visible_seats(&self) -> Grid<Vec<Point>> {
+        self.0.map(|coordinate, _| self.visible_from(coordinate))
     }
 
     pub fn iterate(&self) -> SeatLayout {
-        self.map(|coord, seat| {
-            if seat == &SeatState::Floor {
-                return *seat;
-            }
-
-            let adjacent_occupied = self
-                .adjacent_seats(coord)
-                .filter(|x| x == &&SeatState::Occupied)
-                .count();
-
-            if seat == &SeatState::Empty && adjacent_occupied == 0 {
-                SeatState::Occupied
-            } else if seat == &SeatState::Occupied && adjacent_occupied >= 4 {
-                SeatState::Empty
-            } else {
-                *seat
-            }
-        })
+        SeatLayout(self.adjacent_automaton().step(&self.0))
     }
 
     pub fn iterate_mk2(&self) -> SeatLayout {
-        self.map(|coord, seat| {
-            if seat == &SeatState::Floor {
-                return *seat;
-            }
-
-            let visible_occupied = self.visible_occupied_seats_from(coord);
-
-            if seat == &SeatState::Empty && visible_occupied == 0 {
-                SeatState::Occupied
-            } else if seat == &SeatState::Occupied && visible_occupied >= 5 {
-                SeatState::Empty
-            } else {
-                *seat
-            }
-        })
+        let visible = self.visible_seats();
+        let automaton = Automaton::new(
+            Neighborhood::new(|x: Point| visible[x].iter().copied()),
+            seat_rule(5),
+        );
+        SeatLayout(automaton.step(&self.0))
     }
 
     pub fn iterate_until_stable(&self) -> SeatLayout {
-        let next_state = self.iterate();
-        if &next_state == self {
-            next_state
-        } else {
-            next_state.iterate_until_stable()
-        }
+        SeatLayout(self.adjacent_automaton().run_until_stable(self.0.clone()))
     }
 
     pub fn iterate_until_stable_mk2(&self) -> SeatLayout {
-        let next_state = self.iterate_mk2();
-        if &next_state == self {
-            next_state
-        } else {
-            next_state.iterate_until_stable_mk2()
-        }
+        let visible = self.visible_seats();
+        let automaton = Automaton::new(
+            Neighborhood::new(|x: Point| visible[x].iter().copied()),
+            seat_rule(5),
+        );
+        SeatLayout(automaton.run_until_stable(self.0.clone()))
     }
 
     pub fn occupied(&self) -> usize {
-        self.seats
-            .iter()
+        self.0
+            .cells()
             .filter(|x| x == &&SeatState::Occupied)
             .count()
     }
 }
 
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Seating System";
+    const VERSION: &'static str = "1";
+    type Input<'a> = SeatLayout;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(input.parse()?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(input.iterate_until_stable().occupied())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(input.iterate_until_stable_mk2().occupied())
+    }
+}
+
+/// Rounds of people moving seats under either part's rules, until nobody
+/// moves any more
+pub struct Seating {
+    layout: SeatLayout,
+    part: Part,
+}
+
+impl Simulation for Seating {
+    fn step(&mut self) -> bool {
+        let next = match self.part {
+            Part::One => self.layout.iterate(),
+            Part::Two => self.layout.iterate_mk2(),
+        };
+        if next == self.layout {
+            false
+        } else {
+            self.layout = next;
+            true
+        }
+    }
+
+    fn draw(&self) -> String {
+        self.layout.to_string()
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![("occupied seats", self.layout.occupied().to_string())]
+    }
+}
+
+impl Animate for Day11 {
+    fn simulation<'a>(
+        input: &'a SeatLayout,
+        part: Part,
+    ) -> anyhow::Result<Box<dyn Simulation + 'a>> {
+        Ok(Box::new(Seating {
+            layout: input.clone(),
+            part,
+        }))
+    }
+}
+
+impl Render for Day11 {
+    /// A frame for every round, until nobody moves
+    fn render(input: &SeatLayout, part: Part, frames: &mut dyn Frames) -> anyhow::Result<()> {
+        let mut seating = Seating {
+            layout: input.clone(),
+            part,
+        };
+        loop {
+            frames.push(PALETTE.paint(&seating.layout.0))?;
+            if !seating.step() {
+                return Ok(());
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
 
+    #[test]
+    fn round_trip() {
+        let input = include_str!("test_input.txt").trim();
+        assert_eq!(
+            puzzle_input::assert_round_trip(input, str::parse::<SeatLayout>),
+            input
+        );
+    }
+
     #[test]
     fn test_cases() {
         assert_eq!(TEST_INPUT.iterate_until_stable().occupied(), 37);
     }
 
+    #[test]
+    fn render() {
+        let mut frames = Vec::new();
+        Day11::render(&TEST_INPUT, Part::One, &mut frames).unwrap();
+        assert_eq!(frames.len(), 6);
+        let occupied = PALETTE.color(&SeatState::Occupied);
+        assert_eq!(frames[5].cells().filter(|&&x| x == occupied).count(), 37);
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(11);
         assert_eq!(PUZZLE_INPUT.iterate_until_stable().occupied(), 2489);
     }
 }
@@ -327,6 +329,7 @@ LLLLLLLLL#
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(11);
         assert_eq!(PUZZLE_INPUT.iterate_until_stable_mk2().occupied(), 2180);
     }
 }
@@ -1,28 +1,21 @@
 // Day 12: Rain Risk
 
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
-use shared::prelude::*;
+use shared::{geometry::Direction, prelude::*};
 
 pub mod part_one;
 pub mod part_two;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-pub enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Instruction {
-    Direction(Direction, i32),
-    Turn(i32),
-    Forward(i32),
+    Direction(Direction, i64),
+    Turn(i64),
+    Forward(i64),
 }
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(12).unwrap();
     static ref INSTRUCTION_REGEX: Regex = Regex::new(r"^([NSEWLRF])([0-9]+)$").unwrap();
     static ref TEST_INPUT: Vec<Instruction> = vec!["F10", "N3", "F7", "R90", "F11",]
         .into_iter()
@@ -30,42 +23,22 @@ lazy_static! {
         .collect::<Result<_, _>>()
         .unwrap();
     static ref PUZZLE_INPUT: Vec<Instruction> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"))
-            .into_iter()
-            .map(Instruction::from_str)
-            .collect::<Result<_, _>>()
-            .unwrap();
-}
-
-impl Direction {
-    pub fn clockwise(&self) -> Direction {
-        match self {
-            Direction::North => Direction::East,
-            Direction::South => Direction::West,
-            Direction::East => Direction::South,
-            Direction::West => Direction::North,
-        }
-    }
-
-    pub fn counter_clockwise(&self) -> Direction {
-        match self {
-            Direction::North => Direction::West,
-            Direction::South => Direction::East,
-            Direction::East => Direction::North,
-            Direction::West => Direction::South,
-        }
-    }
+        parse_error::parse_lines(&puzzle_input::lines(&RAW_INPUT), Instruction::from_str).unwrap();
 }
 
 impl FromStr for Instruction {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let captures = INSTRUCTION_REGEX
             .captures(s)
-            .ok_or(anyhow!("Didn't match regex: {}", s))?;
+            .ok_or_else(|| ParseError::new(s, "one of `NSEWLRF` followed by a number"))?;
 
-        let number: i32 = i32::from_str(&captures[2])?;
+        let number_str = captures.get(2).unwrap().as_str();
+        let number: i64 = parse_error::parse_at(s, number_str, "a number")?;
+        if matches!(&captures[1], "L" | "R") && number % 90 != 0 {
+            return Err(ParseError::at(s, number_str, "a multiple of 90 degrees"));
+        }
 
         let parsed = match &captures[1] {
             "N" => Instruction::Direction(Direction::North, number),
@@ -81,3 +54,48 @@ impl FromStr for Instruction {
         Ok(parsed)
     }
 }
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Direction(direction, n) => {
+                let letter = match direction {
+                    Direction::North => 'N',
+                    Direction::South => 'S',
+                    Direction::East => 'E',
+                    Direction::West => 'W',
+                };
+                write!(f, "{}{}", letter, n)
+            }
+            Instruction::Turn(n) if *n < 0 => write!(f, "L{}", -n * 90),
+            Instruction::Turn(n) => write!(f, "R{}", n * 90),
+            Instruction::Forward(n) => write!(f, "F{}", n),
+        }
+    }
+}
+
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Rain Risk";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<Instruction>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_error::parse_lines(
+            &puzzle_input::lines(input),
+            Instruction::from_str,
+        )?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        Ok(part_one::manhattan_distance_of_instructions(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        Ok(part_two::manhattan_distance_of_instructions(input))
+    }
+}
@@ -1,19 +1,18 @@
-use crate::Direction;
+use shared::geometry::{Direction, Vector2};
+
 use crate::Instruction;
 
 #[derive(Copy, Clone)]
 pub struct ShipState {
     direction: Direction,
-    x: i32,
-    y: i32,
+    position: Vector2,
 }
 
 impl Default for ShipState {
     fn default() -> Self {
         ShipState {
             direction: Direction::East,
-            x: 0,
-            y: 0,
+            position: Vector2::ZERO,
         }
     }
 }
@@ -35,39 +34,24 @@ impl ShipState {
         }
     }
 
-    pub fn move_in_direction(&self, direction: Direction, n: i32) -> ShipState {
-        let (x, y) = match direction {
-            Direction::North => (0, -1),
-            Direction::South => (0, 1),
-            Direction::East => (1, 0),
-            Direction::West => (-1, 0),
-        };
-
+    pub fn move_in_direction(&self, direction: Direction, n: i64) -> ShipState {
         ShipState {
-            x: self.x + x * n,
-            y: self.y + y * n,
+            position: self.position + direction.vector() * n,
             ..*self
         }
     }
 
-    pub fn turn(&self, turns: i32) -> ShipState {
-        let turn = match turns > 0 {
-            true => Direction::clockwise,
-            false => Direction::counter_clockwise,
-        };
-
-        let new_direction = (0..turns.abs()).fold(self.direction, |prev, _| turn(&prev));
-
+    pub fn turn(&self, turns: i64) -> ShipState {
         ShipState {
-            direction: new_direction,
+            direction: self.direction.turn(turns),
             ..*self
         }
     }
 }
 
-pub fn manhattan_distance_of_instructions(instructions: &[Instruction]) -> i32 {
+pub fn manhattan_distance_of_instructions(instructions: &[Instruction]) -> i64 {
     let end_state = ShipState::default().follow_instructions(instructions);
-    end_state.x.abs() + end_state.y.abs()
+    end_state.position.manhattan()
 }
 
 #[cfg(test)]
@@ -75,6 +59,18 @@ mod test {
     use super::*;
     use crate::PUZZLE_INPUT;
     use crate::TEST_INPUT;
+    use std::str::FromStr;
+
+    #[test]
+    fn round_trip() {
+        for line in &["F10", "N3", "S0", "E7", "W11", "R90", "L270"] {
+            assert_eq!(
+                shared::puzzle_input::assert_round_trip(line, Instruction::from_str),
+                *line
+            );
+        }
+        assert!(Instruction::from_str("R45").is_err());
+    }
 
     #[test]
     fn test_cases() {
@@ -86,6 +82,7 @@ mod test {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(12);
         assert_eq!(
             manhattan_distance_of_instructions(PUZZLE_INPUT.as_slice()),
             1589
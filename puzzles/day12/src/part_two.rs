@@ -1,69 +1,18 @@
-use crate::Direction;
-use crate::Instruction;
-use core::ops::Add;
-use core::ops::Mul;
+use shared::geometry::Vector2;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub struct Point(i32, i32);
+use crate::Instruction;
 
 #[derive(Debug, Copy, Clone)]
 pub struct ShipState {
-    position: Point,
-    waypoint: Point,
-}
-
-impl Mul<i32> for Point {
-    type Output = Point;
-    fn mul(self, rhs: i32) -> Self::Output {
-        Point(self.0 * rhs, self.1 * rhs)
-    }
-}
-
-impl Add for Point {
-    type Output = Point;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl Point {
-    pub fn clockwise(self) -> Point {
-        let Point(x, y) = self;
-        Point(y * -1, x)
-    }
-
-    pub fn counter_clockwise(self) -> Point {
-        let Point(x, y) = self;
-        Point(y, x * -1)
-    }
-
-    pub fn turn(&self, turns: i32) -> Point {
-        let turn = match turns > 0 {
-            true => Point::clockwise,
-            false => Point::counter_clockwise,
-        };
-
-        (0..turns.abs()).fold(self.to_owned(), |prev, _| turn(prev))
-    }
-
-    pub fn add_direction(&self, direction: Direction, n: i32) -> Point {
-        let delta = match direction {
-            Direction::North => Point(0, -1),
-            Direction::South => Point(0, 1),
-            Direction::East => Point(1, 0),
-            Direction::West => Point(-1, 0),
-        };
-
-        *self + delta * n
-    }
+    position: Vector2,
+    waypoint: Vector2,
 }
 
 impl Default for ShipState {
     fn default() -> Self {
         ShipState {
-            position: Point(0, 0),
-            waypoint: Point(10, -1),
+            position: Vector2::ZERO,
+            waypoint: Vector2::new(10, -1),
         }
     }
 }
@@ -80,11 +29,11 @@ impl ShipState {
     pub fn follow_instruction(&self, instruction: &Instruction) -> ShipState {
         match instruction {
             Instruction::Direction(direction, n) => {
-                let waypoint = self.waypoint.add_direction(*direction, *n);
+                let waypoint = self.waypoint + direction.vector() * *n;
                 ShipState { waypoint, ..*self }
             }
             Instruction::Turn(n) => ShipState {
-                waypoint: self.waypoint.turn(*n),
+                waypoint: self.waypoint.rotate(*n),
                 ..*self
             },
             Instruction::Forward(n) => {
@@ -98,10 +47,9 @@ impl ShipState {
     }
 }
 
-pub fn manhattan_distance_of_instructions(instructions: &[Instruction]) -> i32 {
+pub fn manhattan_distance_of_instructions(instructions: &[Instruction]) -> i64 {
     let end_state = ShipState::default().follow_instructions(instructions);
-    let Point(x, y) = end_state.position;
-    x.abs() + y.abs()
+    end_state.position.manhattan()
 }
 
 #[cfg(test)]
@@ -114,21 +62,21 @@ mod test {
     fn test_clockwise() {
         assert_eq!(
             vec![
-                Point(0, 0),
-                Point(0, -1),
-                Point(-1, -1),
-                Point(1, 0),
-                Point(1, -1),
+                Vector2::new(0, 0),
+                Vector2::new(0, -1),
+                Vector2::new(-1, -1),
+                Vector2::new(1, 0),
+                Vector2::new(1, -1),
             ]
             .into_iter()
             .map(|x| x.clockwise())
-            .collect::<Vec<Point>>(),
+            .collect::<Vec<Vector2>>(),
             vec![
-                Point(0, 0),
-                Point(1, 0),
-                Point(1, -1),
-                Point(0, 1),
-                Point(1, 1)
+                Vector2::new(0, 0),
+                Vector2::new(1, 0),
+                Vector2::new(1, -1),
+                Vector2::new(0, 1),
+                Vector2::new(1, 1)
             ]
         );
     }
@@ -137,21 +85,21 @@ mod test {
     fn test_counter_clockwise() {
         assert_eq!(
             vec![
-                Point(0, 0),
-                Point(1, 0),
-                Point(1, -1),
-                Point(0, 1),
-                Point(1, 1)
+                Vector2::new(0, 0),
+                Vector2::new(1, 0),
+                Vector2::new(1, -1),
+                Vector2::new(0, 1),
+                Vector2::new(1, 1)
             ]
             .into_iter()
             .map(|x| x.counter_clockwise())
-            .collect::<Vec<Point>>(),
+            .collect::<Vec<Vector2>>(),
             vec![
-                Point(0, 0),
-                Point(0, -1),
-                Point(-1, -1),
-                Point(1, 0),
-                Point(1, -1),
+                Vector2::new(0, 0),
+                Vector2::new(0, -1),
+                Vector2::new(-1, -1),
+                Vector2::new(1, 0),
+                Vector2::new(1, -1),
             ]
         );
     }
@@ -166,6 +114,7 @@ mod test {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(12);
         assert_eq!(
             manhattan_distance_of_instructions(PUZZLE_INPUT.as_slice()),
             23960
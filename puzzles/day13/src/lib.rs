@@ -1,14 +1,21 @@
 // Day 13: Shuttle Search
 
-use shared::prelude::*;
+use std::{convert::TryFrom, fmt::Display};
 
-#[derive(Debug, Copy, Clone)]
+use shared::{
+    cancel::{self, Cancelled},
+    math,
+    prelude::*,
+    trace::{self, Event, Explain, NoopTracer, Tracer},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ScheduleEntry {
     X,
     Bus(u16),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Input {
     timestamp: i64,
     schedule: Vec<ScheduleEntry>,
@@ -21,30 +28,33 @@ pub struct EarliestBusOutput {
 }
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(13).unwrap();
     static ref TEST_INPUT: Input = Input::parse(&vec!["939", "7,13,x,x,59,x,31,19"]).unwrap();
-    static ref PUZZLE_INPUT: Input =
-        Input::parse(&puzzle_input::lines(include_str!("puzzle_input.txt"))).unwrap();
+    static ref PUZZLE_INPUT: Input = Input::parse(&puzzle_input::lines(&RAW_INPUT)).unwrap();
 }
 
-pub fn parse_bus_schedule(s: &str) -> anyhow::Result<Vec<ScheduleEntry>> {
-    s.split(",")
-        .map(|x| {
-            if x == "x" {
-                return Ok(ScheduleEntry::X);
-            }
-            Ok(ScheduleEntry::Bus(x.parse()?))
-        })
-        .collect::<anyhow::Result<_>>()
+pub fn parse_bus_schedule(s: &str) -> Result<Vec<ScheduleEntry>, ParseError> {
+    puzzle_input::delimited(s, ",", |x| match x {
+        "x" => Ok(ScheduleEntry::X),
+        _ => match parse_error::parse_at(s, x, "a bus ID or `x`")? {
+            0 => Err(ParseError::at(s, x, "a bus ID above 0")),
+            id => Ok(ScheduleEntry::Bus(id)),
+        },
+    })
 }
 
 impl Input {
-    pub fn parse(s: &[&str]) -> anyhow::Result<Input> {
-        if s.len() != 2 {
-            return Err(anyhow!("Input must be exactly two lines long"));
+    pub fn parse(s: &[&str]) -> Result<Input, ParseError> {
+        match s.len() {
+            0 => return Err(ParseError::at_end("", "a timestamp")),
+            1 => return Err(ParseError::at_end(s[0], "a bus schedule on the next line").on_line(1)),
+            2 => {}
+            _ => return Err(ParseError::new(s[2], "the end of the input").on_line(3)),
         }
 
-        let timestamp = s[0].parse()?;
-        let schedule = parse_bus_schedule(s[1])?;
+        let timestamp =
+            parse_error::parse_at(s[0], s[0], "a timestamp").map_err(|x| x.on_line(1))?;
+        let schedule = parse_bus_schedule(s[1]).map_err(|x| x.on_line(2))?;
 
         Ok(Input {
             timestamp,
@@ -52,6 +62,10 @@ impl Input {
         })
     }
 
+    pub fn schedule(&self) -> &[ScheduleEntry] {
+        &self.schedule
+    }
+
     pub fn earliest_bus(&self) -> Option<EarliestBusOutput> {
         self.schedule
             .iter()
@@ -72,6 +86,20 @@ impl Input {
     }
 }
 
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let schedule: Vec<_> = self
+            .schedule
+            .iter()
+            .map(|x| match x {
+                ScheduleEntry::X => "x".to_string(),
+                ScheduleEntry::Bus(id) => id.to_string(),
+            })
+            .collect();
+        write!(f, "{}\n{}", self.timestamp, schedule.join(","))
+    }
+}
+
 fn all_equal<T: Eq>(arr: &[T]) -> bool {
     if arr.is_empty() {
         return true;
@@ -80,7 +108,8 @@ fn all_equal<T: Eq>(arr: &[T]) -> bool {
     arr.iter().all(|item| item == first)
 }
 
-pub fn brute_force_earliest_sequence(input: &[ScheduleEntry]) -> i64 {
+/// Never finishes if the buses don't line up, so it gives up when cancelled
+pub fn brute_force_earliest_sequence(input: &[ScheduleEntry]) -> Result<i64, Cancelled> {
     let (mut times, buses): (Vec<i64>, Vec<i64>) = input
         .iter()
         .enumerate()
@@ -91,7 +120,9 @@ pub fn brute_force_earliest_sequence(input: &[ScheduleEntry]) -> i64 {
         .map(|(offset, bus_id)| (offset as i64 * -1, *bus_id as i64))
         .unzip();
 
-    while !all_equal(&times) {
+    // The buses all leave together at 0, which doesn't count
+    while !all_equal(&times) || times[0] <= 0 {
+        cancel::check()?;
         let (smallest_index, value) = times
             .iter()
             .enumerate()
@@ -101,54 +132,104 @@ pub fn brute_force_earliest_sequence(input: &[ScheduleEntry]) -> i64 {
         times[smallest_index] = value + buses[smallest_index];
     }
 
-    times[0]
+    Ok(times[0])
 }
 
-pub fn earliest_sequence(input: &[ScheduleEntry]) -> i64 {
-    let (sync_point, _) = input
+/// The first time after 0 that each bus leaves its offset after the first
+/// one, or `None` if they never line up or only do after `i64::MAX`. Each
+/// bus is a congruence `t + offset ≡ 0 (mod id)`.
+pub fn earliest_sequence(input: &[ScheduleEntry]) -> Option<i64> {
+    earliest_sequence_traced(input, &mut NoopTracer)
+}
+
+/// Like [`earliest_sequence`], telling `tracer` where the buses sync up as
+/// each one is added
+pub fn earliest_sequence_traced<T: Tracer + ?Sized>(
+    input: &[ScheduleEntry],
+    tracer: &mut T,
+) -> Option<i64> {
+    let congruences: Vec<(i64, u64)> = input
         .iter()
         .enumerate()
         .filter_map(|(offset, entry)| match entry {
-            ScheduleEntry::Bus(id) => Some((offset as i64, *id as i64)),
+            ScheduleEntry::Bus(id) => Some((-(offset as i64), u64::from(*id))),
             ScheduleEntry::X => None,
         })
-        .fold(
-            (0_i64, 1_i64),
-            |(prev_sync, prev_frequency), (offset, frequency)| {
-                let cycle_length = std::iter::successors(Some(1_i64), |&x| (Some(x + 1)))
-                    .find_map(|i| {
-                        // Find the Least Common Denominator
-                        let n = i * prev_frequency;
-                        if n % frequency == 0 {
-                            Some(n)
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap();
-
-                let sync_point = std::iter::successors(Some(1_i64), |&x| (Some(x + 1)))
-                    .find_map(|i| {
-                        let time = prev_sync + i * prev_frequency;
-                        if (time + offset) % frequency == 0 {
-                            Some(time)
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap();
+        .collect();
 
-                (sync_point, cycle_length)
-            },
-        );
+    // Redoes the fold for each bus, so only when someone's listening
+    if tracer.enabled() {
+        for n in 1..=congruences.len() {
+            let (residue, modulus) = math::crt(congruences[..n].iter().copied())?;
+            let (offset, id) = congruences[n - 1];
+            trace::emit(tracer, || {
+                Event::Note(format!(
+                    "bus {} leaving {} minute(s) later: t ≡ {} (mod {})",
+                    id, -offset, residue, modulus
+                ))
+            });
+        }
+    }
+
+    // A residue of 0 is the buses lining up at the start, so the first time
+    // after that is one whole cycle later
+    let (sync_point, modulus) = math::crt(congruences)?;
+    let sync_point = if sync_point == 0 { modulus } else { sync_point };
+    i64::try_from(sync_point).ok()
+}
 
-    sync_point
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Shuttle Search";
+    const VERSION: &'static str = "2";
+    type Input<'a> = Input;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(Input::parse(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        input
+            .earliest_bus()
+            .map(|x| i64::from(x.bus_id) * x.wait_time)
+            .ok_or(anyhow!("No buses in the schedule"))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        earliest_sequence(&input.schedule).ok_or(anyhow!(
+            "The buses never line up, or not in a 64-bit timestamp"
+        ))
+    }
+}
+
+impl Explain for Day13 {
+    fn explain(input: &Input, part: Part, tracer: &mut dyn Tracer) -> anyhow::Result<String> {
+        match part {
+            Part::One => Self::part_one(input).map(|x| x.to_string()),
+            Part::Two => earliest_sequence_traced(&input.schedule, tracer)
+                .map(|x| x.to_string())
+                .ok_or(anyhow!(
+                    "The buses never line up, or not in a 64-bit timestamp"
+                )),
+        }
+    }
 }
 
 #[cfg(test)]
 mod part_one {
     use super::*;
 
+    #[test]
+    fn round_trip() {
+        let input = "939\n7,13,x,x,59,x,31,19";
+        let parse = |x: &str| Input::parse(&puzzle_input::lines(x));
+        assert_eq!(puzzle_input::assert_round_trip(input, parse), input);
+    }
+
     #[test]
     fn test_cases() {
         let result = TEST_INPUT.earliest_bus().unwrap();
@@ -164,6 +245,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(13);
         let result = PUZZLE_INPUT.earliest_bus().unwrap();
         assert_eq!(i64::from(result.bus_id) * result.wait_time, 4808);
     }
@@ -176,43 +258,158 @@ mod part_two {
     #[test]
     fn basics() {
         let seq: Vec<_> = TEST_INPUT.schedule.iter().take(2).copied().collect();
-        assert_eq!(earliest_sequence(&seq), brute_force_earliest_sequence(&seq));
+        assert_eq!(
+            earliest_sequence(&seq),
+            brute_force_earliest_sequence(&seq).ok()
+        );
 
         let seq: Vec<_> = TEST_INPUT.schedule.iter().take(5).copied().collect();
-        assert_eq!(earliest_sequence(&seq), brute_force_earliest_sequence(&seq));
+        assert_eq!(
+            earliest_sequence(&seq),
+            brute_force_earliest_sequence(&seq).ok()
+        );
+    }
+
+    #[test]
+    fn traces_each_bus() {
+        let mut events = Vec::new();
+        let result = earliest_sequence_traced(&TEST_INPUT.schedule, &mut events);
+        assert_eq!(result, Some(1068781));
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            events[1],
+            Event::Note("bus 13 leaving 1 minute(s) later: t ≡ 77 (mod 91)".to_string())
+        );
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        use shared::differential::{self, Differential};
+
+        // Distinct primes always line up, so the brute force terminates.
+        // Schedules start with a bus, like the puzzle's do, and each later
+        // bus comes after a few `x`s.
+        const PRIMES: [u16; 6] = [2, 3, 5, 7, 11, 13];
+        let gaps = differential::vec_of(differential::range(0..3), 0..PRIMES.len());
+        let schedule = |gaps: &Vec<i64>| -> Vec<ScheduleEntry> {
+            let mut schedule = vec![ScheduleEntry::Bus(PRIMES[0])];
+            for (&gap, &id) in gaps.iter().zip(&PRIMES[1..]) {
+                schedule.extend((0..gap).map(|_| ScheduleEntry::X));
+                schedule.push(ScheduleEntry::Bus(id));
+            }
+            schedule
+        };
+        Differential::new(gaps)
+            .cases(200)
+            .implementation("brute force", |x| {
+                brute_force_earliest_sequence(&schedule(x)).ok()
+            })
+            .implementation("crt", |x| earliest_sequence(&schedule(x)))
+            .assert_agree();
+    }
+
+    #[test]
+    fn lines_up_after_the_start() {
+        use shared::differential::{self, Differential};
+
+        // Each later bus leaves one or two of its own loops after the first,
+        // or isn't in the schedule, so they all line up at 0 as well as at
+        // every multiple of the buses' product
+        const PRIMES: [u16; 6] = [2, 3, 5, 7, 11, 13];
+        let loops = differential::vec_of(differential::range(0..3), 0..PRIMES.len());
+        let schedule = |loops: &Vec<i64>| -> Vec<ScheduleEntry> {
+            let mut schedule = vec![ScheduleEntry::Bus(PRIMES[0])];
+            for (&loops, &id) in loops.iter().zip(&PRIMES[1..]) {
+                if loops == 0 {
+                    continue;
+                }
+                let offset = loops as usize * usize::from(id);
+                if schedule.len() <= offset {
+                    schedule.resize(offset + 1, ScheduleEntry::X);
+                }
+                schedule[offset] = ScheduleEntry::Bus(id);
+            }
+            schedule
+        };
+        Differential::new(loops)
+            .cases(200)
+            .implementation("brute force", |x| {
+                brute_force_earliest_sequence(&schedule(x)).ok()
+            })
+            .implementation("crt", |x| earliest_sequence(&schedule(x)))
+            .assert_agree();
+
+        assert_eq!(
+            earliest_sequence(&parse_bus_schedule("2,x,x,3").unwrap()),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn brute_force_can_be_cancelled() {
+        // Even times can't be one before a multiple of 4
+        let schedule = [ScheduleEntry::Bus(2), ScheduleEntry::Bus(4)];
+        assert_eq!(earliest_sequence(&schedule), None);
+
+        let token = cancel::Token::new();
+        token.cancel();
+        assert_eq!(
+            cancel::with_token(&token, || brute_force_earliest_sequence(&schedule)),
+            Err(Cancelled)
+        );
     }
 
     #[test]
     fn test_case() {
-        assert_eq!(earliest_sequence(&TEST_INPUT.schedule), 1068781);
+        assert_eq!(earliest_sequence(&TEST_INPUT.schedule), Some(1068781));
     }
 
     #[test]
     fn more_test_cases() {
         assert_eq!(
             earliest_sequence(&parse_bus_schedule("17,x,13,19").unwrap()),
-            3417
+            Some(3417)
         );
         assert_eq!(
             earliest_sequence(&parse_bus_schedule("67,7,59,61").unwrap()),
-            754018
+            Some(754018)
         );
         assert_eq!(
             earliest_sequence(&parse_bus_schedule("67,x,7,59,61").unwrap()),
-            779210
+            Some(779210)
         );
         assert_eq!(
             earliest_sequence(&parse_bus_schedule("67,7,x,59,61").unwrap()),
-            1261476
+            Some(1261476)
         );
         assert_eq!(
             earliest_sequence(&parse_bus_schedule("1789,37,47,1889").unwrap()),
-            1202161486
+            Some(1202161486)
+        );
+    }
+
+    #[test]
+    fn shared_factors() {
+        assert_eq!(
+            earliest_sequence(&parse_bus_schedule("4,x,6").unwrap()),
+            Some(4)
         );
+        assert_eq!(earliest_sequence(&parse_bus_schedule("4,6").unwrap()), None);
+    }
+
+    #[test]
+    fn too_late_to_count() {
+        // These primes multiply to more than a u64 holds
+        let schedule = parse_bus_schedule("65521,65519,65497,65479,65449").unwrap();
+        assert_eq!(earliest_sequence(&schedule), None);
     }
 
     #[test]
     fn answer() {
-        assert_eq!(earliest_sequence(&PUZZLE_INPUT.schedule), 741745043105674);
+        shared::skip_without_input!(13);
+        assert_eq!(
+            earliest_sequence(&PUZZLE_INPUT.schedule),
+            Some(741745043105674)
+        );
     }
 }
@@ -1,6 +1,6 @@
 // Day 14: Docking Data
 
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use shared::prelude::*;
 
@@ -25,6 +25,7 @@ pub enum Instruction {
 pub struct Memory(HashMap<u64, u64>);
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(14).unwrap();
     static ref MEM_INDEX_REGEX: Regex = Regex::new(r"^mem\[([0-9]+)\]$").unwrap();
     static ref TEST_INPUT: Vec<Instruction> = vec![
         "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X",
@@ -37,55 +38,87 @@ lazy_static! {
     .collect::<Result<_, _>>()
     .unwrap();
     static ref PUZZLE_INPUT: Vec<Instruction> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"))
-            .into_iter()
-            .map(Instruction::from_str)
-            .collect::<Result<_, _>>()
-            .unwrap();
+        parse_error::parse_lines(&puzzle_input::lines(&RAW_INPUT), Instruction::from_str).unwrap();
 }
 
 impl FromStr for Instruction {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split: Vec<&str> = s.split(" = ").collect();
-        match split[0] {
-            "mask" => Ok(Instruction::SetBitmask(split[1].parse()?)),
+        let (target, value) = s
+            .split_once(" = ")
+            .ok_or_else(|| ParseError::new(s, "`mask = <mask>` or `mem[<address>] = <value>`"))?;
+        match target {
+            "mask" => Ok(Instruction::SetBitmask(
+                value
+                    .parse()
+                    .map_err(|err: ParseError| err.within(s, value))?,
+            )),
             memstr if memstr.starts_with("mem[") => {
                 let captures = MEM_INDEX_REGEX
                     .captures(memstr)
-                    .ok_or(anyhow!("bad index"))?;
+                    .ok_or_else(|| ParseError::at(s, memstr, "`mem[<address>]`"))?;
                 Ok(Instruction::SetValue(MemInstruction {
-                    address: captures[1].parse()?,
-                    value: split[1].parse()?,
+                    address: parse_error::parse_at(
+                        s,
+                        captures.get(1).unwrap().as_str(),
+                        "an address",
+                    )?,
+                    value: parse_error::parse_at(s, value, "a number")?,
                 }))
             }
-            _ => Err(anyhow!("bad instruction")),
+            _ => Err(ParseError::at(s, target, "`mask` or `mem[<address>]`")),
         }
     }
 }
 
 impl FromStr for Bitmask {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.len() != BITS {
-            return Err(anyhow!("Wrong size"));
+            return Err(ParseError::new(s, format!("a {} bit mask", BITS)));
         }
 
         Ok(Bitmask(
-            s.chars()
-                .map(|x| match x {
+            s.char_indices()
+                .map(|(i, x)| match x {
                     'X' => Ok(None),
                     '1' => Ok(Some(true)),
                     '0' => Ok(Some(false)),
-                    _ => Err(anyhow!("bad bitmask")),
+                    _ => Err(ParseError::at_column(s, i, x.len_utf8(), "`X`, `1` or `0`")),
                 })
                 .collect::<Result<_, _>>()?,
         ))
     }
 }
 
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::SetBitmask(mask) => write!(f, "mask = {}", mask),
+            Instruction::SetValue(MemInstruction { address, value }) => {
+                write!(f, "mem[{}] = {}", address, value)
+            }
+        }
+    }
+}
+
+impl Display for Bitmask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mask: String = self
+            .0
+            .iter()
+            .map(|bit| match bit {
+                None => 'X',
+                Some(true) => '1',
+                Some(false) => '0',
+            })
+            .collect();
+        f.write_str(&mask)
+    }
+}
+
 impl Bitmask {
     pub fn apply(&self, original: u64) -> u64 {
         (0..BITS).fold(original, |num, bit| {
@@ -193,10 +226,52 @@ pub fn run_instructions_mk2(instructions: &[Instruction]) -> anyhow::Result<Memo
     Ok(memory)
 }
 
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Docking Data";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<Instruction>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_error::parse_lines(
+            &puzzle_input::lines(input),
+            Instruction::from_str,
+        )?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Ok(run_instructions(input)?.sum_values())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Ok(run_instructions_mk2(input)?.sum_values())
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
 
+    #[test]
+    fn round_trip() {
+        let lines = [
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X",
+            "mem[8] = 11",
+            "mask = 000000000000000000000000000000X1001X",
+            "mem[42] = 100",
+        ];
+        for line in &lines {
+            assert_eq!(
+                puzzle_input::assert_round_trip(line, Instruction::from_str),
+                *line
+            );
+        }
+    }
+
     #[test]
     fn test_basic() {
         let memory =
@@ -217,6 +292,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(14);
         assert_eq!(
             run_instructions(PUZZLE_INPUT.as_slice())
                 .unwrap()
@@ -270,6 +346,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(14);
         assert_eq!(
             run_instructions_mk2(PUZZLE_INPUT.as_slice())
                 .unwrap()
@@ -1,12 +1,22 @@
 // Day 15: Rambunctious Recitation
 
-use shared::prelude::*;
+use shared::{cancel, prelude::*};
+
+pub const PUZZLE_INPUT: &str = "5,2,8,16,18,0,1";
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<usize> = vec![5, 2, 8, 16, 18, 0, 1];
+    static ref STARTING_NUMBERS: Vec<usize> = parse_starting_numbers(PUZZLE_INPUT).unwrap();
+}
+
+pub fn parse_starting_numbers(s: &str) -> Result<Vec<usize>, ParseError> {
+    puzzle_input::number_list(s.trim(), ",")
 }
 
-pub fn result_of_turn(starting: &[usize], final_turn_number: usize) -> usize {
+/// How often the game stops to see if it's been cancelled, since part two
+/// plays 30 million turns
+const TURNS_BETWEEN_CHECKS: usize = 1 << 16;
+
+pub fn result_of_turn(starting: &[usize], final_turn_number: usize) -> anyhow::Result<usize> {
     let starting_turns = starting.len() - 1;
     let mut memory: Vec<Option<usize>> = vec![None; *starting.iter().max().unwrap() + 1];
     // fill out the first few numbers
@@ -17,6 +27,9 @@ pub fn result_of_turn(starting: &[usize], final_turn_number: usize) -> usize {
 
     let mut prev_number = starting[starting_turns];
     for turn_index in (starting_turns + 1)..final_turn_number {
+        if turn_index % TURNS_BETWEEN_CHECKS == 0 {
+            cancel::check()?;
+        }
         let last_turn_of_prev_number = memory.get(prev_number).copied().unwrap_or(None);
         let turns_since_repeat = if let Some(last_turn_of_prev_number) = last_turn_of_prev_number {
             (turn_index - 1) - last_turn_of_prev_number
@@ -32,7 +45,30 @@ pub fn result_of_turn(starting: &[usize], final_turn_number: usize) -> usize {
         prev_number = turns_since_repeat;
     }
 
-    prev_number
+    Ok(prev_number)
+}
+
+pub struct Day15;
+
+impl Puzzle for Day15 {
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Rambunctious Recitation";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<usize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_starting_numbers(input)?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        result_of_turn(input, 2020)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        result_of_turn(input, 30_000_000)
+    }
 }
 
 #[cfg(test)]
@@ -41,27 +77,30 @@ mod part_one {
 
     #[test]
     fn basics() {
-        assert_eq!(result_of_turn(&vec![0, 3, 6], 9), 4);
+        assert_eq!(result_of_turn(&vec![0, 3, 6], 9).unwrap(), 4);
     }
 
     #[test]
     fn test_case() {
-        assert_eq!(result_of_turn(&vec![0, 3, 6], 2020), 436);
+        assert_eq!(result_of_turn(&vec![0, 3, 6], 2020).unwrap(), 436);
     }
 
     #[test]
     fn more_test_cases() {
-        assert_eq!(result_of_turn(&vec![1, 3, 2], 2020), 1);
-        assert_eq!(result_of_turn(&vec![2, 1, 3], 2020), 10);
-        assert_eq!(result_of_turn(&vec![1, 2, 3], 2020), 27);
-        assert_eq!(result_of_turn(&vec![2, 3, 1], 2020), 78);
-        assert_eq!(result_of_turn(&vec![3, 2, 1], 2020), 438);
-        assert_eq!(result_of_turn(&vec![3, 1, 2], 2020), 1836);
+        assert_eq!(result_of_turn(&vec![1, 3, 2], 2020).unwrap(), 1);
+        assert_eq!(result_of_turn(&vec![2, 1, 3], 2020).unwrap(), 10);
+        assert_eq!(result_of_turn(&vec![1, 2, 3], 2020).unwrap(), 27);
+        assert_eq!(result_of_turn(&vec![2, 3, 1], 2020).unwrap(), 78);
+        assert_eq!(result_of_turn(&vec![3, 2, 1], 2020).unwrap(), 438);
+        assert_eq!(result_of_turn(&vec![3, 1, 2], 2020).unwrap(), 1836);
     }
 
     #[test]
     fn answer() {
-        assert_eq!(result_of_turn(PUZZLE_INPUT.as_slice(), 2020), 517);
+        assert_eq!(
+            result_of_turn(STARTING_NUMBERS.as_slice(), 2020).unwrap(),
+            517
+        );
     }
 }
 
@@ -70,11 +109,22 @@ mod part_two {
     use super::*;
     #[test]
     fn test_case() {
-        assert_eq!(result_of_turn(&vec![0, 3, 6], 30_000_000), 175594);
+        assert_eq!(result_of_turn(&vec![0, 3, 6], 30_000_000).unwrap(), 175594);
+    }
+
+    #[test]
+    fn can_be_cancelled() {
+        let token = cancel::Token::new();
+        token.cancel();
+        let result = cancel::with_token(&token, || result_of_turn(&[0, 3, 6], 30_000_000));
+        assert!(result.unwrap_err().is::<cancel::Cancelled>());
     }
 
     #[test]
     fn answer() {
-        assert_eq!(result_of_turn(PUZZLE_INPUT.as_slice(), 30_000_000), 1047739);
+        assert_eq!(
+            result_of_turn(STARTING_NUMBERS.as_slice(), 30_000_000).unwrap(),
+            1047739
+        );
     }
 }
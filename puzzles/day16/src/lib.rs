@@ -1,11 +1,15 @@
-// Day 00: Template
+// Day 16: Ticket Translation
 
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Display,
     ops::Range,
 };
 
-use shared::prelude::*;
+use shared::{
+    prelude::*,
+    trace::{self, Event, Explain, NoopTracer, Tracer},
+};
 
 pub type Rules = HashMap<String, Vec<Range<u32>>>;
 
@@ -22,6 +26,7 @@ pub struct ProblemNotes {
 }
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(16).unwrap();
     static ref RULE_REGEX: Regex =
         Regex::new(r"^([a-z ]+): ([0-9]+)\-([0-9]+) or ([0-9]+)\-([0-9]+)$").unwrap();
     static ref TEST_INPUT: ProblemNotes =
@@ -29,67 +34,42 @@ lazy_static! {
     static ref TEST_INPUT_2: ProblemNotes =
         ProblemNotes::parse_input(&puzzle_input::lines(include_str!("test_input_2.txt"))).unwrap();
     static ref PUZZLE_INPUT: ProblemNotes =
-        ProblemNotes::parse_input(&puzzle_input::lines(include_str!("puzzle_input.txt"))).unwrap();
+        ProblemNotes::parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
+}
+
+fn parse_ticket(line: &str) -> Result<Ticket, ParseError> {
+    puzzle_input::number_list(line, ",")
 }
 
 impl ProblemNotes {
-    pub fn parse_input(lines: &[&str]) -> anyhow::Result<ProblemNotes> {
-        let mut sections = lines.split(|x| x.is_empty());
-        let rules_section = sections.next().ok_or(anyhow!("Missing rules section"))?;
-        let your_ticket_section = sections
-            .next()
-            .ok_or(anyhow!("Missing your ticket section"))?;
-        let nearby_tickets_section = sections
-            .next()
-            .ok_or(anyhow!("Missing nearby tickets section"))?;
-
-        let rules = rules_section
-            .into_iter()
-            .copied()
-            .map(|x| -> anyhow::Result<(String, Vec<Range<u32>>)> {
+    pub fn parse_input(lines: &[&str]) -> Result<ProblemNotes, ParseError> {
+        let [rules, your_ticket, nearby_tickets] =
+            puzzle_input::exact_sections(lines, "rules, your ticket and nearby tickets")?;
+
+        let rules = rules
+            .parse_lines(|x| {
                 let captures = RULE_REGEX
                     .captures(x)
-                    .ok_or(anyhow!("Rule doesn't match format"))?;
-                let field_name = captures[1].to_string();
-
-                let (low1, high1, low2, high2) =
-                    (&captures[2], &captures[3], &captures[4], &captures[5]);
+                    .ok_or_else(|| ParseError::new(x, "a rule like `class: 1-3 or 5-7`"))?;
+                let bound = |i| {
+                    parse_error::parse_at::<u32>(x, captures.get(i).unwrap().as_str(), "a number")
+                };
 
                 // Note that Rust ranges are exclusive at the top range, but the input ranges are formatted as inclusive
-                let range1 = low1.parse().unwrap()..high1.parse::<u32>().unwrap() + 1;
-                let range2 = low2.parse().unwrap()..high2.parse::<u32>().unwrap() + 1;
-
-                Ok((field_name, vec![range1, range2]))
-            })
-            .collect::<anyhow::Result<Rules>>()?;
-
-        let your_ticket = {
-            if your_ticket_section.len() != 2 || your_ticket_section.get(0) != Some(&"your ticket:")
-            {
-                Err(anyhow!("your ticket section improperly formatted"))
-            } else {
-                your_ticket_section[1]
-                    .split(",")
-                    .map(|x| x.parse().map_err(|x| anyhow::Error::from(x)))
-                    .collect()
-            }
-        }?;
-
-        let nearby_tickets = {
-            if nearby_tickets_section.get(0) != Some(&"nearby tickets:") {
-                Err(anyhow!("nearby tickets sections improperly formatted"))
-            } else {
-                nearby_tickets_section
-                    .into_iter()
-                    .skip(1)
-                    .map(|x| {
-                        x.split(",")
-                            .map(|y| y.parse::<u32>().map_err(|y| anyhow::Error::from(y)))
-                            .collect()
-                    })
-                    .collect()
-            }
-        }?;
+                let range1 = bound(2)?..bound(3)? + 1;
+                let range2 = bound(4)?..bound(5)? + 1;
+
+                Ok((captures[1].to_string(), vec![range1, range2]))
+            })?
+            .into_iter()
+            .collect();
+
+        let your_ticket = your_ticket
+            .header("your ticket:")?
+            .parse_single("one ticket", parse_ticket)?;
+        let nearby_tickets = nearby_tickets
+            .header("nearby tickets:")?
+            .parse_lines(parse_ticket)?;
 
         Ok(ProblemNotes {
             rules,
@@ -109,6 +89,12 @@ impl ProblemNotes {
     }
 
     pub fn field_mapping(&self) -> FieldMapping {
+        self.field_mapping_traced(&mut NoopTracer)
+    }
+
+    /// Like [`ProblemNotes::field_mapping`], telling `tracer` how each column
+    /// is narrowed down
+    pub fn field_mapping_traced<T: Tracer + ?Sized>(&self, tracer: &mut T) -> FieldMapping {
         let all_valid_tickets: Vec<&Ticket> = {
             let all_ranges: Vec<&Range<u32>> = self.rules.values().flatten().collect();
 
@@ -153,6 +139,11 @@ impl ProblemNotes {
 
             columns.iter().map(possibilities_for_column).collect()
         };
+        for (i, possibilities) in possibilities_for_columns.iter().enumerate() {
+            trace::emit(tracer, || {
+                Event::narrowed(format!("column {}", i), possibilities)
+            });
+        }
 
         #[derive(Debug, PartialEq, Eq, Clone)]
         enum ColumnState<'a> {
@@ -160,7 +151,10 @@ impl ProblemNotes {
             Possibilities(HashSet<&'a String>),
         }
 
-        fn solve_columns(possibilities: Vec<ColumnState>) -> FieldMapping {
+        fn solve_columns<T: Tracer + ?Sized>(
+            possibilities: Vec<ColumnState>,
+            tracer: &mut T,
+        ) -> FieldMapping {
             // All are solved; stop recursing
             if possibilities.iter().all(|x| {
                 if let ColumnState::Solved(_) = x {
@@ -202,6 +196,7 @@ impl ProblemNotes {
                     .map(|(i, x)| match x {
                         ColumnState::Solved(x) => ColumnState::Solved(x),
                         ColumnState::Possibilities(column_possibilities) => {
+                            let before = column_possibilities.len();
                             let column_possibilities: HashSet<&String> = column_possibilities
                                 .difference(&all_solved)
                                 .copied()
@@ -230,10 +225,20 @@ impl ProblemNotes {
                                     .collect();
 
                             if unique_column_possibilities.len() == 1 {
-                                ColumnState::Solved(
-                                    unique_column_possibilities.iter().next().unwrap(),
-                                )
+                                let solved = unique_column_possibilities.iter().next().unwrap();
+                                trace::emit(tracer, || {
+                                    Event::resolved(format!("column {}", i), solved)
+                                });
+                                ColumnState::Solved(solved)
                             } else {
+                                if column_possibilities.len() < before {
+                                    trace::emit(tracer, || {
+                                        Event::narrowed(
+                                            format!("column {}", i),
+                                            &column_possibilities,
+                                        )
+                                    });
+                                }
                                 ColumnState::Possibilities(column_possibilities)
                             }
                         }
@@ -245,7 +250,7 @@ impl ProblemNotes {
                 panic!("No solutions found! Got stuck on: {:#?}", possibilities);
             }
 
-            solve_columns(possibilities_after_solving)
+            solve_columns(possibilities_after_solving, tracer)
         }
 
         solve_columns(
@@ -253,10 +258,40 @@ impl ProblemNotes {
                 .into_iter()
                 .map(|x| ColumnState::Possibilities(x))
                 .collect(),
+            tracer,
         )
     }
 }
 
+fn write_ticket(f: &mut std::fmt::Formatter<'_>, ticket: &[u32]) -> std::fmt::Result {
+    let numbers: Vec<_> = ticket.iter().map(u32::to_string).collect();
+    writeln!(f, "{}", numbers.join(","))
+}
+
+impl Display for ProblemNotes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The rules are in a map, so sort them to print the same way every time
+        let mut rules: Vec<_> = self.rules.iter().collect();
+        rules.sort_by_key(|(name, _)| *name);
+        for (name, ranges) in rules {
+            let ranges: Vec<_> = ranges
+                .iter()
+                .map(|x| format!("{}-{}", x.start, x.end - 1))
+                .collect();
+            writeln!(f, "{}: {}", name, ranges.join(" or "))?;
+        }
+
+        writeln!(f, "\nyour ticket:")?;
+        write_ticket(f, &self.your_ticket)?;
+
+        writeln!(f, "\nnearby tickets:")?;
+        for ticket in &self.nearby_tickets {
+            write_ticket(f, ticket)?;
+        }
+        Ok(())
+    }
+}
+
 impl FieldMapping {
     pub fn translate(&self, ticket: &Ticket) -> HashMap<String, u32> {
         self.0
@@ -267,6 +302,67 @@ impl FieldMapping {
     }
 }
 
+pub fn part_two(notes: &ProblemNotes) -> u64 {
+    part_two_traced(notes, &mut NoopTracer)
+}
+
+pub fn part_two_traced<T: Tracer + ?Sized>(notes: &ProblemNotes, tracer: &mut T) -> u64 {
+    let ticket = notes
+        .field_mapping_traced(tracer)
+        .translate(&notes.your_ticket);
+    let departures = || {
+        ticket
+            .iter()
+            .filter(|(key, _)| key.starts_with("departure "))
+    };
+
+    if tracer.enabled() {
+        // Sorted so the trace comes out the same every time
+        let mut sorted: Vec<_> = departures().collect();
+        sorted.sort();
+        for (key, value) in sorted {
+            tracer.event(Event::Note(format!("{} on your ticket is {}", key, value)));
+        }
+    }
+    departures().map(|(_, &value)| u64::from(value)).product()
+}
+
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    const DAY: u8 = 16;
+    const TITLE: &'static str = "Ticket Translation";
+    const VERSION: &'static str = "1";
+    type Input<'a> = ProblemNotes;
+    type PartOne = u32;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(ProblemNotes::parse_input(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<u32> {
+        Ok(input.scanning_error_rate())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Ok(part_two(input))
+    }
+}
+
+impl Explain for Day16 {
+    fn explain(
+        input: &ProblemNotes,
+        part: Part,
+        tracer: &mut dyn Tracer,
+    ) -> anyhow::Result<String> {
+        match part {
+            Part::One => Ok(input.scanning_error_rate().to_string()),
+            Part::Two => Ok(part_two_traced(input, tracer).to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -278,6 +374,14 @@ mod part_one {
         assert_eq!(TEST_INPUT.nearby_tickets.len(), 4);
     }
 
+    #[test]
+    fn round_trip() {
+        let parse = |x: &str| ProblemNotes::parse_input(&puzzle_input::lines(x));
+        let input = include_str!("test_input.txt");
+        assert_eq!(puzzle_input::assert_round_trip(input, parse), input);
+        puzzle_input::assert_round_trip(include_str!("test_input_2.txt"), parse);
+    }
+
     #[test]
     fn test_case() {
         assert_eq!(TEST_INPUT.scanning_error_rate(), 71);
@@ -285,6 +389,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(16);
         assert_eq!(PUZZLE_INPUT.scanning_error_rate(), 25961);
     }
 }
@@ -310,17 +415,17 @@ mod part_two {
     }
 
     #[test]
-    fn answer() {
-        let mapped_ticket = PUZZLE_INPUT
-            .field_mapping()
-            .translate(&PUZZLE_INPUT.your_ticket);
-
-        let result: u64 = mapped_ticket
-            .iter()
-            .filter(|(key, _)| key.starts_with("departure "))
-            .map(|(_, &value)| u64::from(value))
-            .product();
+    fn traces_deductions() {
+        let mut events = Vec::new();
+        let mapping = TEST_INPUT_2.field_mapping_traced(&mut events);
+        assert_eq!(mapping, TEST_INPUT_2.field_mapping());
+        assert_eq!(events[0], Event::narrowed("column 0", vec!["row"]));
+        assert!(events.contains(&Event::resolved("column 2", "seat")));
+    }
 
-        assert_eq!(result, 603409823791);
+    #[test]
+    fn answer() {
+        shared::skip_without_input!(16);
+        assert_eq!(part_two(&PUZZLE_INPUT), 603409823791);
     }
 }
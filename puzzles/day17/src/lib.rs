@@ -1,138 +1,206 @@
 // Day 17: Conway Cubes
 
-use std::{collections::HashSet, hash::Hash, ops::Add};
+use std::{collections::HashSet, iter};
 
-use rayon::prelude::*;
-use shared::prelude::*;
+use shared::{
+    automaton::{Automaton, Lattice, Life},
+    geometry::{Vector, Vector2},
+    prelude::*,
+    render::{self, Frames, Palette, Render, Rgb},
+    simulation::{Animate, Simulation},
+};
 
-pub trait Point: Add + Sized + Copy + Hash + Eq + Send + Sync {
-    fn from_xy_slice(x: usize, y: usize) -> Self;
-    fn neighbors(&self) -> Box<dyn Iterator<Item = Self> + '_>;
+/// Active cubes in `N` dimensions, all starting in one 2D slice
+#[derive(Debug, Clone)]
+pub struct ActiveCubes<const N: usize>(HashSet<Vector<N>>);
+
+lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(17).unwrap();
+    static ref TEST_INPUT: Vec<&'static str> = vec![".#.", "..#", "###"];
+    static ref PUZZLE_INPUT: Vec<&'static str> = puzzle_input::lines(&RAW_INPUT);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Point3(i32, i32, i32);
+const CONWAY_CUBES: Life = Life {
+    born: &[3],
+    survives: &[2, 3],
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Point4(i32, i32, i32, i32);
+impl<const N: usize> ActiveCubes<N> {
+    pub fn parse(s: &[&str]) -> Result<ActiveCubes<N>, ParseError> {
+        let slice = puzzle_input::char_grid(s, "`.` or `#`", |char| match char {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
 
-#[derive(Debug, Clone)]
-pub struct ActiveCubes<T: Point>(HashSet<T>);
+        let cubes = slice
+            .iter()
+            .filter(|(_, active)| **active)
+            .map(|(point, _)| Vector2::from(point).extend())
+            .collect();
 
-lazy_static! {
-    static ref TEST_INPUT: Vec<&'static str> = vec![".#.", "..#", "###,"];
-    static ref PUZZLE_INPUT: Vec<&'static str> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"));
-}
+        Ok(ActiveCubes(cubes))
+    }
 
-impl Point for Point3 {
-    fn from_xy_slice(x: usize, y: usize) -> Self {
-        Point3(x as i32, y as i32, 0)
+    pub fn count(&self) -> usize {
+        self.0.len()
     }
 
-    fn neighbors(&self) -> Box<dyn Iterator<Item = Self> + '_> {
-        let directions = (-1..2)
-            .flat_map(|x| (-1..2).flat_map(move |y| (-1..2).map(move |z| Point3(x, y, z))))
-            .filter(|x| x != &Point3(0, 0, 0)); // make sure 0,0 isn't in the list of directions!!
+    fn automaton() -> Automaton<Lattice<Vector<N>, N>, Life> {
+        Automaton::new(Lattice::default(), CONWAY_CUBES).parallel()
+    }
 
-        Box::from(directions.map(move |direction| *self + direction))
+    pub fn boot_cycle(&self) -> ActiveCubes<N> {
+        ActiveCubes(Self::automaton().step(&self.0))
     }
-}
 
-impl Add for Point3 {
-    type Output = Point3;
+    pub fn boot(&self) -> Self {
+        ActiveCubes(Self::automaton().run(self.0.clone(), 6))
+    }
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Point3(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    /// Cuts each state into x/y slices, laid out in a grid by z and then w.
+    /// Every slice covers the same area, so states can be compared.
+    pub fn slices(states: &[Self]) -> Vec<Grid<Grid<bool>>> {
+        let mut cubes = states.iter().flat_map(|x| x.0.iter().copied());
+        let first = match cubes.next() {
+            Some(cube) => cube,
+            None => return states.iter().map(|_| Grid::new(0, 0, vec![])).collect(),
+        };
+        let (min, max) = cubes.fold((first, first), |(mut min, mut max), cube| {
+            for axis in 0..N {
+                min[axis] = min[axis].min(cube[axis]);
+                max[axis] = max[axis].max(cube[axis]);
+            }
+            (min, max)
+        });
+        let extent = |axis: usize| (max[axis] - min[axis] + 1) as usize;
+        let slice_count = (2..N).map(extent).product::<usize>();
+
+        states
+            .iter()
+            .map(|state| {
+                let slices = (0..slice_count)
+                    .map(|mut i| {
+                        let mut corner = min;
+                        for axis in 2..N {
+                            corner[axis] += (i % extent(axis)) as i64;
+                            i /= extent(axis);
+                        }
+                        Grid::from_fn(extent(0), extent(1), |(x, y)| {
+                            let mut cube = corner;
+                            cube[0] += x as i64;
+                            cube[1] += y as i64;
+                            state.0.contains(&cube)
+                        })
+                    })
+                    .collect();
+                Grid::new(extent(2), slice_count / extent(2), slices)
+            })
+            .collect()
     }
 }
 
-impl Point for Point4 {
-    fn from_xy_slice(x: usize, y: usize) -> Self {
-        Point4(x as i32, y as i32, 0, 0)
+impl<const N: usize> Simulation for ActiveCubes<N> {
+    fn step(&mut self) -> bool {
+        *self = self.boot_cycle();
+        true
     }
 
-    fn neighbors(&self) -> Box<dyn Iterator<Item = Self> + '_> {
-        let directions = (-1..2)
-            .flat_map(|x| {
-                (-1..2).flat_map(move |y| {
-                    (-1..2).flat_map(move |z| (-1..2).map(move |w| Point4(x, y, z, w)))
-                })
-            })
-            .filter(|x| x != &Point4(0, 0, 0, 0));
+    /// Each z slice side by side, with a row of them for each w
+    fn draw(&self) -> String {
+        let slices = Self::slices(std::slice::from_ref(self)).remove(0);
+        let mut text = String::new();
+        for row in slices.rows() {
+            for y in 0..row.first().map_or(0, Grid::height) {
+                let line: Vec<String> = row
+                    .iter()
+                    .map(|slice| {
+                        (0..slice.width())
+                            .map(|x| if slice[(x, y)] { '#' } else { '.' })
+                            .collect()
+                    })
+                    .collect();
+                text.push_str(&line.join(" "));
+                text.push('\n');
+            }
+            text.push('\n');
+        }
+        text
+    }
 
-        Box::from(directions.map(move |direction| *self + direction))
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![("active cubes", self.count().to_string())]
     }
-}
 
-impl Add for Point4 {
-    type Output = Point4;
+    /// The boot process is six cycles
+    fn target(&self) -> Option<usize> {
+        Some(6)
+    }
+}
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Point4(
-            self.0 + rhs.0,
-            self.1 + rhs.1,
-            self.2 + rhs.2,
-            self.3 + rhs.3,
-        )
+/// A frame for each cycle of the boot process, showing every slice
+fn render_boot<const N: usize>(
+    cubes: &ActiveCubes<N>,
+    frames: &mut dyn Frames,
+) -> anyhow::Result<()> {
+    let palette = Palette::new(Rgb::hex(0x1a237e)).with(true, Rgb::hex(0xffeb3b));
+    let states: Vec<_> = iter::successors(Some(cubes.clone()), |x| Some(x.boot_cycle()))
+        .take(7)
+        .collect();
+
+    for slices in ActiveCubes::slices(&states) {
+        let images: Vec<_> = slices.cells().map(|x| palette.paint(x)).collect();
+        frames.push(render::tiles(&images, slices.width(), 1, Rgb::BLACK))?;
     }
+    Ok(())
 }
 
-impl<T: Point> ActiveCubes<T> {
-    pub fn parse(s: &[&str]) -> ActiveCubes<T> {
-        let cubes = s
-            .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.chars()
-                    .enumerate()
-                    .filter(|(_, char)| *char == '#')
-                    .map(move |(x, _)| T::from_xy_slice(x, y))
-            })
-            .collect();
+pub struct Day17;
 
-        ActiveCubes(cubes)
-    }
+impl Puzzle for Day17 {
+    const DAY: u8 = 17;
+    const TITLE: &'static str = "Conway Cubes";
+    const VERSION: &'static str = "1";
+    type Input<'a> = (ActiveCubes<3>, ActiveCubes<4>);
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    pub fn count(&self) -> usize {
-        self.0.len()
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        let lines = puzzle_input::lines(input);
+        Ok((ActiveCubes::parse(&lines)?, ActiveCubes::parse(&lines)?))
     }
 
-    pub fn cycle(&self) -> ActiveCubes<T> {
-        // All active cubes plus all their neighbors
-        let search_space: HashSet<T> = self
-            .0
-            .par_iter()
-            .flat_map(|point| point.neighbors().collect::<Vec<_>>())
-            .chain(self.0.par_iter().copied())
-            .collect();
-
-        let active_neighbors = |point: &T| -> usize {
-            point
-                .neighbors()
-                .filter(|neighbor| self.0.contains(neighbor))
-                .count()
-        };
+    fn part_one((cubes, _): &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(cubes.boot().count())
+    }
 
-        let next_active: HashSet<T> = search_space
-            .par_iter()
-            .copied()
-            .filter(|point| {
-                let is_active = self.0.contains(point);
-                let active_neighbors = active_neighbors(point);
-
-                match is_active {
-                    true => active_neighbors == 2 || active_neighbors == 3,
-                    false => active_neighbors == 3,
-                }
-            })
-            .collect();
+    fn part_two((_, cubes): &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(cubes.boot().count())
+    }
+}
 
-        ActiveCubes(next_active)
+impl Animate for Day17 {
+    fn simulation<'a>(
+        (three, four): &'a Self::Input<'_>,
+        part: Part,
+    ) -> anyhow::Result<Box<dyn Simulation + 'a>> {
+        Ok(match part {
+            Part::One => Box::new(three.clone()),
+            Part::Two => Box::new(four.clone()),
+        })
     }
+}
 
-    pub fn boot(&self) -> Self {
-        (0..6).fold(self.clone(), |prev_state, _| prev_state.cycle())
+impl Render for Day17 {
+    fn render(
+        (three, four): &Self::Input<'_>,
+        part: Part,
+        frames: &mut dyn Frames,
+    ) -> anyhow::Result<()> {
+        match part {
+            Part::One => render_boot(three, frames),
+            Part::Two => render_boot(four, frames),
+        }
     }
 }
 
@@ -143,7 +211,9 @@ mod part_one {
     #[test]
     fn test_parse() {
         assert_eq!(
-            ActiveCubes::<Point3>::parse(TEST_INPUT.as_slice()).count(),
+            ActiveCubes::<3>::parse(TEST_INPUT.as_slice())
+                .unwrap()
+                .count(),
             5
         );
     }
@@ -151,17 +221,41 @@ mod part_one {
     #[test]
     fn test_one_cycle() {
         assert_eq!(
-            ActiveCubes::<Point3>::parse(TEST_INPUT.as_slice())
-                .cycle()
+            ActiveCubes::<3>::parse(TEST_INPUT.as_slice())
+                .unwrap()
+                .boot_cycle()
                 .count(),
             11
         );
     }
 
+    #[test]
+    fn test_slices() {
+        let cubes = ActiveCubes::<3>::parse(TEST_INPUT.as_slice()).unwrap();
+        let slices = ActiveCubes::slices(&[cubes.clone(), cubes.boot_cycle()]);
+        assert_eq!((slices[1].width(), slices[1].height()), (3, 1));
+        let active = |x: &Grid<Grid<bool>>| x.cells().flat_map(Grid::cells).filter(|&&x| x).count();
+        assert_eq!(active(&slices[0]), 5);
+        assert_eq!(active(&slices[1]), 11);
+        // The original slice is in the middle, at z = 0
+        assert_eq!(slices[0][(1, 0)].cells().filter(|&&x| x).count(), 5);
+    }
+
+    #[test]
+    fn test_draw() {
+        let cubes = ActiveCubes::<3>::parse(TEST_INPUT.as_slice()).unwrap();
+        assert_eq!(cubes.draw(), ".#.\n..#\n###\n\n");
+        assert_eq!(
+            cubes.boot_cycle().draw().lines().next(),
+            Some("#.. #.# #..")
+        );
+    }
+
     #[test]
     fn test_case() {
         assert_eq!(
-            ActiveCubes::<Point3>::parse(TEST_INPUT.as_slice())
+            ActiveCubes::<3>::parse(TEST_INPUT.as_slice())
+                .unwrap()
                 .boot()
                 .count(),
             112
@@ -170,8 +264,10 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(17);
         assert_eq!(
-            ActiveCubes::<Point3>::parse(PUZZLE_INPUT.as_slice())
+            ActiveCubes::<3>::parse(PUZZLE_INPUT.as_slice())
+                .unwrap()
                 .boot()
                 .count(),
             247
@@ -185,8 +281,9 @@ mod part_two {
     #[test]
     fn test_one_cycle() {
         assert_eq!(
-            ActiveCubes::<Point4>::parse(TEST_INPUT.as_slice())
-                .cycle()
+            ActiveCubes::<4>::parse(TEST_INPUT.as_slice())
+                .unwrap()
+                .boot_cycle()
                 .count(),
             29
         );
@@ -195,7 +292,8 @@ mod part_two {
     #[test]
     fn test_case() {
         assert_eq!(
-            ActiveCubes::<Point4>::parse(TEST_INPUT.as_slice())
+            ActiveCubes::<4>::parse(TEST_INPUT.as_slice())
+                .unwrap()
                 .boot()
                 .count(),
             848
@@ -204,8 +302,10 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(17);
         assert_eq!(
-            ActiveCubes::<Point4>::parse(PUZZLE_INPUT.as_slice())
+            ActiveCubes::<4>::parse(PUZZLE_INPUT.as_slice())
+                .unwrap()
                 .boot()
                 .count(),
             1392
@@ -34,36 +34,57 @@ pub struct Operation {
 pub struct OperationContinuation(Operator, Box<Expression>);
 
 lazy_static! {
-    static ref PUZZLE_INPUT: Vec<&'static str> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"));
+    static ref RAW_INPUT: String = puzzle_input::load(18).unwrap();
+    static ref PUZZLE_INPUT: Vec<&'static str> = puzzle_input::lines(&RAW_INPUT);
 }
 
-pub fn parse(s: &str) -> anyhow::Result<Vec<Token>> {
-    s.chars()
-        .filter(|&x| x != ' ')
-        .map(|x| match x {
-            '(' => Ok(Token::ParenOpen),
-            ')' => Ok(Token::ParenClose),
-            '+' => Ok(Token::Operator(Operator::Add)),
-            '*' => Ok(Token::Operator(Operator::Multiply)),
+/// Tokens paired with the byte column they start at
+pub fn parse(s: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    s.char_indices()
+        .filter(|&(_, x)| x != ' ')
+        .map(|(column, x)| match x {
+            '(' => Ok((column, Token::ParenOpen)),
+            ')' => Ok((column, Token::ParenClose)),
+            '+' => Ok((column, Token::Operator(Operator::Add))),
+            '*' => Ok((column, Token::Operator(Operator::Multiply))),
             x => x
                 .to_digit(10)
-                .ok_or(anyhow!("Unsupported character"))
-                .map(|x| Token::Number(i64::from(x))),
+                .ok_or_else(|| {
+                    ParseError::at_column(
+                        s,
+                        column,
+                        x.len_utf8(),
+                        "a digit, operator or parenthesis",
+                    )
+                })
+                .map(|x| (column, Token::Number(i64::from(x)))),
         })
         .collect()
 }
 
+fn unexpected(line: &str, token: Option<(usize, Token)>, expected: &str) -> ParseError {
+    match token {
+        Some((column, _)) => ParseError::at_column(line, column, 1, expected),
+        None => ParseError::at_end(line, expected),
+    }
+}
+
 impl Expression {
-    pub fn parse(input: &mut impl Iterator<Item = Token>) -> anyhow::Result<Expression> {
-        Ok(Expression::Operation(Operation::try_collect(input)?))
+    pub fn parse(
+        line: &str,
+        input: &mut impl Iterator<Item = (usize, Token)>,
+    ) -> Result<Expression, ParseError> {
+        Ok(Expression::Operation(Operation::try_collect(line, input)?))
     }
 
-    fn parse_numeric(input: &mut impl Iterator<Item = Token>) -> anyhow::Result<Expression> {
+    fn parse_numeric(
+        line: &str,
+        input: &mut impl Iterator<Item = (usize, Token)>,
+    ) -> Result<Expression, ParseError> {
         match input.next() {
-            Some(Token::Number(number)) => Ok(Expression::Number(number)),
-            Some(Token::ParenOpen) => Expression::parse(input),
-            Some(_) | None => Err(anyhow!("Expected number")),
+            Some((_, Token::Number(number))) => Ok(Expression::Number(number)),
+            Some((_, Token::ParenOpen)) => Expression::parse(line, input),
+            token => Err(unexpected(line, token, "a number or `(`")),
         }
     }
 
@@ -83,27 +104,31 @@ impl Expression {
 }
 
 impl Operation {
-    pub fn try_collect(input: &mut impl Iterator<Item = Token>) -> anyhow::Result<Operation> {
-        let left = Box::new(Expression::parse_numeric(input)?);
+    pub fn try_collect(
+        line: &str,
+        input: &mut impl Iterator<Item = (usize, Token)>,
+    ) -> Result<Operation, ParseError> {
+        let left = Box::new(Expression::parse_numeric(line, input)?);
 
         fn next(
-            iter: &mut impl Iterator<Item = Token>,
+            line: &str,
+            iter: &mut impl Iterator<Item = (usize, Token)>,
             mut list_so_far: Vec<OperationContinuation>,
-        ) -> anyhow::Result<Vec<OperationContinuation>> {
+        ) -> Result<Vec<OperationContinuation>, ParseError> {
             let operator = iter.next();
             match operator {
-                Some(Token::Operator(operator)) => {
-                    let expression = Expression::parse_numeric(iter)?;
+                Some((_, Token::Operator(operator))) => {
+                    let expression = Expression::parse_numeric(line, iter)?;
                     let continuation = OperationContinuation(operator, Box::new(expression));
                     list_so_far.push(continuation);
-                    next(iter, list_so_far)
+                    next(line, iter, list_so_far)
                 }
-                None | Some(Token::ParenClose) => Ok(list_so_far), // end of operation
-                Some(_) => Err(anyhow!("Expected an operator")),
+                None | Some((_, Token::ParenClose)) => Ok(list_so_far), // end of operation
+                token => Err(unexpected(line, token, "an operator")),
             }
         }
 
-        let right = next(input, vec![])?;
+        let right = next(line, input, vec![])?;
 
         Ok(Operation { left, right })
     }
@@ -207,41 +232,70 @@ impl Display for Expression {
     }
 }
 
+/// The way it's written in the puzzle, without parentheses around the whole
+/// thing
 impl Display for Operation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let tokens = self
-            .right
-            .iter()
-            .flat_map(|OperationContinuation(operator, expression)| {
-                vec![
-                    match operator {
-                        Operator::Add => "+".to_string(),
-                        Operator::Multiply => "*".to_string(),
-                    },
-                    expression.to_string(),
-                ]
-            })
-            .collect::<Vec<String>>()
-            .join(" ");
-
-        f.write_str(&format!("{} {}", self.left.to_string(), tokens))
+        write!(f, "{}", self.left)?;
+        for OperationContinuation(operator, expression) in &self.right {
+            let operator = match operator {
+                Operator::Add => "+",
+                Operator::Multiply => "*",
+            };
+            write!(f, " {} {}", operator, expression)?;
+        }
+        Ok(())
     }
 }
 
 pub fn eval(s: &str) -> anyhow::Result<i64> {
     let tokens = parse(s)?;
-    let expression: Expression = Expression::parse(&mut tokens.into_iter())?;
+    let expression: Expression = Expression::parse(s, &mut tokens.into_iter())?;
 
     Ok(expression.evaluate())
 }
 
 pub fn eval_mk2(s: &str) -> anyhow::Result<i64> {
     let tokens = parse(s)?;
-    let expression: Expression = Expression::parse(&mut tokens.into_iter())?;
+    let expression: Expression = Expression::parse(s, &mut tokens.into_iter())?;
 
     Ok(expression.evaluate_mk2())
 }
 
+pub fn part_one(lines: &[&str]) -> anyhow::Result<i64> {
+    lines.iter().map(|&x| eval(x)).sum()
+}
+
+pub fn part_two(lines: &[&str]) -> anyhow::Result<i64> {
+    lines.iter().map(|&x| eval_mk2(x)).sum()
+}
+
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    const DAY: u8 = 18;
+    const TITLE: &'static str = "Operation Order";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<Expression>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_error::parse_lines(
+            &puzzle_input::lines(input),
+            |line| Expression::parse(line, &mut parse(line)?.into_iter()),
+        )?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        Ok(input.iter().map(Expression::evaluate).sum())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<i64> {
+        Ok(input.iter().map(Expression::evaluate_mk2).sum())
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -251,6 +305,21 @@ mod part_one {
         assert_eq!(eval("1 + 2 * 3 + 4 * 5 + 6").unwrap(), 71);
     }
 
+    #[test]
+    fn round_trip() {
+        let parse_line = |line: &str| -> Result<Operation, ParseError> {
+            Operation::try_collect(line, &mut parse(line)?.into_iter())
+        };
+        for line in &[
+            "7",
+            "(7)",
+            "1 + 2 * 3 + 4 * 5 + 6",
+            "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+        ] {
+            assert_eq!(puzzle_input::assert_round_trip(line, parse_line), *line);
+        }
+    }
+
     #[test]
     fn test_parentheses() {
         assert_eq!(eval("1 + (2 * 3) + (4 * (5 + 6))").unwrap(), 51);
@@ -272,14 +341,8 @@ mod part_one {
 
     #[test]
     fn answer() {
-        let result = PUZZLE_INPUT
-            .iter()
-            .map(|&x| eval(x))
-            .try_fold(0, |a, b| match b {
-                Ok(b) => Ok(a + b),
-                err @ Err(_) => err,
-            })
-            .unwrap();
+        shared::skip_without_input!(18);
+        let result = part_one(&PUZZLE_INPUT).unwrap();
 
         assert_eq!(result, 11004703763391);
     }
@@ -310,14 +373,8 @@ mod part_two {
 
     #[test]
     fn answer() {
-        let result = PUZZLE_INPUT
-            .iter()
-            .map(|&x| eval_mk2(x))
-            .try_fold(0, |a, b| match b {
-                Ok(b) => Ok(a + b),
-                err @ Err(_) => err,
-            })
-            .unwrap();
+        shared::skip_without_input!(18);
+        let result = part_two(&PUZZLE_INPUT).unwrap();
 
         assert_eq!(result, 290726428573651);
     }
@@ -2,16 +2,17 @@
 
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Display,
     iter::FromIterator,
     str::FromStr,
 };
 
 use shared::prelude::*;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Input<'a>(Rules, Vec<&'a str>);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rules {
     rule_map: HashMap<usize, Rule>,
 }
@@ -23,8 +24,9 @@ pub enum Rule {
 }
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(19).unwrap();
     static ref PUZZLE_INPUT: Input<'static> =
-        Input::parse(&puzzle_input::lines(include_str!("puzzle_input.txt"))).unwrap();
+        Input::parse(&puzzle_input::lines(&RAW_INPUT)).unwrap();
     static ref TEST_INPUT: Input<'static> =
         Input::parse(&puzzle_input::lines(include_str!("test_input.txt"))).unwrap();
     static ref TEST_INPUT_2: Input<'static> =
@@ -32,16 +34,11 @@ lazy_static! {
 }
 
 impl Input<'_> {
-    pub fn parse<'a>(input: &[&'a str]) -> anyhow::Result<Input<'a>> {
-        let sections: Vec<_> = input.split(|x| x.trim().is_empty()).collect();
-        if sections.len() != 2 {
-            return Err(anyhow!("wrong number of sections"));
-        }
+    pub fn parse<'a>(input: &[&'a str]) -> Result<Input<'a>, ParseError> {
+        let [rules, values] = puzzle_input::exact_sections(input, "rules and messages")?;
+        let rules = Rules::parse(rules)?;
 
-        let rules = Rules::parse(sections[0])?;
-        let values = sections[1];
-
-        Ok(Input(rules, values.to_vec()))
+        Ok(Input(rules, values.lines.to_vec()))
     }
 }
 
@@ -87,23 +84,22 @@ impl<'a> FromIterator<RuleParseResult<'a>> for RuleParseResult<'a> {
 }
 
 impl Rules {
-    pub fn parse(rules: &[&str]) -> anyhow::Result<Rules> {
-        let rule_map: anyhow::Result<HashMap<usize, Rule>> = rules
-            .iter()
-            .map(|x| {
-                let captures = RULE_REGEX
-                    .captures(x)
-                    .ok_or(anyhow!("bad rule formatting"))?;
+    pub fn parse(rules: puzzle_input::Section) -> Result<Rules, ParseError> {
+        let rule_map = rules.parse_lines(|x| {
+            let captures = RULE_REGEX
+                .captures(x)
+                .ok_or_else(|| ParseError::new(x, "a rule like `0: 4 1 5`"))?;
 
-                let rule_id: usize = captures[1].parse()?;
-                let rule: Rule = captures[2].parse()?;
+            let rule_id: usize = parse_error::parse_at(x, &captures[1], "a rule number")?;
+            let rule: Rule = captures[2]
+                .parse()
+                .map_err(|err: ParseError| err.within(x, &captures[2]))?;
 
-                Ok((rule_id, rule))
-            })
-            .collect();
+            Ok((rule_id, rule))
+        })?;
 
         Ok(Rules {
-            rule_map: rule_map?,
+            rule_map: rule_map.into_iter().collect(),
         })
     }
 
@@ -201,7 +197,7 @@ impl Rules {
 }
 
 impl FromStr for Rule {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if let Some(literal_char_captures) = LITERAL_CHAR_REGEX.captures(s) {
@@ -214,7 +210,7 @@ impl FromStr for Rule {
                 .map(|alternative| {
                     alternative
                         .split(" ")
-                        .map(|x| x.parse().map_err(|e| anyhow::Error::from(e)))
+                        .map(|x| parse_error::parse_at(s, x, "a rule number"))
                         .collect()
                 })
                 .collect();
@@ -224,10 +220,101 @@ impl FromStr for Rule {
     }
 }
 
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::LiteralChar(char) => write!(f, "\"{}\"", char),
+            Rule::RuleLists(lists) => {
+                let lists: Vec<String> = lists
+                    .iter()
+                    .map(|list| {
+                        list.iter()
+                            .map(usize::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect();
+                f.write_str(&lists.join(" | "))
+            }
+        }
+    }
+}
+
+impl Display for Rules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rule_ids: Vec<_> = self.rule_map.keys().collect();
+        rule_ids.sort();
+        for rule_id in rule_ids {
+            writeln!(f, "{}: {}", rule_id, self.rule_map[rule_id])?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Input<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Input(rules, values) = self;
+        writeln!(f, "{}", rules)?;
+        for value in values {
+            writeln!(f, "{}", value)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn part_one(input: &Input) -> usize {
+    let Input(rules, values) = input;
+    values.iter().filter(|x| rules.matches(x)).count()
+}
+
+pub fn part_two(input: &Input) -> usize {
+    let Input(rules, values) = input;
+    let rules = rules.to_owned().mk2_patch();
+    values.iter().filter(|x| rules.matches(x)).count()
+}
+
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    const DAY: u8 = 19;
+    const TITLE: &'static str = "Monster Messages";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Input<'a>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(Input::parse(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(part_one(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(part_two(input))
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
 
+    #[test]
+    fn round_trip() {
+        let parse = |x: &str| Rules::parse(puzzle_input::Section::new(&puzzle_input::lines(x)));
+        let rules = "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n";
+        assert_eq!(puzzle_input::assert_round_trip(rules, parse), rules);
+
+        for input in &[&*TEST_INPUT, &*TEST_INPUT_2] {
+            let printed = input.to_string();
+            assert_eq!(
+                Input::parse(&puzzle_input::lines(&printed)).unwrap(),
+                **input
+            );
+        }
+    }
+
     #[test]
     fn test_cases() {
         let Input(rules, values) = TEST_INPUT.deref();
@@ -241,9 +328,8 @@ mod part_one {
 
     #[test]
     fn answer() {
-        let Input(rules, values) = PUZZLE_INPUT.deref();
-        let matches = values.iter().copied().filter(|x| rules.matches(x)).count();
-        assert_eq!(matches, 144);
+        shared::skip_without_input!(19);
+        assert_eq!(part_one(&PUZZLE_INPUT), 144);
     }
 }
 
@@ -302,9 +388,7 @@ mod part_two {
 
     #[test]
     fn answer() {
-        let Input(rules, values) = PUZZLE_INPUT.deref();
-        let rules = rules.to_owned().mk2_patch();
-        let matches = values.iter().copied().filter(|x| rules.matches(x)).count();
-        assert_eq!(matches, 260);
+        shared::skip_without_input!(19);
+        assert_eq!(part_two(&PUZZLE_INPUT), 260);
     }
 }
@@ -3,43 +3,37 @@
 use std::{
     borrow::Cow,
     collections::{hash_map::Entry, HashMap, HashSet},
-    fmt::Debug,
-    iter,
+    fmt::{Debug, Display},
 };
 
 use rayon::prelude::*;
-use shared::prelude::*;
+use shared::{
+    cancel,
+    extras::{json, Extras, Value},
+    grid::{Point, Transform},
+    prelude::*,
+    puzzle::Answer,
+    render::{Frames, Palette, Render, Rgb},
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Tile {
     tile_id: u64,
-    size: usize,
-    data: Vec<bool>,
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Default, Clone, Copy)]
-pub struct Transformation {
-    flip_x: bool,
-    flip_y: bool,
-    rotated: bool,
+    pixels: Grid<bool>,
 }
 
 #[derive(PartialEq, Eq, Clone)]
 struct ImageSolvingData<'a, 'b> {
-    size: usize,
     remaining_tiles: Vec<&'a Tile>,
-    grid: Vec<Option<TilePlacement<'a>>>,
+    grid: Grid<Option<TilePlacement<'a>>>,
     edges_map: Cow<'b, EdgeMap<'a>>,
 }
 
 #[derive(PartialEq, Eq, Clone)]
-struct ImageSolution<'a> {
-    size: usize,
-    grid: Vec<TilePlacement<'a>>,
-}
+struct ImageSolution<'a>(Grid<TilePlacement<'a>>);
 
 #[derive(PartialEq, Eq, Copy, Clone, Hash)]
-pub struct TilePlacement<'a>(&'a Tile, Transformation);
+pub struct TilePlacement<'a>(&'a Tile, Transform);
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Direction {
@@ -50,22 +44,19 @@ pub enum Direction {
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Image {
-    size: usize,
-    data: Vec<bool>,
-}
+pub struct Image(Grid<bool>);
 
 type EdgeMap<'a> = HashMap<Vec<bool>, Vec<(TilePlacement<'a>, Direction)>>;
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(20).unwrap();
     static ref TILE_REGEX: Regex = Regex::new(r"^Tile ([0-9]+):$").unwrap();
     static ref TEST_INPUT: Vec<Tile> =
         parse_input(&puzzle_input::lines(include_str!("test_input.txt"))).unwrap();
-    static ref PUZZLE_INPUT: Vec<Tile> =
-        parse_input(&puzzle_input::lines(include_str!("puzzle_input.txt"))).unwrap();
+    static ref PUZZLE_INPUT: Vec<Tile> = parse_input(&puzzle_input::lines(&RAW_INPUT)).unwrap();
     static ref TEST_IMAGE: Image =
         Image::parse(&puzzle_input::lines(include_str!("test_image.txt"))).unwrap();
-    static ref SEA_MONSTER: Vec<(usize, usize)> = include_str!("sea_monster.txt")
+    static ref SEA_MONSTER: Vec<Point> = include_str!("sea_monster.txt")
         .lines()
         .enumerate()
         .flat_map(|(y, line)| line
@@ -75,63 +66,68 @@ lazy_static! {
         .collect();
     static ref SEA_MONSTER_LENGTH: usize = SEA_MONSTER.iter().map(|(x, _)| x + 1).max().unwrap();
     static ref SEA_MONSTER_HEIGHT: usize = SEA_MONSTER.iter().map(|(_, y)| y + 1).max().unwrap();
+    static ref PALETTE: Palette<Water> = Palette::new(Rgb::hex(0x0d47a1))
+        .with(Water::Rough, Rgb::hex(0x90caf9))
+        .with(Water::SeaMonster, Rgb::hex(0x76ff03));
 }
 
-pub fn parse_input(input: &[&str]) -> anyhow::Result<Vec<Tile>> {
-    input
-        .split(|line| line.is_empty())
-        .map(|section| Tile::parse(section))
+pub fn parse_input(input: &[&str]) -> Result<Vec<Tile>, ParseError> {
+    puzzle_input::sections(input)
+        .into_iter()
+        .map(Tile::parse)
         .collect()
 }
 
-impl Tile {
-    pub fn parse(section: &[&str]) -> anyhow::Result<Tile> {
-        let first_line = section.get(0).ok_or(anyhow!("empty section"))?;
-        let tile_id = TILE_REGEX
-            .captures(first_line)
-            .ok_or(anyhow!("invalid header line"))
-            .and_then(|captures| captures[1].parse::<u64>().map_err(|err| err.into()))?;
-
-        let size = section.len() - 1;
+/// Parses a square of `.` and `#`
+fn parse_pixels(rows: puzzle_input::Section) -> Result<Grid<bool>, ParseError> {
+    let grid = rows.char_grid("`.` or `#`", |char| match char {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+
+    let size = grid.width();
+    if grid.height() != size {
+        let last = rows.lines.last().copied().unwrap_or_default();
+        return Err(
+            ParseError::at_end(last, format!("{} rows, to make a square", size))
+                .on_line(rows.first_line + rows.lines.len() - 1),
+        );
+    }
 
-        if !section.iter().skip(1).all(|line| line.len() == size) {
-            return Err(anyhow!("Tile isn't square"));
-        }
+    Ok(grid)
+}
 
-        let data = section
-            .iter()
-            .skip(1)
-            .flat_map(|line| line.chars().map(|char| char == '#'))
-            .collect();
+impl Tile {
+    pub fn parse(section: puzzle_input::Section) -> Result<Tile, ParseError> {
+        let (tile_id, pixels) = section.parse_header("a `Tile <id>:` header", |line| {
+            let captures = TILE_REGEX
+                .captures(line)
+                .ok_or_else(|| ParseError::new(line, "a `Tile <id>:` header"))?;
+            parse_error::parse_at(line, &captures[1], "a tile id")
+        })?;
+        let pixels = parse_pixels(pixels)?;
 
-        Ok(Tile {
-            tile_id,
-            size,
-            data,
-        })
+        Ok(Tile { tile_id, pixels })
     }
 }
 
-impl Transformation {
-    pub fn all() -> impl Iterator<Item = Transformation> {
-        let booleans = || vec![true, false].into_iter();
-        booleans().flat_map(move |rotated| {
-            booleans().flat_map(move |flip_x| {
-                booleans().map(move |flip_y| Transformation {
-                    rotated,
-                    flip_x,
-                    flip_y,
-                })
-            })
-        })
+impl Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Tile {}:", self.tile_id)?;
+        write!(
+            f,
+            "{}",
+            self.pixels.display_with(|&x| if x { '#' } else { '.' })
+        )
     }
 }
 
-fn all_edges(tiles: &[Tile]) -> EdgeMap {
+fn all_edges(tiles: &[Tile]) -> EdgeMap<'_> {
     tiles
         .par_iter()
         .flat_map_iter(|tile| {
-            Transformation::all().flat_map(move |transform| {
+            Transform::all().flat_map(move |transform| {
                 let placement = TilePlacement(tile, transform);
                 Direction::all().map(move |direction| {
                     let edge = placement.edge(direction);
@@ -162,47 +158,39 @@ fn all_edges(tiles: &[Tile]) -> EdgeMap {
 
 impl<'a, 'b> ImageSolvingData<'a, 'b> {
     fn new(tiles: &'a [Tile]) -> anyhow::Result<ImageSolvingData<'a, 'a>> {
-        let size = (tiles.len() as f64).sqrt().floor();
-        if size.fract() > 0.001 {
+        let size = (tiles.len() as f64).sqrt().round() as usize;
+        if size * size != tiles.len() {
             return Err(anyhow!("Tiles do not fit into a square"));
         }
-        let size = size as usize;
 
         let edges_map = all_edges(tiles);
 
         Ok(ImageSolvingData {
-            size,
-            grid: iter::repeat(None).take(tiles.len()).collect(),
+            grid: Grid::filled(size, size, None),
             remaining_tiles: tiles.iter().collect(),
             edges_map: Cow::Owned(edges_map),
         })
     }
 
-    fn fill(&self) -> Vec<ImageSolution<'a>> {
-        let first_unfilled_tile = self.grid.iter().enumerate().find_map(|(i, placement)| {
-            if let None = placement {
-                Some(i)
-            } else {
-                None
-            }
-        });
+    /// Every way to finish the image. Checks for cancellation at each tile it
+    /// tries, passing the token on to rayon's threads.
+    fn fill(&self) -> anyhow::Result<Vec<ImageSolution<'a>>> {
+        cancel::check()?;
+        let first_unfilled_tile = self.grid.iter().find(|(_, placement)| placement.is_none());
 
-        let first_unfilled_tile = if let Some(x) = first_unfilled_tile {
-            x
+        let (x, y) = if let Some((point, _)) = first_unfilled_tile {
+            point
         } else {
-            return vec![self.solution().unwrap()];
+            return Ok(vec![self.solution().unwrap()]);
         };
 
-        let (x, y) = self.index_to_coord(first_unfilled_tile);
-
         let matches = self.matches_for(x, y);
 
         let next_images = matches.into_par_iter().map(|placement| ImageSolvingData {
-            size: self.size,
             grid: {
-                let mut x = self.grid.clone();
-                x[first_unfilled_tile] = Some(placement);
-                x
+                let mut grid = self.grid.clone();
+                grid[(x, y)] = Some(placement);
+                grid
             },
             remaining_tiles: {
                 let x = self
@@ -219,15 +207,16 @@ impl<'a, 'b> ImageSolvingData<'a, 'b> {
             },
         });
 
-        next_images.flat_map(|image| image.fill()).collect()
+        let token = cancel::current();
+        let filled: Vec<_> = next_images
+            .map(|image| cancel::with_token(&token, || image.fill()))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(filled.into_iter().flatten().collect())
     }
 
     fn solution(&self) -> Option<ImageSolution<'a>> {
-        if self.grid.iter().all(|x| x.is_some()) {
-            Some(ImageSolution {
-                size: self.size,
-                grid: self.grid.iter().map(|x| *x.as_ref().unwrap()).collect(),
-            })
+        if self.grid.cells().all(|x| x.is_some()) {
+            Some(ImageSolution(self.grid.map(|_, x| x.unwrap())))
         } else {
             None
         }
@@ -283,7 +272,7 @@ impl<'a, 'b> ImageSolvingData<'a, 'b> {
                     .remaining_tiles
                     .iter()
                     .flat_map(|tile| {
-                        Transformation::all().map(move |transformation| {
+                        Transform::all().map(move |transformation| {
                             TilePlacement(tile.to_owned(), transformation)
                         })
                     })
@@ -301,95 +290,54 @@ impl<'a, 'b> ImageSolvingData<'a, 'b> {
             .collect()
     }
 
-    fn tile_at(&self, x: usize, y: usize) -> Option<&TilePlacement> {
-        if x > self.size {
-            return None;
-        }
-
-        let index = y * self.size + x;
-        if index >= self.grid.len() {
-            return None;
-        }
-
-        (&self.grid[index]).into()
-    }
-
-    fn index_to_coord(&self, index: usize) -> (usize, usize) {
-        let x = index % self.size;
-        let y = index / self.size;
-        (x, y)
+    fn tile_at(&self, x: usize, y: usize) -> Option<&TilePlacement<'a>> {
+        self.grid.get((x, y)).and_then(Option::as_ref)
     }
 }
 
 impl ImageSolution<'_> {
-    fn tile_at(&self, x: usize, y: usize) -> Option<&TilePlacement> {
-        if x > self.size {
-            return None;
-        }
-
-        let index = y * self.size + x;
-        if index >= self.grid.len() {
-            return None;
-        }
+    fn size(&self) -> usize {
+        self.0.width()
+    }
 
-        self.grid.get(index)
+    fn tile_at(&self, x: usize, y: usize) -> Option<&TilePlacement<'_>> {
+        self.0.get((x, y))
     }
 
     fn image(&self) -> Image {
-        let tile_size = self.grid[0].0.size;
-        let all_pixels: Vec<bool> = (0..self.size * tile_size)
-            .flat_map(|y| {
-                (0..self.size * tile_size).map(move |x| {
-                    let grid_x = x / tile_size;
-                    let grid_y = y / tile_size;
-                    let tile_x = x % tile_size;
-                    let tile_y = y % tile_size;
-                    // trim out the edges of each tile
-                    if tile_x == 0
-                        || tile_x == tile_size - 1
-                        || tile_y == 0
-                        || tile_y == tile_size - 1
-                    {
-                        None
-                    } else {
-                        Some(
-                            self.tile_at(grid_x, grid_y)
-                                .unwrap()
-                                .pixel_at(tile_x, tile_y),
-                        )
-                    }
-                })
-            })
-            .filter_map(|x| x)
-            .collect();
-
-        let image_size = self.size * (tile_size - 2);
+        let inner_size = self.0[(0, 0)].0.pixels.width() - 2;
+        // trim out the edges of each tile
+        let trimmed = self.0.map(|_, TilePlacement(tile, transformation)| {
+            tile.pixels
+                .transformed(*transformation)
+                .sub_grid((1, 1), inner_size, inner_size)
+        });
 
-        Image {
-            data: all_pixels,
-            size: image_size,
-        }
+        let image_size = self.size() * inner_size;
+        Image(Grid::from_fn(image_size, image_size, |(x, y)| {
+            trimmed[(x / inner_size, y / inner_size)][(x % inner_size, y % inner_size)]
+        }))
     }
 }
 
 impl TilePlacement<'_> {
     fn right_edge(&self) -> Vec<bool> {
-        let size = self.0.size;
+        let size = self.0.pixels.width();
         (0..size).map(|y| self.pixel_at(size - 1, y)).collect()
     }
 
     fn left_edge(&self) -> Vec<bool> {
-        let size = self.0.size;
+        let size = self.0.pixels.width();
         (0..size).map(|y| self.pixel_at(0, y)).collect()
     }
 
     fn top_edge(&self) -> Vec<bool> {
-        let size = self.0.size;
+        let size = self.0.pixels.width();
         (0..size).map(|x| self.pixel_at(x, 0)).collect()
     }
 
     fn bottom_edge(&self) -> Vec<bool> {
-        let size = self.0.size;
+        let size = self.0.pixels.width();
         (0..size).map(|x| self.pixel_at(x, size - 1)).collect()
     }
 
@@ -402,35 +350,16 @@ impl TilePlacement<'_> {
         }
     }
 
-    fn pixel_at(&self, mut x: usize, mut y: usize) -> bool {
-        let size = self.0.size;
-        let transformation = self.1;
-        if transformation.flip_x {
-            x = (size - 1) - x;
-        }
-        if transformation.flip_y {
-            y = (size - 1) - y;
-        }
-        if transformation.rotated {
-            let (new_x, new_y) = ((size - 1) - y, x);
-            x = new_x;
-            y = new_y;
-        }
-
-        let index = y * size + x;
-        self.0.data[index]
+    fn pixel_at(&self, x: usize, y: usize) -> bool {
+        *self.0.pixels.get_transformed(self.1, (x, y)).unwrap()
     }
 
     pub fn display_tile(&self) -> String {
-        (0..self.0.size)
-            .map(|y| {
-                (0..self.0.size)
-                    .map(|x| if self.pixel_at(x, y) { "#" } else { "." })
-                    .collect::<Vec<&str>>()
-                    .concat()
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
+        self.0
+            .pixels
+            .transformed(self.1)
+            .display_with(|&x| if x { '#' } else { '.' })
+            .to_string()
     }
 }
 
@@ -456,33 +385,42 @@ impl Direction {
     }
 }
 
+impl Display for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display_with(|&x| if x { '#' } else { '.' }))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Water {
+    Calm,
+    Rough,
+    SeaMonster,
+}
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct WaterRoughness {
     monsters_found: usize,
     roughness_rating: usize,
 }
 
-impl Image {
-    pub fn parse(input: &[&str]) -> anyhow::Result<Self> {
-        let size = input.len();
-
-        if !input.iter().all(|line| line.len() == size) {
-            return Err(anyhow!("Image isn't square"));
-        }
-
-        let data = input
-            .iter()
-            .flat_map(|line| line.chars().map(|char| char == '#'))
-            .collect();
+/// The answer is just the rating
+impl Display for WaterRoughness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.roughness_rating)
+    }
+}
 
-        Ok(Image { data, size })
+impl Image {
+    pub fn parse(input: &[&str]) -> Result<Self, ParseError> {
+        Ok(Image(parse_pixels(puzzle_input::Section::new(input))?))
     }
 
-    fn find_sea_monsters(&self) -> Vec<(usize, usize)> {
-        (0..self.size - *SEA_MONSTER_LENGTH)
+    fn find_sea_monsters(&self) -> Vec<Point> {
+        (0..self.0.width() - *SEA_MONSTER_LENGTH)
             .into_par_iter()
             .flat_map(|x| {
-                (0..self.size - *SEA_MONSTER_HEIGHT)
+                (0..self.0.height() - *SEA_MONSTER_HEIGHT)
                     .into_par_iter()
                     .filter_map(move |y| {
                         if self.sea_monster_at(x, y) {
@@ -506,48 +444,41 @@ impl Image {
         })
     }
 
-    fn index_to_coord(&self, index: usize) -> (usize, usize) {
-        let x = index % self.size;
-        let y = index / self.size;
-        (x, y)
-    }
-
     pub fn pixel_at(&self, x: usize, y: usize) -> bool {
-        let index = y * self.size + x;
-        self.data[index]
+        self.0[(x, y)]
     }
 
     pub fn display(&self) -> String {
-        (0..self.size)
-            .map(|y| {
-                (0..self.size)
-                    .map(|x| if self.pixel_at(x, y) { "#" } else { "." })
-                    .collect::<Vec<&str>>()
-                    .concat()
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
+        self.to_string()
     }
 
-    pub fn roughness(&self) -> WaterRoughness {
-        let monsters = self.find_sea_monsters();
-        let monster_parts: HashSet<(usize, usize)> = monsters
+    fn monster_parts(monsters: &[Point]) -> HashSet<Point> {
+        monsters
             .iter()
             .flat_map(|&(x, y)| {
                 SEA_MONSTER
                     .iter()
                     .map(move |&(monster_x, monster_y)| (x + monster_x, y + monster_y))
             })
-            .collect();
+            .collect()
+    }
+
+    fn water(&self, monster_parts: &HashSet<Point>) -> Grid<Water> {
+        self.0.map(|point, &rough| match rough {
+            _ if monster_parts.contains(&point) => Water::SeaMonster,
+            true => Water::Rough,
+            false => Water::Calm,
+        })
+    }
+
+    pub fn roughness(&self) -> WaterRoughness {
+        let monsters = self.find_sea_monsters();
+        let monster_parts = Self::monster_parts(&monsters);
 
         let roughness = self
-            .data
+            .0
             .iter()
-            .enumerate()
-            .filter(|&(i, &pixel)| {
-                let (x, y) = self.index_to_coord(i);
-                pixel && !monster_parts.contains(&(x, y))
-            })
+            .filter(|&(point, &pixel)| pixel && !monster_parts.contains(&point))
             .count();
 
         WaterRoughness {
@@ -560,16 +491,16 @@ impl Image {
 pub fn get_corner_ids(tiles: &[Tile]) -> anyhow::Result<HashSet<u64>> {
     let image = ImageSolvingData::new(tiles)?;
 
-    let final_image = image.fill().into_iter().find(|_| true);
+    let final_image = image.fill()?.into_iter().next();
 
     final_image
         .ok_or(anyhow!("Solution not found"))
         .map(|final_image| {
             vec![
                 final_image.tile_at(0, 0),
-                final_image.tile_at(final_image.size - 1, 0),
-                final_image.tile_at(0, final_image.size - 1),
-                final_image.tile_at(final_image.size - 1, final_image.size - 1),
+                final_image.tile_at(final_image.size() - 1, 0),
+                final_image.tile_at(0, final_image.size() - 1),
+                final_image.tile_at(final_image.size() - 1, final_image.size() - 1),
             ]
             .into_iter()
             .map(|x| match x {
@@ -584,19 +515,72 @@ pub fn get_images(tiles: &[Tile]) -> anyhow::Result<Vec<Image>> {
     let solver = ImageSolvingData::new(tiles)?;
 
     Ok(solver
-        .fill()
+        .fill()?
         .into_iter()
         .map(|solver| solver.image())
         .collect())
 }
 
-pub fn get_roughness(tiles: &[Tile]) -> anyhow::Result<usize> {
-    Ok(get_images(tiles)?
+/// The roughness of the image turned to where the sea monsters are
+pub fn get_water_roughness(tiles: &[Tile]) -> anyhow::Result<WaterRoughness> {
+    get_images(tiles)?
         .into_iter()
         .map(|img| img.roughness())
         .max_by_key(|&WaterRoughness { monsters_found, .. }| monsters_found)
-        .ok_or(anyhow!("Couldn't find any monsters"))?
-        .roughness_rating)
+        .ok_or(anyhow!("Couldn't find any monsters"))
+}
+
+pub fn get_roughness(tiles: &[Tile]) -> anyhow::Result<usize> {
+    Ok(get_water_roughness(tiles)?.roughness_rating)
+}
+
+pub struct Day20;
+
+impl Puzzle for Day20 {
+    const DAY: u8 = 20;
+    const TITLE: &'static str = "Jurassic Jigsaw";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<Tile>;
+    type PartOne = u64;
+    type PartTwo = WaterRoughness;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_input(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Ok(get_corner_ids(input)?.iter().product())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<WaterRoughness> {
+        get_water_roughness(input)
+    }
+}
+
+impl Extras for Day20 {
+    fn extras(_: &Vec<Tile>, answer: &Answer<Self>) -> anyhow::Result<Value> {
+        Ok(match answer {
+            Answer::One(_) => Value::Null,
+            Answer::Two(roughness) => json!({ "monsters_found": roughness.monsters_found }),
+        })
+    }
+}
+
+impl Render for Day20 {
+    /// The assembled image. Part two turns it to where the sea monsters are
+    /// and picks them out.
+    fn render(input: &Vec<Tile>, part: Part, frames: &mut dyn Frames) -> anyhow::Result<()> {
+        let images = get_images(input)?;
+        let water = match part {
+            Part::One => images.first().map(|x| x.water(&HashSet::new())),
+            Part::Two => images
+                .iter()
+                .map(|x| (x, x.find_sea_monsters()))
+                .max_by_key(|(_, monsters)| monsters.len())
+                .map(|(x, monsters)| x.water(&Image::monster_parts(&monsters))),
+        };
+        frames.push(PALETTE.paint(&water.ok_or(anyhow!("Solution not found"))?))
+    }
 }
 
 #[cfg(test)]
@@ -610,10 +594,24 @@ mod part_one {
             .concat()
     }
 
+    #[test]
+    fn round_trip() {
+        let parse = |x: &str| Tile::parse(puzzle_input::Section::new(&puzzle_input::lines(x)));
+        let lines = puzzle_input::lines(include_str!("test_input.txt"));
+        for section in puzzle_input::sections(&lines) {
+            let tile = section.lines.join("\n");
+            assert_eq!(puzzle_input::assert_round_trip(&tile, parse), tile);
+        }
+
+        let image = include_str!("test_image.txt").trim();
+        let parse = |x: &str| Image::parse(&puzzle_input::lines(x));
+        assert_eq!(puzzle_input::assert_round_trip(image, parse), image);
+    }
+
     #[test]
     fn test_edges() {
         let test_tile = TEST_INPUT.iter().find(|x| x.tile_id == 2311).unwrap();
-        let placement = TilePlacement(test_tile, Transformation::default());
+        let placement = TilePlacement(test_tile, Transform::default());
 
         assert_eq!(edge_to_string(&placement.top_edge()), "..##.#..#.");
         assert_eq!(edge_to_string(&placement.bottom_edge()), "..###..###");
@@ -621,15 +619,21 @@ mod part_one {
         assert_eq!(edge_to_string(&placement.right_edge()), "...#.##..#");
     }
 
+    #[test]
+    fn needs_a_square_of_tiles() {
+        assert!(ImageSolvingData::new(&TEST_INPUT[..8]).is_err());
+        assert_eq!(ImageSolvingData::new(&TEST_INPUT).unwrap().grid.width(), 3);
+    }
+
     #[test]
     fn test_selection() {
         let mut test_image = ImageSolvingData::new(TEST_INPUT.as_slice()).unwrap();
 
         let test_tile = TEST_INPUT.iter().find(|x| x.tile_id == 1951).unwrap();
 
-        test_image.grid[0] = Some(TilePlacement(
+        test_image.grid[(0, 0)] = Some(TilePlacement(
             test_tile,
-            Transformation {
+            Transform {
                 flip_y: true,
                 ..Default::default()
             },
@@ -659,6 +663,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(20);
         let result = get_corner_ids(PUZZLE_INPUT.as_slice()).unwrap();
         assert_eq!(result.iter().product::<u64>(), 54755174472007);
     }
@@ -670,7 +675,7 @@ mod part_two {
 
     #[test]
     fn test_sea_monster_parse() {
-        let in_sea_monster: HashSet<(usize, usize)> = SEA_MONSTER.iter().copied().collect();
+        let in_sea_monster: HashSet<Point> = SEA_MONSTER.iter().copied().collect();
         let reconstituted_sea_monster = (0..*SEA_MONSTER_HEIGHT)
             .map(|y| {
                 (0..*SEA_MONSTER_LENGTH)
@@ -722,8 +727,26 @@ mod part_two {
         assert_eq!(get_roughness(TEST_INPUT.as_slice()).unwrap(), 273);
     }
 
+    #[test]
+    fn can_be_cancelled() {
+        let token = cancel::Token::new();
+        token.cancel();
+        let result = cancel::with_token(&token, || get_water_roughness(&TEST_INPUT));
+        assert!(result.unwrap_err().is::<cancel::Cancelled>());
+    }
+
+    #[test]
+    fn render() {
+        let mut frames = Vec::new();
+        Day20::render(&TEST_INPUT, Part::Two, &mut frames).unwrap();
+        let monster = PALETTE.color(&Water::SeaMonster);
+        let highlighted = frames[0].cells().filter(|&&x| x == monster).count();
+        assert_eq!(highlighted, 2 * SEA_MONSTER.len());
+    }
+
     #[test]
     fn answer() {
+        shared::skip_without_input!(20);
         assert_eq!(get_roughness(PUZZLE_INPUT.as_slice()).unwrap(), 1692);
     }
 }
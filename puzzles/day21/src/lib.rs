@@ -1,8 +1,16 @@
 // Day 21: Allergen Assessment
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
 
-use shared::prelude::*;
+use shared::{
+    extras::{json, Extras, Value},
+    prelude::*,
+    puzzle::Answer,
+    trace::{self, Event, Explain, NoopTracer, Tracer},
+};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct FoodLabel<'a> {
@@ -11,24 +19,20 @@ pub struct FoodLabel<'a> {
 }
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(21).unwrap();
     static ref FOOD_LABEL_REGEX: Regex =
         Regex::new(r"^([a-z ]+?) \(contains ([a-z, ]+)\)$").unwrap();
-    static ref TEST_INPUT: Vec<FoodLabel<'static>> =
-        puzzle_input::lines(include_str!("test_input.txt"))
-            .into_iter()
-            .map(|x| FoodLabel::parse(x))
-            .collect::<Result<_, _>>()
-            .unwrap();
+    static ref TEST_INPUT: Vec<FoodLabel<'static>> = parse_error::parse_lines(
+        &puzzle_input::lines(include_str!("test_input.txt")),
+        FoodLabel::parse
+    )
+    .unwrap();
     static ref PUZZLE_INPUT: Vec<FoodLabel<'static>> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"))
-            .into_iter()
-            .map(|x| FoodLabel::parse(x))
-            .collect::<Result<_, _>>()
-            .unwrap();
+        parse_error::parse_lines(&puzzle_input::lines(&RAW_INPUT), FoodLabel::parse).unwrap();
 }
 
 impl FoodLabel<'_> {
-    pub fn parse<'a>(s: &'a str) -> anyhow::Result<FoodLabel<'a>> {
+    pub fn parse<'a>(s: &'a str) -> Result<FoodLabel<'a>, ParseError> {
         let captures = FOOD_LABEL_REGEX.captures(s);
         if let Some(captures) = captures {
             let ingredients = captures.get(1).unwrap().as_str().split(" ").collect();
@@ -38,17 +42,38 @@ impl FoodLabel<'_> {
                 allergens,
             })
         } else {
-            Err(anyhow!("invalid label: {}", s))
+            Err(ParseError::new(
+                s,
+                "a label like `mxmxvkd kfcds (contains dairy, fish)`",
+            ))
         }
     }
 }
 
+impl Display for FoodLabel<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (contains {})",
+            self.ingredients.join(" "),
+            self.allergens.join(", ")
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Solution<'a> {
     allergens_for_ingredients_map: HashMap<&'a str, Option<&'a str>>,
 }
 impl Solution<'_> {
     fn solve<'a>(labels: &'a [FoodLabel<'a>]) -> anyhow::Result<Solution<'a>> {
+        Solution::solve_traced(labels, &mut NoopTracer)
+    }
+
+    fn solve_traced<'a, T: Tracer + ?Sized>(
+        labels: &'a [FoodLabel<'a>],
+        tracer: &mut T,
+    ) -> anyhow::Result<Solution<'a>> {
         let allergen_map = labels
             .iter()
             .flat_map(
@@ -85,8 +110,32 @@ impl Solution<'_> {
             .map(|(k, v)| (k, v.into_iter().collect()))
             .collect();
 
-        fn iterate_solutions<'a>(
+        // Maps iterate in any order, so sort what's traced to read the same every time
+        fn trace_changes<T: Tracer + ?Sized>(
+            before: Option<&HashMap<&str, Vec<&str>>>,
+            after: &HashMap<&str, Vec<&str>>,
+            tracer: &mut T,
+        ) {
+            if !tracer.enabled() {
+                return;
+            }
+            let mut allergens: Vec<&str> = after.keys().copied().collect();
+            allergens.sort_unstable();
+            for allergen in allergens {
+                let candidates = &after[allergen];
+                let changed = before.is_none_or(|x| x[allergen].len() > candidates.len());
+                if changed && candidates.len() == 1 {
+                    trace::emit(tracer, || Event::resolved(allergen, candidates[0]));
+                } else if changed {
+                    trace::emit(tracer, || Event::narrowed(allergen, candidates));
+                }
+            }
+        }
+        trace_changes(None, &allergen_map, tracer);
+
+        fn iterate_solutions<'a, T: Tracer + ?Sized>(
             allergen_map: HashMap<&'a str, Vec<&'a str>>,
+            tracer: &mut T,
         ) -> Option<HashMap<&'a str, Vec<&'a str>>> {
             let solved_allergens: HashSet<&str> = allergen_map
                 .iter()
@@ -126,14 +175,15 @@ impl Solution<'_> {
                     .collect();
 
                 if new_map != allergen_map {
-                    iterate_solutions(new_map)
+                    trace_changes(Some(&allergen_map), &new_map, tracer);
+                    iterate_solutions(new_map, tracer)
                 } else {
                     None
                 }
             }
         }
 
-        let resulting_map = iterate_solutions(allergen_map);
+        let resulting_map = iterate_solutions(allergen_map, tracer);
         let resulting_map = resulting_map.ok_or(anyhow!("Couldn't find a solution"))?;
         let inverted_map: HashMap<&str, &str> = resulting_map
             .into_iter()
@@ -162,7 +212,28 @@ pub struct SafeIngredients<'a> {
 }
 impl SafeIngredients<'_> {
     pub fn solve<'a>(labels: &'a [FoodLabel<'a>]) -> anyhow::Result<SafeIngredients<'a>> {
-        let solution = Solution::solve(labels)?;
+        Ok(SafeIngredients::from_solution(
+            labels,
+            Solution::solve(labels)?,
+        ))
+    }
+
+    /// Like [`SafeIngredients::solve`], telling `tracer` how each allergen's
+    /// ingredient was found
+    pub fn solve_traced<'a, T: Tracer + ?Sized>(
+        labels: &'a [FoodLabel<'a>],
+        tracer: &mut T,
+    ) -> anyhow::Result<SafeIngredients<'a>> {
+        Ok(SafeIngredients::from_solution(
+            labels,
+            Solution::solve_traced(labels, tracer)?,
+        ))
+    }
+
+    fn from_solution<'a>(
+        labels: &'a [FoodLabel<'a>],
+        solution: Solution<'a>,
+    ) -> SafeIngredients<'a> {
         let safe_ingredients: HashSet<&str> = solution
             .allergens_for_ingredients_map
             .iter()
@@ -178,15 +249,27 @@ impl SafeIngredients<'_> {
             .filter(|ingredient| safe_ingredients.contains(ingredient))
             .count();
 
-        Ok(SafeIngredients {
+        SafeIngredients {
             ingredients: safe_ingredients,
             count,
-        })
+        }
     }
 }
 
 pub fn canonical_dangerous_ingredient_list(labels: &[FoodLabel]) -> anyhow::Result<String> {
-    let solution = Solution::solve(labels)?;
+    Ok(dangerous_ingredient_list(Solution::solve(labels)?))
+}
+
+pub fn canonical_dangerous_ingredient_list_traced<T: Tracer + ?Sized>(
+    labels: &[FoodLabel],
+    tracer: &mut T,
+) -> anyhow::Result<String> {
+    Ok(dangerous_ingredient_list(Solution::solve_traced(
+        labels, tracer,
+    )?))
+}
+
+fn dangerous_ingredient_list(solution: Solution<'_>) -> String {
     let mut dangerous_ingredients: Vec<(&str, &str)> = solution
         .allergens_for_ingredients_map
         .iter()
@@ -197,11 +280,68 @@ pub fn canonical_dangerous_ingredient_list(labels: &[FoodLabel]) -> anyhow::Resu
         .collect();
     dangerous_ingredients.sort_by_key(|(_, allergen)| *allergen);
 
-    Ok(dangerous_ingredients
+    dangerous_ingredients
         .into_iter()
         .map(|(ingredient, _)| ingredient)
         .collect::<Vec<&str>>()
-        .join(","))
+        .join(",")
+}
+
+pub struct Day21;
+
+impl Puzzle for Day21 {
+    const DAY: u8 = 21;
+    const TITLE: &'static str = "Allergen Assessment";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<FoodLabel<'a>>;
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_error::parse_lines(
+            &puzzle_input::lines(input),
+            FoodLabel::parse,
+        )?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(SafeIngredients::solve(input)?.count)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<String> {
+        canonical_dangerous_ingredient_list(input)
+    }
+}
+
+impl Extras for Day21 {
+    fn extras(input: &Vec<FoodLabel<'_>>, answer: &Answer<Self>) -> anyhow::Result<Value> {
+        Ok(match answer {
+            Answer::One(_) => {
+                let mut ingredients: Vec<_> = SafeIngredients::solve(input)?
+                    .ingredients
+                    .into_iter()
+                    .collect();
+                ingredients.sort_unstable();
+                json!({ "safe_ingredients": ingredients })
+            }
+            Answer::Two(_) => Value::Null,
+        })
+    }
+}
+
+impl Explain for Day21 {
+    fn explain(
+        input: &Vec<FoodLabel<'_>>,
+        part: Part,
+        tracer: &mut dyn Tracer,
+    ) -> anyhow::Result<String> {
+        match part {
+            Part::One => Ok(SafeIngredients::solve_traced(input, tracer)?
+                .count
+                .to_string()),
+            Part::Two => canonical_dangerous_ingredient_list_traced(input, tracer),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -209,6 +349,17 @@ mod part_one {
 
     use super::*;
 
+    #[test]
+    fn round_trip() {
+        // Labels borrow their text, so this can't use `assert_round_trip`
+        for line in puzzle_input::lines(include_str!("test_input.txt")) {
+            let label = FoodLabel::parse(line).unwrap();
+            let printed = label.to_string();
+            assert_eq!(printed, line);
+            assert_eq!(FoodLabel::parse(&printed).unwrap(), label);
+        }
+    }
+
     #[test]
     fn can_find_solution() {
         let solution = Solution::solve(TEST_INPUT.as_slice());
@@ -227,6 +378,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(21);
         let result = SafeIngredients::solve(PUZZLE_INPUT.as_slice()).unwrap();
         assert_eq!(result.count, 2072);
     }
@@ -242,8 +394,25 @@ mod part_two {
             "mxmxvkd,sqjhc,fvjkl".to_string()
         );
     }
+
+    #[test]
+    fn traces_deductions() {
+        let mut events = Vec::new();
+        canonical_dangerous_ingredient_list_traced(TEST_INPUT.as_slice(), &mut events).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event::resolved("dairy", "mxmxvkd"),
+                Event::narrowed("fish", vec!["mxmxvkd", "sqjhc"]),
+                Event::narrowed("soy", vec!["fvjkl", "sqjhc"]),
+                Event::resolved("fish", "sqjhc"),
+                Event::resolved("soy", "fvjkl"),
+            ]
+        );
+    }
     #[test]
     fn answer() {
+        shared::skip_without_input!(21);
         assert_eq!(
             canonical_dangerous_ingredient_list(PUZZLE_INPUT.as_slice()).unwrap(),
             "fdsfpg,jmvxx,lkv,cbzcgvc,kfgln,pqqks,pqrvc,lclnj".to_string()
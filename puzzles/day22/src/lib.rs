@@ -1,7 +1,16 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Display,
+};
 
 // Day 22: Crab Combat
-use shared::prelude::*;
+use shared::{
+    cancel::{self, Cancelled},
+    extras::{json, Extras, Value},
+    prelude::*,
+    puzzle::Answer,
+    simulation::{Animate, Simulation},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Player {
@@ -38,9 +47,9 @@ pub struct GameResult {
 }
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(22).unwrap();
     static ref TEST_INPUT: Vec<&'static str> = puzzle_input::lines(include_str!("test_input.txt"));
-    static ref PUZZLE_INPUT: Vec<&'static str> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"));
+    static ref PUZZLE_INPUT: Vec<&'static str> = puzzle_input::lines(&RAW_INPUT);
 }
 
 impl GameResult {
@@ -58,36 +67,63 @@ impl GameResult {
 }
 
 impl DeckState {
-    pub fn parse(input: &[&str]) -> anyhow::Result<Self> {
-        let sections: Vec<&[&str]> = input.split(|line| line.is_empty()).collect();
+    pub fn parse(input: &[&str]) -> Result<Self, ParseError> {
+        let [player1, player2] = puzzle_input::exact_sections(input, "two decks")?;
+        let deck = |section: puzzle_input::Section, header| {
+            Ok(section
+                .header(header)?
+                .parse_lines(|line| parse_error::parse_at(line, line, "a card number"))?
+                .into())
+        };
 
-        if sections.len() != 2 {
-            return Err(anyhow!("Wrong number of sections"));
-        }
-        if sections[0][0] != "Player 1:" {
-            return Err(anyhow!("Player 1 section is missing"));
-        }
-        if sections[1][0] != "Player 2:" {
-            return Err(anyhow!("Player 2 section is missing"));
-        }
+        Ok(DeckState {
+            player1_cards: deck(player1, "Player 1:")?,
+            player2_cards: deck(player2, "Player 2:")?,
+        })
+    }
+}
 
-        fn parse_section(section: &[&str]) -> anyhow::Result<VecDeque<u32>> {
-            section
+impl DeckState {
+    /// Each deck on one line, top card first
+    fn summary(&self) -> String {
+        let deck = |cards: &VecDeque<u32>| {
+            cards
                 .iter()
-                .skip(1)
-                .map(|line| line.parse().map_err(anyhow::Error::from))
-                .collect()
-        }
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        format!(
+            "Player 1: {}\nPlayer 2: {}",
+            deck(&self.player1_cards),
+            deck(&self.player2_cards)
+        )
+    }
 
-        Ok(DeckState {
-            player1_cards: parse_section(sections[0])?,
-            player2_cards: parse_section(sections[1])?,
-        })
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("player 1 cards", self.player1_cards.len().to_string()),
+            ("player 2 cards", self.player2_cards.len().to_string()),
+        ]
+    }
+}
+
+impl Display for DeckState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Player 1:")?;
+        for card in &self.player1_cards {
+            writeln!(f, "{}", card)?;
+        }
+        writeln!(f, "\nPlayer 2:")?;
+        for card in &self.player2_cards {
+            writeln!(f, "{}", card)?;
+        }
+        Ok(())
     }
 }
 
 impl GameState {
-    pub fn parse(input: &[&str]) -> anyhow::Result<Self> {
+    pub fn parse(input: &[&str]) -> Result<Self, ParseError> {
         Ok(GameState {
             deck_state: DeckState::parse(input)?,
         })
@@ -120,32 +156,36 @@ impl GameState {
         }
     }
 
-    pub fn game_result(mut self) -> GameResult {
+    /// Plays until someone wins, or the game is cancelled. Games without
+    /// recursion can go on forever.
+    pub fn game_result(mut self) -> Result<GameResult, Cancelled> {
         loop {
+            cancel::check()?;
             let round_result = self.round();
             match round_result {
                 RoundResult::Active {
                     winner: _,
                     game_state,
                 } => self = game_state,
-                RoundResult::Finished(result) => return result,
+                RoundResult::Finished(result) => return Ok(result),
             }
         }
     }
 }
 
 impl RecursiveGameState {
-    pub fn parse(input: &[&str]) -> anyhow::Result<Self> {
+    pub fn parse(input: &[&str]) -> Result<Self, ParseError> {
         Ok(RecursiveGameState {
             prev_states: HashSet::new(),
             deck_state: DeckState::parse(input)?,
         })
     }
 
-    pub fn round(mut self) -> RoundResult<Self> {
+    /// Fails if a sub-game is cancelled
+    pub fn round(mut self) -> Result<RoundResult<Self>, Cancelled> {
         let mut deck_state = self.deck_state;
 
-        if self.prev_states.contains(&deck_state) {
+        Ok(if self.prev_states.contains(&deck_state) {
             // infinite loop prevention
             RoundResult::Finished(GameResult::new(&deck_state, Player::Player1))
         } else if deck_state.player1_cards.len() == 0 {
@@ -181,7 +221,7 @@ impl RecursiveGameState {
                     prev_states: HashSet::new(),
                 };
 
-                subgame.game_result().winner
+                subgame.game_result()?.winner
             } else if player1_card > player2_card {
                 Player::Player1
             } else {
@@ -204,21 +244,65 @@ impl RecursiveGameState {
                 game_state: self,
                 winner,
             }
-        }
+        })
     }
 
-    pub fn game_result(mut self) -> GameResult {
+    pub fn game_result(mut self) -> Result<GameResult, Cancelled> {
         loop {
-            let round_result = self.round();
+            cancel::check()?;
+            let round_result = self.round()?;
             match round_result {
                 RoundResult::Active {
                     winner: _,
                     game_state,
                 } => self = game_state,
-                RoundResult::Finished(result) => return result,
+                RoundResult::Finished(result) => return Ok(result),
+            }
+        }
+    }
+}
+
+impl Simulation for GameState {
+    fn step(&mut self) -> bool {
+        match self.clone().round() {
+            RoundResult::Active { game_state, .. } => {
+                *self = game_state;
+                true
+            }
+            RoundResult::Finished(_) => false,
+        }
+    }
+
+    fn draw(&self) -> String {
+        self.deck_state.summary()
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        self.deck_state.counters()
+    }
+}
+
+/// Sub-games are played out within a single step
+impl Simulation for RecursiveGameState {
+    fn step(&mut self) -> bool {
+        match self.clone().round() {
+            Ok(RoundResult::Active { game_state, .. }) => {
+                *self = game_state;
+                true
             }
+            Ok(RoundResult::Finished(_)) | Err(Cancelled) => false,
         }
     }
+
+    fn draw(&self) -> String {
+        self.deck_state.summary()
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let mut counters = self.deck_state.counters();
+        counters.push(("rounds played", self.prev_states.len().to_string()));
+        counters
+    }
 }
 
 pub fn score_deck(deck: &[u32]) -> u32 {
@@ -229,22 +313,97 @@ pub fn score_deck(deck: &[u32]) -> u32 {
         .sum()
 }
 
+/// The answer is the winning deck's score
+impl Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", score_deck(&self.winning_deck))
+    }
+}
+
 pub fn part_one(input: &[&str]) -> anyhow::Result<u32> {
     let game_state = GameState::parse(input)?;
-    let GameResult { winning_deck, .. } = game_state.game_result();
+    let GameResult { winning_deck, .. } = game_state.game_result()?;
     Ok(score_deck(&winning_deck))
 }
 
 pub fn part_two(input: &[&str]) -> anyhow::Result<u32> {
     let game_state = RecursiveGameState::parse(input)?;
-    let GameResult { winning_deck, .. } = game_state.game_result();
+    let GameResult { winning_deck, .. } = game_state.game_result()?;
     Ok(score_deck(&winning_deck))
 }
 
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    const DAY: u8 = 22;
+    const TITLE: &'static str = "Crab Combat";
+    const VERSION: &'static str = "1";
+    type Input<'a> = DeckState;
+    type PartOne = GameResult;
+    type PartTwo = GameResult;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(DeckState::parse(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<GameResult> {
+        let game_state = GameState {
+            deck_state: input.clone(),
+        };
+        Ok(game_state.game_result()?)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<GameResult> {
+        let game_state = RecursiveGameState {
+            prev_states: HashSet::new(),
+            deck_state: input.clone(),
+        };
+        Ok(game_state.game_result()?)
+    }
+}
+
+impl Extras for Day22 {
+    fn extras(_: &DeckState, answer: &Answer<Self>) -> anyhow::Result<Value> {
+        let (Answer::One(result) | Answer::Two(result)) = answer;
+        let winner = match result.winner {
+            Player::Player1 => 1,
+            Player::Player2 => 2,
+        };
+        Ok(json!({ "winner": winner }))
+    }
+}
+
+impl Animate for Day22 {
+    fn simulation<'a>(
+        input: &'a DeckState,
+        part: Part,
+    ) -> anyhow::Result<Box<dyn Simulation + 'a>> {
+        let deck_state = input.clone();
+        Ok(match part {
+            Part::One => Box::new(GameState { deck_state }),
+            Part::Two => Box::new(RecursiveGameState {
+                prev_states: HashSet::new(),
+                deck_state,
+            }),
+        })
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
 
+    #[test]
+    fn round_trip() {
+        let parse = |x: &str| DeckState::parse(&puzzle_input::lines(x));
+        let input = include_str!("test_input.txt");
+        assert_eq!(
+            puzzle_input::assert_round_trip(input, parse).trim_end(),
+            input.trim_end()
+        );
+        puzzle_input::assert_round_trip("Player 1:\n\nPlayer 2:\n1", parse);
+    }
+
     #[test]
     fn test_round() {
         let game_state = GameState::parse(TEST_INPUT.as_slice()).unwrap();
@@ -263,6 +422,37 @@ mod part_one {
         );
     }
 
+    #[test]
+    fn endless_games_can_be_cancelled() {
+        // Nothing stops a game without recursion from going round in circles
+        let game_state =
+            GameState::parse(&["Player 1:", "1", "2", "4", "", "Player 2:", "3", "5"]).unwrap();
+        let token = cancel::Token::new();
+        let canceller = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            canceller.cancel();
+        });
+        assert_eq!(
+            cancel::with_token(&token, || game_state.game_result()),
+            Err(Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_steps() {
+        let mut game_state = GameState::parse(TEST_INPUT.as_slice()).unwrap();
+        let mut rounds = 0;
+        while game_state.step() {
+            rounds += 1;
+        }
+        assert_eq!(rounds, 29);
+        assert_eq!(
+            game_state.draw(),
+            "Player 1: \nPlayer 2: 3, 2, 10, 6, 8, 5, 9, 4, 7, 1"
+        );
+    }
+
     #[test]
     fn test_case() {
         assert_eq!(part_one(TEST_INPUT.as_slice()).unwrap(), 306);
@@ -270,6 +460,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(22);
         assert_eq!(part_one(PUZZLE_INPUT.as_slice()).unwrap(), 31957);
     }
 }
@@ -292,7 +483,7 @@ mod part_two {
         ])
         .unwrap();
 
-        let GameResult { winner, .. } = game_state.game_result();
+        let GameResult { winner, .. } = game_state.game_result().unwrap();
 
         assert_eq!(winner, Player::Player1);
     }
@@ -304,6 +495,7 @@ mod part_two {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(22);
         assert_eq!(part_two(PUZZLE_INPUT.as_slice()).unwrap(), 33212);
     }
 }
@@ -1,13 +1,21 @@
 // Day 23: Crab Cups
 
-use std::str::FromStr;
+use std::{fmt, fmt::Display, str::FromStr};
 
-use shared::prelude::*;
+use shared::{
+    cancel,
+    prelude::*,
+    simulation::{Animate, Simulation},
+};
 
 pub const TEST_INPUT: &str = "389125467";
 pub const PUZZLE_INPUT: &str = "463528179";
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// How often a long game stops to see if it's been cancelled, since part two
+/// makes 10 million moves
+const MOVES_BETWEEN_CHECKS: usize = 1 << 16;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct CrabGame {
     highest_label: u32,
     lowest_label: u32,
@@ -15,16 +23,55 @@ pub struct CrabGame {
     next_cup_map: Vec<u32>,
 }
 
+/// The cups' labels, which have to be each of 1 up to the number of cups
+pub fn parse_cups(s: &str) -> Result<Vec<u32>, ParseError> {
+    let cups = s.chars().count();
+    if cups <= 4 {
+        return Err(ParseError::at_end(s, "at least 5 cups"));
+    }
+
+    let mut taken = vec![false; cups + 1];
+    s.char_indices()
+        .map(|(i, char)| {
+            let at = |expected: String| ParseError::at_column(s, i, char.len_utf8(), expected);
+            let label = char
+                .to_digit(10)
+                .ok_or_else(|| at("a cup label digit".to_string()))?;
+            let slot = taken
+                .get_mut(label as usize)
+                .filter(|_| label != 0)
+                .ok_or_else(|| at(format!("a label from 1 to {}", cups)))?;
+            if *slot {
+                return Err(at(format!("a label other than {}, which is taken", label)));
+            }
+            *slot = true;
+            Ok(label)
+        })
+        .collect()
+}
+
 impl FromStr for CrabGame {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let numbers: Vec<u32> = s
-            .chars()
-            .map(|char| char.to_string().parse())
-            .collect::<Result<_, _>>()?;
+        let numbers = parse_cups(s)?;
 
-        CrabGame::from_list(&numbers)
+        Ok(CrabGame::from_cups(&numbers))
+    }
+}
+
+/// The cups clockwise from the current one, which parses back to the same
+/// game as long as every label is a single digit
+impl Display for CrabGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cup = self.current_cup;
+        loop {
+            write!(f, "{}", cup)?;
+            cup = self.next_cup(cup).unwrap();
+            if cup == self.current_cup {
+                return Ok(());
+            }
+        }
     }
 }
 
@@ -34,6 +81,11 @@ impl CrabGame {
             return Err(anyhow!("Sequence requires at least 4 numbers"));
         }
 
+        Ok(CrabGame::from_cups(numbers))
+    }
+
+    /// Expects at least 5 cups, labelled 1 up to the number of cups
+    fn from_cups(numbers: &[u32]) -> Self {
         let current_cup = numbers[0];
         let highest_label = *numbers.iter().max().unwrap();
         let lowest_label = *numbers.iter().min().unwrap();
@@ -46,12 +98,12 @@ impl CrabGame {
         }
         next_cup_map[*numbers.last().unwrap() as usize] = *numbers.first().unwrap();
 
-        Ok(CrabGame {
+        CrabGame {
             current_cup,
             highest_label,
             lowest_label,
             next_cup_map,
-        })
+        }
     }
 
     pub fn from_list_expanded(numbers: &[u32]) -> anyhow::Result<Self> {
@@ -61,10 +113,7 @@ impl CrabGame {
     }
 
     pub fn from_str_expanded(s: &str) -> anyhow::Result<Self> {
-        let numbers: Vec<u32> = s
-            .chars()
-            .map(|char| char.to_string().parse())
-            .collect::<Result<_, _>>()?;
+        let numbers = parse_cups(s)?;
 
         Self::from_list_expanded(&numbers)
     }
@@ -107,8 +156,14 @@ impl CrabGame {
         self
     }
 
-    pub fn perform_moves(self, moves: usize) -> CrabGame {
-        (0..moves).fold(self, |game, _| game.perform_move())
+    pub fn perform_moves(mut self, moves: usize) -> anyhow::Result<CrabGame> {
+        for i in 0..moves {
+            if i % MOVES_BETWEEN_CHECKS == 0 {
+                cancel::check()?;
+            }
+            self = self.perform_move();
+        }
+        Ok(self)
     }
 
     pub fn cups_after_1(&self) -> CrabGameIterator {
@@ -168,6 +223,97 @@ impl Iterator for CrabGameIterator<'_> {
     }
 }
 
+/// The crab's moves, up to as many as the part asks for
+pub struct CupMoves {
+    game: CrabGame,
+    moves: usize,
+}
+
+impl Simulation for CupMoves {
+    fn step(&mut self) -> bool {
+        let game = std::mem::take(&mut self.game);
+        self.game = game.perform_move();
+        true
+    }
+
+    /// The cups clockwise from the current one, which is in brackets
+    fn draw(&self) -> String {
+        let cups = self.game.next_cup_map.len() - 1;
+        let mut cup = self.game.current_cup;
+        let mut text = format!("({})", cup);
+        for _ in 1..cups.min(30) {
+            cup = self.game.next_cup(cup).unwrap();
+            text += &format!(" {}", cup);
+        }
+        if cups > 30 {
+            text += " ...";
+        }
+        text
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let after_1: Vec<_> = self.game.cups_after_1().take(8).collect();
+        vec![
+            ("current cup", self.game.current_cup.to_string()),
+            (
+                "cups after 1",
+                after_1
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            ("next two multiplied", self.game.output_mk2().to_string()),
+        ]
+    }
+
+    fn target(&self) -> Option<usize> {
+        Some(self.moves)
+    }
+}
+
+pub struct Day23;
+
+impl Puzzle for Day23 {
+    const DAY: u8 = 23;
+    const TITLE: &'static str = "Crab Cups";
+    const VERSION: &'static str = "1";
+    type Input<'a> = Vec<u32>;
+    type PartOne = String;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(parse_cups(input.trim())?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<String> {
+        Ok(CrabGame::from_list(input)?
+            .perform_moves(100)?
+            .output_string())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Ok(CrabGame::from_list_expanded(input)?
+            .perform_moves(10_000_000)?
+            .output_mk2())
+    }
+}
+
+impl Animate for Day23 {
+    fn simulation<'a>(input: &'a Vec<u32>, part: Part) -> anyhow::Result<Box<dyn Simulation + 'a>> {
+        Ok(Box::new(match part {
+            Part::One => CupMoves {
+                game: CrabGame::from_list(input)?,
+                moves: 100,
+            },
+            Part::Two => CupMoves {
+                game: CrabGame::from_list_expanded(input)?,
+                moves: 10_000_000,
+            },
+        }))
+    }
+}
+
 #[cfg(test)]
 mod part_one {
     use super::*;
@@ -180,6 +326,43 @@ mod part_one {
         assert_eq!(game.output_string(), "25467389");
     }
 
+    #[test]
+    fn round_trip() {
+        assert_eq!(
+            puzzle_input::assert_round_trip(TEST_INPUT, CrabGame::from_str),
+            TEST_INPUT
+        );
+        let moved = CrabGame::from_str(TEST_INPUT).unwrap().perform_move();
+        assert_eq!(moved.to_string(), "289154673");
+        assert_eq!(CrabGame::from_str("289154673").unwrap(), moved);
+    }
+
+    #[test]
+    fn rejects_mislabelled_cups() {
+        let err = CrabGame::from_str("0123456789").unwrap_err();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (0, "a label from 1 to 10")
+        );
+        let err = CrabGame::from_str("1123456789").unwrap_err();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (1, "a label other than 1, which is taken")
+        );
+        let err = CrabGame::from_str("13579").unwrap_err();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (3, "a label from 1 to 5")
+        );
+        let err = CrabGame::from_str("3x1").unwrap_err();
+        assert_eq!(err.expected, "at least 5 cups");
+        let err = parse_cups("389x25467").unwrap_err();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (3, "a cup label digit")
+        );
+    }
+
     #[test]
     fn test_move() {
         let game = CrabGame::from_str(TEST_INPUT).unwrap();
@@ -188,19 +371,31 @@ mod part_one {
         assert_eq!(game.output_string(), "54673289");
     }
 
+    #[test]
+    fn test_steps() {
+        let mut moves = CupMoves {
+            game: CrabGame::from_str(TEST_INPUT).unwrap(),
+            moves: 100,
+        };
+        assert_eq!(moves.draw(), "(3) 8 9 1 2 5 4 6 7");
+        moves.step();
+        assert_eq!(moves.draw(), "(2) 8 9 1 5 4 6 7 3");
+        assert_eq!(moves.counters()[1].1, "5 4 6 7 3 2 8 9");
+    }
+
     #[test]
     fn test_case() {
         let game = CrabGame::from_str(TEST_INPUT).unwrap();
-        let game = game.perform_moves(10);
+        let game = game.perform_moves(10).unwrap();
         assert_eq!(game.output_string(), "92658374");
-        let game = game.perform_moves(90);
+        let game = game.perform_moves(90).unwrap();
         assert_eq!(game.output_string(), "67384529");
     }
 
     #[test]
     fn answer() {
         let game = CrabGame::from_str(PUZZLE_INPUT).unwrap();
-        let game = game.perform_moves(100);
+        let game = game.perform_moves(100).unwrap();
         assert_eq!(game.output_string(), "52937846");
     }
 }
@@ -221,7 +416,7 @@ mod part_two {
     #[test]
     fn test_case() {
         let game = CrabGame::from_str_expanded(TEST_INPUT).unwrap();
-        let game = game.perform_moves(10_000_000);
+        let game = game.perform_moves(10_000_000).unwrap();
         assert_eq!(
             game.cups_after_1().take(2).collect::<Vec<u32>>(),
             vec![934001, 159792]
@@ -229,10 +424,19 @@ mod part_two {
         assert_eq!(game.output_mk2(), 149245887792);
     }
 
+    #[test]
+    fn can_be_cancelled() {
+        let game = CrabGame::from_str_expanded(TEST_INPUT).unwrap();
+        let token = cancel::Token::new();
+        token.cancel();
+        let result = cancel::with_token(&token, || game.perform_moves(10_000_000));
+        assert!(result.unwrap_err().is::<cancel::Cancelled>());
+    }
+
     #[test]
     fn answer() {
         let game = CrabGame::from_str_expanded(PUZZLE_INPUT).unwrap();
-        let game = game.perform_moves(10_000_000);
+        let game = game.perform_moves(10_000_000).unwrap();
         assert_eq!(game.output_mk2(), 8456532414);
     }
 }
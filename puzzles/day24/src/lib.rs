@@ -1,155 +1,225 @@
 // Day 24: Lobby Layout
 
-use std::{borrow::Cow, collections::HashSet};
-
-use rayon::prelude::*;
-use shared::prelude::*;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Direction {
-    E,
-    SE,
-    SW,
-    W,
-    NW,
-    NE,
-}
+use std::{collections::HashSet, fmt, fmt::Display, iter};
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Tile(i64, i64, i64);
+use shared::{
+    automaton::{Automaton, HexLattice, Life},
+    geometry::{Hex, HexDirection},
+    prelude::*,
+    render::{Frames, HexLayout, Render, Rgb},
+    simulation::{Animate, Simulation},
+};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
-pub struct TilePattern(HashSet<Tile>);
+pub struct TilePattern(HashSet<Hex>);
 
 lazy_static! {
+    static ref RAW_INPUT: String = puzzle_input::load(24).unwrap();
     static ref TEST_INPUT: Vec<&'static str> = puzzle_input::lines(include_str!("test_input.txt"));
-    static ref PUZZLE_INPUT: Vec<&'static str> =
-        puzzle_input::lines(include_str!("puzzle_input.txt"));
+    static ref PUZZLE_INPUT: Vec<&'static str> = puzzle_input::lines(&RAW_INPUT);
 }
 
-impl Direction {
-    pub fn all() -> Vec<Self> {
-        vec![
-            Direction::E,
-            Direction::SE,
-            Direction::SW,
-            Direction::W,
-            Direction::NE,
-            Direction::NW,
-        ]
+/// Follows a line of directions like `nwwswee` from the reference tile
+pub fn parse_tile(directions: &str) -> Result<Hex, ParseError> {
+    let mut iter = directions.char_indices().peekable();
+    let unexpected = |next: Option<(usize, char)>, expected| match next {
+        Some((i, char)) => ParseError::at_column(directions, i, char.len_utf8(), expected),
+        None => ParseError::at_end(directions, expected),
+    };
+
+    let mut tile = Hex::ORIGIN;
+    loop {
+        let next_direction = match iter.next() {
+            Some((_, 'e')) => Ok(HexDirection::East),
+            Some((_, 's')) => match iter.next() {
+                Some((_, 'e')) => Ok(HexDirection::SouthEast),
+                Some((_, 'w')) => Ok(HexDirection::SouthWest),
+                next => Err(unexpected(next, "`e` or `w`")),
+            },
+            Some((_, 'w')) => Ok(HexDirection::West),
+            Some((_, 'n')) => match iter.next() {
+                Some((_, 'e')) => Ok(HexDirection::NorthEast),
+                Some((_, 'w')) => Ok(HexDirection::NorthWest),
+                next => Err(unexpected(next, "`e` or `w`")),
+            },
+            next @ Some(_) => Err(unexpected(next, "`e`, `se`, `sw`, `w`, `nw` or `ne`")),
+            None => break,
+        }?;
+        tile = tile.neighbor(next_direction);
     }
+
+    Ok(tile)
 }
 
-impl Tile {
-    pub fn from_directions_str(directions: &str) -> anyhow::Result<Tile> {
-        let mut iter = directions.chars();
-
-        let mut tile = Tile::default();
-        loop {
-            let next_direction = match iter.next() {
-                Some('e') => Ok(Direction::E),
-                Some('s') => match iter.next() {
-                    Some('e') => Ok(Direction::SE),
-                    Some('w') => Ok(Direction::SW),
-                    _ => Err(anyhow!("expected e or w")),
-                },
-                Some('w') => Ok(Direction::W),
-                Some('n') => match iter.next() {
-                    Some('e') => Ok(Direction::NE),
-                    Some('w') => Ok(Direction::NW),
-                    _ => Err(anyhow!("expected e or w")),
-                },
-                Some(_) => Err(anyhow!("unrecognized character")),
-                None => break,
-            }?;
-            tile = tile.neighbor(next_direction);
+fn letters(direction: HexDirection) -> &'static str {
+    match direction {
+        HexDirection::East => "e",
+        HexDirection::SouthEast => "se",
+        HexDirection::SouthWest => "sw",
+        HexDirection::West => "w",
+        HexDirection::NorthWest => "nw",
+        HexDirection::NorthEast => "ne",
+    }
+}
+
+/// Black tiles with zero or more than 2 black neighbors flip to white, and
+/// white tiles with exactly 2 black neighbors flip to black
+const BLACK_TILES: Life = Life {
+    born: &[2],
+    survives: &[1, 2],
+};
+
+impl TilePattern {
+    pub fn from_instructions(instructions: &[&str]) -> Result<Self, ParseError> {
+        let mut tiles = HashSet::new();
+        for tile in parse_error::parse_lines(instructions, parse_tile)? {
+            if tiles.contains(&tile) {
+                tiles.remove(&tile);
+            } else {
+                tiles.insert(tile);
+            }
         }
 
-        Ok(tile)
+        Ok(TilePattern(tiles))
     }
 
-    pub fn neighbor(&self, direction: Direction) -> Tile {
-        let (dx, dy, dz) = match direction {
-            Direction::E => (1, -1, 0),
-            Direction::SE => (0, -1, 1),
-            Direction::SW => (-1, 0, 1),
-            Direction::W => (-1, 1, 0),
-            Direction::NW => (0, 1, -1),
-            Direction::NE => (1, 0, -1),
-        };
+    pub fn count_black_tiles(&self) -> usize {
+        self.0.len()
+    }
 
-        let &Tile(x, y, z) = self;
+    fn automaton() -> Automaton<HexLattice<Hex>, Life> {
+        Automaton::new(HexLattice::default(), BLACK_TILES).parallel()
+    }
 
-        Tile(x + dx, y + dy, z + dz)
+    pub fn update(&self) -> Self {
+        TilePattern(Self::automaton().step(&self.0))
     }
 
-    pub fn all_neighbors(&self) -> impl Iterator<Item = Tile> + '_ {
-        Direction::all()
-            .into_iter()
-            .map(move |direction| self.neighbor(direction))
+    pub fn update_for_days(&self, days: usize) -> Self {
+        TilePattern(Self::automaton().run(self.0.clone(), days))
     }
 }
 
-impl TilePattern {
-    pub fn from_instructions(instructions: &[&str]) -> anyhow::Result<Self> {
-        let tiles: anyhow::Result<HashSet<Tile>> =
-            instructions
-                .iter()
-                .try_fold(HashSet::new(), |mut tiles, &instruction| {
-                    let tile = Tile::from_directions_str(instruction)?;
-                    if tiles.contains(&tile) {
-                        tiles.remove(&tile);
-                    } else {
-                        tiles.insert(tile);
-                    }
-                    Ok(tiles)
-                });
-
-        Ok(TilePattern(tiles?))
+/// One line of directions per black tile, nearest first, each taking the
+/// shortest way there. The reference tile goes east and back so its line
+/// isn't blank
+impl Display for TilePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tiles: Vec<Hex> = self.0.iter().copied().collect();
+        tiles.sort_by_key(|&x| (x.distance(Hex::ORIGIN), x.cube().0));
+        for (i, &target) in tiles.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            if target == Hex::ORIGIN {
+                write!(f, "ew")?;
+            }
+            let mut tile = Hex::ORIGIN;
+            while tile != target {
+                let direction = *HexDirection::ALL
+                    .iter()
+                    .min_by_key(|&&x| tile.neighbor(x).distance(target))
+                    .unwrap();
+                write!(f, "{}", letters(direction))?;
+                tile = tile.neighbor(direction);
+            }
+        }
+        Ok(())
     }
+}
 
-    pub fn count_black_tiles(&self) -> usize {
-        self.0.len()
+pub fn part_one(instructions: &[&str]) -> anyhow::Result<usize> {
+    Ok(TilePattern::from_instructions(instructions)?.count_black_tiles())
+}
+
+pub fn part_two(instructions: &[&str]) -> anyhow::Result<usize> {
+    Ok(TilePattern::from_instructions(instructions)?
+        .update_for_days(100)
+        .count_black_tiles())
+}
+
+pub struct Day24;
+
+impl Puzzle for Day24 {
+    const DAY: u8 = 24;
+    const TITLE: &'static str = "Lobby Layout";
+    const VERSION: &'static str = "1";
+    type Input<'a> = TilePattern;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(TilePattern::from_instructions(&puzzle_input::lines(input))?)
     }
 
-    pub fn update(&self) -> Self {
-        let tiles_to_consider: HashSet<Tile> = self
-            .0
-            .iter()
-            .flat_map(|x| x.all_neighbors().chain(std::iter::once(*x)))
-            .collect();
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(input.count_black_tiles())
+    }
 
-        let tiles: HashSet<Tile> = tiles_to_consider
-            .into_par_iter()
-            .filter(|tile| {
-                let is_black = self.0.contains(&tile);
-                let black_neighbors = tile
-                    .all_neighbors()
-                    .filter(|neighbor| self.0.contains(neighbor))
-                    .count();
-                if is_black {
-                    !(black_neighbors == 0 || black_neighbors > 2)
-                } else {
-                    black_neighbors == 2
-                }
-            })
-            .collect();
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+        Ok(input.update_for_days(100).count_black_tiles())
+    }
+}
 
-        TilePattern(tiles)
+impl Simulation for TilePattern {
+    fn step(&mut self) -> bool {
+        *self = self.update();
+        true
     }
 
-    pub fn update_for_days(&self, days: usize) -> Self {
-        (0..days)
-            .into_iter()
-            .fold(Cow::Borrowed(self), |pattern, _| {
-                Cow::Owned(pattern.update())
-            })
-            .into_owned()
+    /// Black tiles as `##`, with each row shifted half a tile from the last
+    fn draw(&self) -> String {
+        let layout = HexLayout::around(self.0.iter().copied());
+        let image = layout.draw(self.0.iter().map(|&x| (x, BLACK)), WHITE);
+        // Every hex is two pixels tall, so half the rows are repeats
+        let rows = image.rows().step_by(2).map(|row| {
+            row.iter()
+                .map(|&x| if x == BLACK { '#' } else { '.' })
+                .collect::<String>()
+        });
+        rows.collect::<Vec<_>>().join("\n")
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![("black tiles", self.count_black_tiles().to_string())]
+    }
+
+    /// The exhibit runs for 100 days
+    fn target(&self) -> Option<usize> {
+        Some(100)
     }
 }
 
-pub fn part_one(instructions: &[&str]) -> anyhow::Result<usize> {
-    Ok(TilePattern::from_instructions(instructions)?.count_black_tiles())
+impl Animate for Day24 {
+    fn simulation<'a>(
+        input: &'a TilePattern,
+        _part: Part,
+    ) -> anyhow::Result<Box<dyn Simulation + 'a>> {
+        Ok(Box::new(input.clone()))
+    }
+}
+
+const BLACK: Rgb = Rgb::hex(0x212121);
+const WHITE: Rgb = Rgb::hex(0xf5f5f5);
+
+impl Render for Day24 {
+    /// Part one is the floor once it's laid, and part two has a frame for
+    /// each day of the exhibit
+    fn render(input: &TilePattern, part: Part, frames: &mut dyn Frames) -> anyhow::Result<()> {
+        let days = match part {
+            Part::One => 0,
+            Part::Two => 100,
+        };
+        let floors: Vec<_> = iter::successors(Some(input.clone()), |x| Some(x.update()))
+            .take(days + 1)
+            .collect();
+
+        let layout = HexLayout::around(floors.iter().flat_map(|x| x.0.iter().copied()));
+        for floor in &floors {
+            frames.push(layout.draw(floor.0.iter().map(|&x| (x, BLACK)), WHITE))?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -158,11 +228,17 @@ mod part_one {
 
     #[test]
     fn test_directions() {
-        assert_ne!(Tile::from_directions_str("esew").unwrap(), Tile::default());
-        assert_eq!(
-            Tile::from_directions_str("nwwswee").unwrap(),
-            Tile::default()
-        );
+        assert_ne!(parse_tile("esew").unwrap(), Hex::ORIGIN);
+        assert_eq!(parse_tile("nwwswee").unwrap(), Hex::ORIGIN);
+    }
+
+    #[test]
+    fn round_trip() {
+        let parse = |text: &str| TilePattern::from_instructions(&puzzle_input::lines(text));
+        let printed = puzzle_input::assert_round_trip(&TEST_INPUT.join("\n"), parse);
+        assert_eq!(printed.lines().count(), 10);
+        let ring = TilePattern::from_instructions(&["nwwswee", "e", "sw"]).unwrap();
+        assert_eq!(ring.to_string(), "ew\nsw\ne");
     }
 
     #[test]
@@ -172,6 +248,7 @@ mod part_one {
 
     #[test]
     fn answer() {
+        shared::skip_without_input!(24);
         assert_eq!(part_one(PUZZLE_INPUT.as_slice()).unwrap(), 495);
     }
 }
@@ -190,6 +267,26 @@ mod part_two {
         let floor = floor.update();
         assert_eq!(floor.count_black_tiles(), 25);
     }
+    #[test]
+    fn draw() {
+        let tiles = ["e", "se", "sw", "w", "nw", "ne"];
+        let ring = TilePattern::from_instructions(&tiles).unwrap();
+        assert_eq!(ring.draw(), ".####.\n##..##\n.####.");
+        assert_eq!(ring.counters(), vec![("black tiles", "6".to_string())]);
+    }
+
+    #[test]
+    fn render() {
+        let floor = TilePattern::from_instructions(TEST_INPUT.as_slice()).unwrap();
+        let mut frames = Vec::new();
+        Day24::render(&floor, Part::Two, &mut frames).unwrap();
+        assert_eq!(frames.len(), 101);
+        assert!(frames.iter().all(|x| x.width() == frames[0].width()));
+        // Each tile is two pixels square
+        let black = frames[1].cells().filter(|&&x| x == BLACK).count();
+        assert_eq!(black, 4 * 15);
+    }
+
     #[test]
     fn test_case() {
         assert_eq!(
@@ -203,12 +300,7 @@ mod part_two {
 
     #[test]
     fn answer() {
-        assert_eq!(
-            TilePattern::from_instructions(PUZZLE_INPUT.as_slice())
-                .unwrap()
-                .update_for_days(100)
-                .count_black_tiles(),
-            4012
-        );
+        shared::skip_without_input!(24);
+        assert_eq!(part_two(PUZZLE_INPUT.as_slice()).unwrap(), 4012);
     }
 }
@@ -1,6 +1,11 @@
 // Day 25: Combo Breaker
 
-use rayon::prelude::*;
+use std::fmt::Display;
+
+use shared::{math, prelude::*};
+
+const MODULUS: u64 = 20201227;
+const SUBJECT: u64 = 7;
 
 pub struct Input {
     card_public_key: u64,
@@ -17,46 +22,79 @@ pub const PUZZLE_INPUT: Input = Input {
     door_public_key: 3647239,
 };
 
-fn transform_step(number: u64, subject: u64) -> u64 {
-    (number * subject) % 20201227
+impl Input {
+    pub fn parse(input: &[&str]) -> Result<Input, ParseError> {
+        let keys = parse_error::parse_lines(input, |line| {
+            match parse_error::parse_at(line, line, "a public key")? {
+                key if (1..MODULUS).contains(&key) => Ok(key),
+                _ => Err(ParseError::new(line, "a public key between 1 and 20201226")),
+            }
+        })?;
+
+        match keys[..] {
+            [card_public_key, door_public_key] => Ok(Input {
+                card_public_key,
+                door_public_key,
+            }),
+            _ => Err(ParseError::new(
+                input.get(2).copied().unwrap_or_default(),
+                "exactly two public keys, one per line",
+            )
+            .on_line(input.len().min(3))),
+        }
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.card_public_key, self.door_public_key)
+    }
 }
 
 pub fn transform(subject: u64, loop_size: u64) -> u64 {
-    let mut number = 1;
-    for _ in 0..loop_size {
-        number = transform_step(number, subject);
-    }
-    number
+    math::pow_mod(subject, loop_size, MODULUS)
 }
 
+/// Every key has a loop size, since 7 generates every number below the
+/// modulus except 0
 pub fn discover_loop_size(public_key: u64) -> u64 {
-    let mut number = 1;
-    let mut loop_size = 1;
-    loop {
-        number = transform_step(number, 7);
-        if number == public_key {
-            return loop_size;
-        }
-        loop_size += 1;
-    }
+    math::discrete_log(SUBJECT, public_key, MODULUS)
+        .unwrap_or_else(|| panic!("{} isn't a public key", public_key))
 }
 
 pub fn discover_encryption_key(input: Input) -> u64 {
-    vec![
-        (input.door_public_key, input.card_public_key),
-        (input.card_public_key, input.door_public_key),
-    ]
-    .into_par_iter()
-    .find_map_any(|(public_key, other_public_key)| {
-        let loop_size = discover_loop_size(public_key);
-        Some(transform(other_public_key, loop_size))
-    })
-    .unwrap()
+    let loop_size = discover_loop_size(input.card_public_key);
+    transform(input.door_public_key, loop_size)
+}
+
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    const DAY: u8 = 25;
+    const TITLE: &'static str = "Combo Breaker";
+    const PARTS: &'static [Part] = &[Part::One];
+    const VERSION: &'static str = "1";
+    type Input<'a> = Input;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+        Ok(Input::parse(&puzzle_input::lines(input))?)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Ok(discover_encryption_key(Input { ..*input }))
+    }
+
+    fn part_two(_: &Self::Input<'_>) -> anyhow::Result<u64> {
+        Err(anyhow!("Day 25 has no part two"))
+    }
 }
 
 #[cfg(test)]
 mod part_one {
     use super::*;
+    use rayon::prelude::*;
 
     #[test]
     fn test_transform() {
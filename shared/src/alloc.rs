@@ -0,0 +1,136 @@
+//! A global allocator that counts what it hands out, for finding the solvers
+//! that allocate the most. A binary opts in with
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOC: shared::alloc::Counting = shared::alloc::Counting;
+//! ```
+//!
+//! The counts are for the whole process, so [`measure`] is only accurate
+//! while nothing else is running.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+};
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting as it goes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Counting;
+
+impl Counting {
+    fn record(&self, allocated: usize, freed: usize) {
+        INSTALLED.store(true, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(allocated as u64, Ordering::Relaxed);
+        let live = LIVE.fetch_add(allocated, Ordering::Relaxed) + allocated;
+        LIVE.fetch_sub(freed, Ordering::Relaxed);
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    /// Counts as allocating the new size and freeing the old one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            self.record(new_size, layout.size());
+        }
+        new
+    }
+}
+
+/// What was allocated while [`measure`] ran
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    /// The most that was live at once, over what already was at the start
+    pub peak_bytes: u64,
+}
+
+/// Whether [`Counting`] is the global allocator
+pub fn is_counting() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, counting its allocations. Everything is zero unless [`Counting`]
+/// is installed.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED.load(Ordering::Relaxed);
+    let live = LIVE.load(Ordering::Relaxed);
+    // An outer `measure` still needs to see this one's peak
+    let outer_peak = PEAK.swap(live, Ordering::Relaxed);
+
+    let result = f();
+
+    let peak = PEAK.fetch_max(outer_peak, Ordering::Relaxed);
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: ALLOCATED.load(Ordering::Relaxed) - bytes,
+        peak_bytes: peak.saturating_sub(live) as u64,
+    };
+    (result, stats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: Counting = Counting;
+
+    // Other tests allocate at the same time, so this only checks lower bounds
+    // on numbers far bigger than theirs
+    const BIG: usize = 64 << 20;
+
+    #[test]
+    fn counts_allocations() {
+        let (_, stats) = measure(|| {
+            let mut grown = vec![0u8; BIG];
+            grown.resize(2 * BIG, 0);
+            drop(grown);
+            let _again = vec![0u8; BIG];
+        });
+        assert!(is_counting());
+        assert!(stats.allocations >= 3);
+        assert!(stats.bytes >= 4 * BIG as u64);
+        assert!(stats.peak_bytes >= 2 * BIG as u64);
+        assert!(stats.peak_bytes < 4 * BIG as u64);
+    }
+
+    #[test]
+    fn nested_peaks_reach_the_outer_measure() {
+        let (inner, outer) = measure(|| measure(|| vec![0u8; BIG]).1);
+        assert!(inner.peak_bytes >= BIG as u64);
+        assert!(outer.peak_bytes >= BIG as u64);
+    }
+}
@@ -0,0 +1,370 @@
+use std::{collections::HashSet, hash::Hash, marker::PhantomData};
+
+use crate::{
+    geometry::{Hex, HexDirection, Vector},
+    grid::{Grid, Point},
+};
+
+/// Which cells are next to which
+pub trait Topology: Sync {
+    type Cell: Copy + Eq + Hash + Send + Sync;
+
+    fn neighbors(&self, cell: Self::Cell) -> impl Iterator<Item = Self::Cell>;
+}
+
+/// Cell states that count towards a neighbor total
+pub trait Live {
+    fn is_live(&self) -> bool;
+}
+
+impl Live for bool {
+    fn is_live(&self) -> bool {
+        *self
+    }
+}
+
+/// Picks a cell's next state from its current one and how many of its
+/// neighbors are live
+pub trait Rule<S>: Sync {
+    fn next(&self, cell: &S, live_neighbors: usize) -> S;
+}
+
+impl<S, F: Fn(&S, usize) -> S + Sync> Rule<S> for F {
+    fn next(&self, cell: &S, live_neighbors: usize) -> S {
+        self(cell, live_neighbors)
+    }
+}
+
+/// A birth/survival rule like Conway's Life, which is `born: &[3], survives: &[2, 3]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Life {
+    pub born: &'static [usize],
+    pub survives: &'static [usize],
+}
+
+impl Rule<bool> for Life {
+    fn next(&self, &alive: &bool, live_neighbors: usize) -> bool {
+        if alive {
+            self.survives.contains(&live_neighbors)
+        } else {
+            self.born.contains(&live_neighbors)
+        }
+    }
+}
+
+/// A finite grid where cells past the edge don't exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundedGrid {
+    pub width: usize,
+    pub height: usize,
+    /// Offsets to the neighbors, e.g. [`crate::grid::NEIGHBORS8`]
+    pub directions: &'static [(isize, isize)],
+}
+
+impl BoundedGrid {
+    pub fn around<T>(grid: &Grid<T>, directions: &'static [(isize, isize)]) -> Self {
+        BoundedGrid {
+            width: grid.width(),
+            height: grid.height(),
+            directions,
+        }
+    }
+}
+
+impl Topology for BoundedGrid {
+    type Cell = Point;
+
+    fn neighbors(&self, (x, y): Point) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        self.directions.iter().filter_map(move |&(dx, dy)| {
+            let point = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            if point.0 < width && point.1 < height {
+                Some(point)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Cells that can convert to and from `N` integer coordinates
+pub trait Coordinates<const N: usize>: Copy + Eq + Hash + Send + Sync {
+    fn coordinates(self) -> [i64; N];
+    fn from_coordinates(coordinates: [i64; N]) -> Self;
+}
+
+impl<const N: usize> Coordinates<N> for [i64; N] {
+    fn coordinates(self) -> [i64; N] {
+        self
+    }
+
+    fn from_coordinates(coordinates: [i64; N]) -> Self {
+        coordinates
+    }
+}
+
+impl<const N: usize> Coordinates<N> for Vector<N> {
+    fn coordinates(self) -> [i64; N] {
+        self.0
+    }
+
+    fn from_coordinates(coordinates: [i64; N]) -> Self {
+        Vector(coordinates)
+    }
+}
+
+impl Coordinates<3> for Hex {
+    fn coordinates(self) -> [i64; 3] {
+        self.cube().0
+    }
+
+    fn from_coordinates(coordinates: [i64; 3]) -> Self {
+        Hex::from_cube(Vector(coordinates))
+    }
+}
+
+/// An unbounded `N`-dimensional square lattice, where every cell touching
+/// another, even at a corner, is a neighbor
+pub struct Lattice<C, const N: usize>(PhantomData<fn() -> C>);
+
+impl<C, const N: usize> Default for Lattice<C, N> {
+    fn default() -> Self {
+        Lattice(PhantomData)
+    }
+}
+
+impl<C: Coordinates<N>, const N: usize> Topology for Lattice<C, N> {
+    type Cell = C;
+
+    fn neighbors(&self, cell: C) -> impl Iterator<Item = C> {
+        Vector(cell.coordinates())
+            .moore_neighbors()
+            .map(|x| C::from_coordinates(x.0))
+    }
+}
+
+/// An unbounded lattice of hexagons, in cube coordinates like [`Hex`]
+pub struct HexLattice<C>(PhantomData<fn() -> C>);
+
+impl<C> Default for HexLattice<C> {
+    fn default() -> Self {
+        HexLattice(PhantomData)
+    }
+}
+
+impl<C: Coordinates<3>> Topology for HexLattice<C> {
+    type Cell = C;
+
+    fn neighbors(&self, cell: C) -> impl Iterator<Item = C> {
+        let cube = Vector(cell.coordinates());
+        HexDirection::ALL
+            .iter()
+            .map(move |x| C::from_coordinates((cube + x.vector()).0))
+    }
+}
+
+/// A topology defined by a function from a cell to its neighbors
+pub struct Neighborhood<C, F> {
+    neighbors: F,
+    cell: PhantomData<fn() -> C>,
+}
+
+impl<C, F> Neighborhood<C, F> {
+    pub fn new(neighbors: F) -> Self {
+        Neighborhood {
+            neighbors,
+            cell: PhantomData,
+        }
+    }
+}
+
+impl<C, F, I> Topology for Neighborhood<C, F>
+where
+    C: Copy + Eq + Hash + Send + Sync,
+    F: Fn(C) -> I + Sync,
+    I: IntoIterator<Item = C>,
+{
+    type Cell = C;
+
+    fn neighbors(&self, cell: C) -> impl Iterator<Item = C> {
+        (self.neighbors)(cell).into_iter()
+    }
+}
+
+/// A way of holding every cell's state. [`Grid`]s store every cell of a
+/// bounded topology, and `HashSet`s store just the live cells of a two-state
+/// automaton on any topology.
+pub trait Storage<T: Topology>: Sized + PartialEq {
+    type State;
+
+    fn step<R: Rule<Self::State>>(&self, topology: &T, rule: &R, parallel: bool) -> Self;
+}
+
+impl<T, S> Storage<T> for Grid<S>
+where
+    T: Topology<Cell = Point>,
+    S: Live + PartialEq + Send + Sync,
+{
+    type State = S;
+
+    fn step<R: Rule<S>>(&self, topology: &T, rule: &R, parallel: bool) -> Self {
+        let cells = map_cells(self.points().collect(), parallel, |point| {
+            let live_neighbors = topology
+                .neighbors(point)
+                .filter(|&x| self.get(x).is_some_and(Live::is_live))
+                .count();
+            rule.next(&self[point], live_neighbors)
+        });
+        Grid::new(self.width(), self.height(), cells)
+    }
+}
+
+impl<T: Topology> Storage<T> for HashSet<T::Cell> {
+    type State = bool;
+
+    fn step<R: Rule<bool>>(&self, topology: &T, rule: &R, parallel: bool) -> Self {
+        // Only live cells and their neighbors can be live next time
+        let candidates: HashSet<T::Cell> = self
+            .iter()
+            .flat_map(|&cell| topology.neighbors(cell).chain(std::iter::once(cell)))
+            .collect();
+
+        map_cells(candidates.into_iter().collect(), parallel, |cell| {
+            let live_neighbors = topology
+                .neighbors(cell)
+                .filter(|x| self.contains(x))
+                .count();
+            Some(cell).filter(|x| rule.next(&self.contains(x), live_neighbors))
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn map_cells<I: Send, O: Send>(
+    cells: Vec<I>,
+    parallel: bool,
+    f: impl Fn(I) -> O + Sync + Send,
+) -> Vec<O> {
+    use rayon::prelude::*;
+
+    if parallel {
+        cells.into_par_iter().map(f).collect()
+    } else {
+        cells.into_iter().map(f).collect()
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+fn map_cells<I, O>(cells: Vec<I>, _parallel: bool, f: impl Fn(I) -> O) -> Vec<O> {
+    cells.into_iter().map(f).collect()
+}
+
+/// Counts live neighbors and applies a rule to every cell, generation after
+/// generation
+pub struct Automaton<T, R> {
+    topology: T,
+    rule: R,
+    parallel: bool,
+}
+
+impl<T: Topology, R> Automaton<T, R> {
+    pub fn new(topology: T, rule: R) -> Self {
+        Automaton {
+            topology,
+            rule,
+            parallel: false,
+        }
+    }
+
+    /// Spreads each generation across threads. Only has an effect with the
+    /// `rayon` feature enabled.
+    pub fn parallel(self) -> Self {
+        Automaton {
+            parallel: true,
+            ..self
+        }
+    }
+
+    pub fn step<S>(&self, state: &S) -> S
+    where
+        S: Storage<T>,
+        R: Rule<S::State>,
+    {
+        state.step(&self.topology, &self.rule, self.parallel)
+    }
+
+    pub fn run<S>(&self, state: S, generations: usize) -> S
+    where
+        S: Storage<T>,
+        R: Rule<S::State>,
+    {
+        (0..generations).fold(state, |state, _| self.step(&state))
+    }
+
+    /// Steps until a generation is the same as the one before it
+    pub fn run_until_stable<S>(&self, mut state: S) -> S
+    where
+        S: Storage<T>,
+        R: Rule<S::State>,
+    {
+        loop {
+            let next = self.step(&state);
+            if next == state {
+                return state;
+            }
+            state = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::NEIGHBORS8;
+
+    const CONWAY: Life = Life {
+        born: &[3],
+        survives: &[2, 3],
+    };
+
+    #[test]
+    fn blinker() {
+        let vertical = Grid::from_fn(3, 3, |(x, _)| x == 1);
+        let horizontal = Grid::from_fn(3, 3, |(_, y)| y == 1);
+
+        let automaton = Automaton::new(BoundedGrid::around(&vertical, &NEIGHBORS8), CONWAY);
+        assert_eq!(automaton.step(&vertical), horizontal);
+        assert_eq!(automaton.run(vertical.clone(), 2), vertical);
+
+        let sparse: HashSet<[i64; 2]> = [[1, 0], [1, 1], [1, 2]].iter().copied().collect();
+        let automaton = Automaton::new(Lattice::default(), CONWAY).parallel();
+        let expected: HashSet<_> = [[0, 1], [1, 1], [2, 1]].iter().copied().collect();
+        assert_eq!(automaton.step(&sparse), expected);
+    }
+
+    #[test]
+    fn block_is_stable() {
+        let block: HashSet<[i64; 2]> = [[0, 0], [0, 1], [1, 0], [1, 1]].iter().copied().collect();
+        let automaton = Automaton::new(Lattice::default(), CONWAY);
+        assert_eq!(automaton.run_until_stable(block.clone()), block);
+    }
+
+    #[test]
+    fn neighbor_counts() {
+        assert_eq!(
+            Lattice::<[i64; 3], 3>::default().neighbors([0; 3]).count(),
+            26
+        );
+        assert!(!Lattice::<[i64; 4], 4>::default()
+            .neighbors([0; 4])
+            .any(|x| x == [0; 4]));
+        let hex = HexLattice::<[i64; 3]>::default();
+        assert!(hex.neighbors([0; 3]).all(|[x, y, z]| x + y + z == 0));
+
+        let line = Neighborhood::new(|x: i64| vec![x - 1, x + 1]);
+        assert_eq!(line.neighbors(5).collect::<Vec<_>>(), vec![4, 6]);
+    }
+}
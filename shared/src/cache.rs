@@ -0,0 +1,175 @@
+//! Answers saved on disk, so slow days only have to be solved once per input.
+//! Each entry is keyed by day, part and the input's SHA-256, and remembers the
+//! [`Puzzle::VERSION`](crate::puzzle::Puzzle::VERSION) that produced it. A
+//! different version is a miss. Nothing else expires, so clearing is up to
+//! whoever runs the solvers.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{puzzle::Part, puzzle_input};
+
+/// Hex SHA-256 of a puzzle input
+pub fn sha256(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key<'a> {
+    pub day: u8,
+    pub part: Part,
+    pub input_sha256: String,
+    pub version: &'a str,
+}
+
+impl<'a> Key<'a> {
+    pub fn new(day: u8, part: Part, input: &str, version: &'a str) -> Self {
+        Key {
+            day,
+            part,
+            input_sha256: sha256(input),
+            version,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Cache { dir: dir.into() }
+    }
+
+    /// `target/aoc-cache` under the workspace root, so `cargo clean` clears it
+    /// too
+    pub fn in_workspace() -> Self {
+        Cache::new(
+            puzzle_input::workspace_root()
+                .join("target")
+                .join("aoc-cache"),
+        )
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn day_dir(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{:02}", day))
+    }
+
+    /// One file per input and part, holding the version then the answer
+    fn path(&self, key: &Key<'_>) -> PathBuf {
+        self.day_dir(key.day).join(format!(
+            "{}-part{}.txt",
+            key.input_sha256,
+            key.part.number()
+        ))
+    }
+
+    /// The saved answer, if there is one from the same solver version. An
+    /// unreadable entry counts as a miss.
+    pub fn get(&self, key: &Key<'_>) -> Option<String> {
+        let contents = fs::read_to_string(self.path(key)).ok()?;
+        let (version, answer) = contents.split_once('\n')?;
+        if version == key.version {
+            Some(answer.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Saves `answer`, replacing whatever was there for `key`'s input and part
+    pub fn put(&self, key: &Key<'_>, answer: &str) -> io::Result<()> {
+        let path = self.path(key);
+        fs::create_dir_all(self.day_dir(key.day))?;
+        fs::write(path, format!("{}\n{}", key.version, answer))
+    }
+
+    /// Removes every saved answer, or only `day`'s. Returns how many there were.
+    pub fn clear(&self, day: Option<u8>) -> io::Result<usize> {
+        let dirs: Vec<PathBuf> = match day {
+            Some(day) => vec![self.day_dir(day)],
+            None => (0..=25).map(|day| self.day_dir(day)).collect(),
+        };
+
+        let mut removed = 0;
+        for dir in dirs {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            removed += entries.count();
+            fs::remove_dir_all(&dir)?;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cache(name: &str) -> Cache {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-{}-{}", name, std::process::id()));
+        Cache::new(dir)
+    }
+
+    #[test]
+    fn round_trips_answers() {
+        let cache = cache("round-trip");
+        let key = Key::new(23, Part::Two, "463528179", "1");
+        assert_eq!(cache.get(&key), None);
+
+        cache.put(&key, "8456532414").unwrap();
+        assert_eq!(cache.get(&key).as_deref(), Some("8456532414"));
+
+        // A new solver version or a different input misses
+        assert_eq!(
+            cache.get(&Key {
+                version: "2",
+                ..key.clone()
+            }),
+            None
+        );
+        assert_eq!(cache.get(&Key::new(23, Part::Two, "389125467", "1")), None);
+        assert_eq!(cache.get(&Key::new(23, Part::One, "463528179", "1")), None);
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn clears_explicitly() {
+        let cache = cache("clear");
+        cache
+            .put(&Key::new(15, Part::One, "0,3,6", "1"), "436")
+            .unwrap();
+        cache
+            .put(&Key::new(15, Part::Two, "0,3,6", "1"), "175594")
+            .unwrap();
+        cache
+            .put(
+                &Key::new(25, Part::One, "5764801\n17807724", "1"),
+                "14897079",
+            )
+            .unwrap();
+
+        assert_eq!(cache.clear(Some(15)).unwrap(), 2);
+        assert_eq!(cache.get(&Key::new(15, Part::One, "0,3,6", "1")), None);
+        assert_eq!(cache.clear(Some(15)).unwrap(), 0);
+        assert_eq!(cache.clear(None).unwrap(), 1);
+
+        fs::remove_dir_all(cache.dir()).ok();
+    }
+}
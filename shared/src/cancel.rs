@@ -0,0 +1,129 @@
+//! Cooperative cancellation for solvers that could run for a long time, or
+//! forever on bad input. Whoever runs a solver sets a [`Token`] for the
+//! thread with [`with_token`], and the solver calls [`check`] now and then.
+//!
+//! ```ignore
+//! while !finished(&state) {
+//!     cancel::check()?;
+//!     state = step(state);
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// Shared between a running solver and whoever wants to stop it
+#[derive(Debug, Clone, Default)]
+pub struct Token(Arc<AtomicBool>);
+
+impl Token {
+    pub fn new() -> Self {
+        Token::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The error [`check`] returns once the current token is cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// Puts the previous token back when dropped, even if `f` panics
+struct Restore(Option<Token>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|x| *x.borrow_mut() = self.0.take());
+    }
+}
+
+/// Runs `f` with `token` as this thread's token. Work `f` hands to other
+/// threads, like rayon's, doesn't see it.
+pub fn with_token<T>(token: &Token, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.with(|x| x.borrow_mut().replace(token.clone()));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// This thread's token, for passing on to work handed to other threads. Outside
+/// [`with_token`] it's a fresh one that nobody else can cancel.
+pub fn current() -> Token {
+    CURRENT.with(|x| x.borrow().clone()).unwrap_or_default()
+}
+
+/// Fails once this thread's token has been cancelled. Never fails outside
+/// [`with_token`].
+pub fn check() -> Result<(), Cancelled> {
+    let cancelled = CURRENT.with(|x| x.borrow().as_ref().is_some_and(Token::is_cancelled));
+    if cancelled {
+        Err(Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checks_the_current_token() {
+        assert_eq!(check(), Ok(()));
+
+        let token = Token::new();
+        with_token(&token, || {
+            assert_eq!(check(), Ok(()));
+            token.cancel();
+            assert_eq!(check(), Err(Cancelled));
+
+            // Nested tokens only last as long as their closure
+            with_token(&Token::new(), || assert_eq!(check(), Ok(())));
+            assert_eq!(check(), Err(Cancelled));
+            assert!(current().is_cancelled());
+        });
+        assert_eq!(check(), Ok(()));
+        assert!(!current().is_cancelled());
+    }
+
+    #[test]
+    fn stops_a_loop_from_another_thread() {
+        let token = Token::new();
+        let worker = {
+            let token = token.clone();
+            std::thread::spawn(move || {
+                with_token(&token, || -> Result<(), Cancelled> {
+                    loop {
+                        check()?;
+                        std::hint::spin_loop();
+                    }
+                })
+            })
+        };
+        token.cancel();
+        assert_eq!(worker.join().unwrap(), Err(Cancelled));
+    }
+}
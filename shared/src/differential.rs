@@ -0,0 +1,347 @@
+//! Runs several implementations of the same thing against random inputs and
+//! shrinks any input they disagree on down to a minimal counterexample
+//!
+//! ```ignore
+//! Differential::new(differential::vec_of(differential::range(0..2020), 0..10))
+//!     .implementation("functional", |x| correct_expense_report(x))
+//!     .implementation("imperative", |x| imperative::correct_expense_report(x))
+//!     .assert_agree();
+//! ```
+
+use std::{fmt, ops::Range};
+
+pub use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Generates random inputs, and suggests simpler versions of an input for
+/// shrinking
+pub trait Strategy {
+    type Value: Clone + fmt::Debug;
+
+    fn generate(&self, rng: &mut StdRng) -> Self::Value;
+
+    /// Candidates that are simpler than `value`, simplest first
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        Vec::new()
+    }
+}
+
+/// Numbers in `range`, shrinking towards its start
+#[derive(Debug, Clone)]
+pub struct Integers(Range<i64>);
+
+pub fn range(range: Range<i64>) -> Integers {
+    assert!(!range.is_empty(), "can't generate from an empty range");
+    Integers(range)
+}
+
+impl Strategy for Integers {
+    type Value = i64;
+
+    fn generate(&self, rng: &mut StdRng) -> i64 {
+        rng.gen_range(self.0.clone())
+    }
+
+    fn shrink(&self, &value: &i64) -> Vec<i64> {
+        let target = self.0.start;
+        // Jump straight to the start, then halve the distance, then step by one
+        let mut candidates = vec![target, target + (value - target) / 2, value - 1];
+        candidates.retain(|&x| x >= target && x < value);
+        candidates.dedup();
+        candidates
+    }
+}
+
+/// One of a fixed set of choices, shrinking towards the first
+#[derive(Debug, Clone)]
+pub struct OneOf<T>(Vec<T>);
+
+pub fn one_of<T: Clone + PartialEq + fmt::Debug>(choices: Vec<T>) -> OneOf<T> {
+    assert!(!choices.is_empty(), "need at least one choice");
+    OneOf(choices)
+}
+
+impl<T: Clone + PartialEq + fmt::Debug> Strategy for OneOf<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut StdRng) -> T {
+        self.0[rng.gen_range(0..self.0.len())].clone()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let index = self.0.iter().position(|x| x == value).unwrap_or(0);
+        self.0[..index].to_vec()
+    }
+}
+
+/// Lists with a length in `len`, shrinking by dropping items and then by
+/// shrinking each item
+#[derive(Debug, Clone)]
+pub struct VecOf<S> {
+    item: S,
+    len: Range<usize>,
+}
+
+pub fn vec_of<S: Strategy>(item: S, len: Range<usize>) -> VecOf<S> {
+    VecOf { item, len }
+}
+
+impl<S: Strategy> Strategy for VecOf<S> {
+    type Value = Vec<S::Value>;
+
+    fn generate(&self, rng: &mut StdRng) -> Self::Value {
+        let len = if self.len.is_empty() {
+            self.len.start
+        } else {
+            rng.gen_range(self.len.clone())
+        };
+        (0..len).map(|_| self.item.generate(rng)).collect()
+    }
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+        let mut candidates = Vec::new();
+        if value.len() > self.len.start {
+            // Drop each half, then each single item
+            let half = value.len() / 2;
+            if half > 0 && value.len() - half >= self.len.start {
+                candidates.push(value[half..].to_vec());
+                candidates.push(value[..value.len() - half].to_vec());
+            }
+            for i in 0..value.len() {
+                let mut shorter = value.clone();
+                shorter.remove(i);
+                candidates.push(shorter);
+            }
+        }
+        for (i, item) in value.iter().enumerate() {
+            for simpler in self.item.shrink(item) {
+                let mut candidate = value.clone();
+                candidate[i] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+macro_rules! tuple_strategy {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: Strategy),+> Strategy for ($($name,)+) {
+            type Value = ($($name::Value,)+);
+
+            fn generate(&self, rng: &mut StdRng) -> Self::Value {
+                ($(self.$index.generate(rng),)+)
+            }
+
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut candidates = Vec::new();
+                $(
+                    for simpler in self.$index.shrink(&value.$index) {
+                        let mut candidate = value.clone();
+                        candidate.$index = simpler;
+                        candidates.push(candidate);
+                    }
+                )+
+                candidates
+            }
+        }
+    };
+}
+
+tuple_strategy!(A 0, B 1);
+tuple_strategy!(A 0, B 1, C 2);
+tuple_strategy!(A 0, B 1, C 2, D 3);
+
+/// A strategy from a generating function, for inputs with structure the
+/// other strategies can't express. Doesn't shrink.
+pub struct FromFn<F>(F);
+
+pub fn from_fn<T, F>(generate: F) -> FromFn<F>
+where
+    T: Clone + fmt::Debug,
+    F: Fn(&mut StdRng) -> T,
+{
+    FromFn(generate)
+}
+
+impl<T: Clone + fmt::Debug, F: Fn(&mut StdRng) -> T> Strategy for FromFn<F> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut StdRng) -> T {
+        (self.0)(rng)
+    }
+}
+
+type Implementation<'a, I, O> = Box<dyn Fn(&I) -> O + 'a>;
+
+/// Equivalent implementations to compare, and the inputs to compare them on
+pub struct Differential<'a, S: Strategy, O> {
+    strategy: S,
+    implementations: Vec<(&'static str, Implementation<'a, S::Value, O>)>,
+    examples: Vec<S::Value>,
+    cases: usize,
+    seed: u64,
+}
+
+/// An input the implementations gave different answers for
+#[derive(Debug, Clone)]
+pub struct Disagreement<I, O> {
+    /// The minimal input after shrinking
+    pub input: I,
+    /// What each implementation returned for `input`
+    pub outputs: Vec<(&'static str, O)>,
+    /// The input as first generated
+    pub original: I,
+    pub seed: u64,
+}
+
+impl<'a, S: Strategy, O: PartialEq + fmt::Debug> Differential<'a, S, O> {
+    pub fn new(strategy: S) -> Self {
+        Differential {
+            strategy,
+            implementations: Vec::new(),
+            examples: Vec::new(),
+            cases: 1000,
+            seed: 2020,
+        }
+    }
+
+    pub fn implementation(mut self, name: &'static str, f: impl Fn(&S::Value) -> O + 'a) -> Self {
+        self.implementations.push((name, Box::new(f)));
+        self
+    }
+
+    /// An input to try every run before the random ones, for edge cases the
+    /// generator might miss
+    pub fn example(mut self, input: S::Value) -> Self {
+        self.examples.push(input);
+        self
+    }
+
+    /// How many random inputs to try. Defaults to 1000.
+    pub fn cases(self, cases: usize) -> Self {
+        Differential { cases, ..self }
+    }
+
+    /// Seeds the generator, so a run can be repeated exactly
+    pub fn seed(self, seed: u64) -> Self {
+        Differential { seed, ..self }
+    }
+
+    fn outputs(&self, input: &S::Value) -> Vec<(&'static str, O)> {
+        self.implementations
+            .iter()
+            .map(|(name, f)| (*name, f(input)))
+            .collect()
+    }
+
+    fn disagree(outputs: &[(&'static str, O)]) -> bool {
+        outputs.windows(2).any(|x| x[0].1 != x[1].1)
+    }
+
+    /// Tries the examples, then `cases` random inputs, returning the first
+    /// disagreement after shrinking it
+    pub fn run(&self) -> Result<(), Disagreement<S::Value, O>> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let generated = (0..self.cases).map(|_| self.strategy.generate(&mut rng));
+        for input in self.examples.iter().cloned().chain(generated) {
+            if Self::disagree(&self.outputs(&input)) {
+                let minimal = self.shrink(input.clone());
+                return Err(Disagreement {
+                    outputs: self.outputs(&minimal),
+                    input: minimal,
+                    original: input,
+                    seed: self.seed,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps taking the first simpler candidate that still disagrees, until
+    /// none do
+    fn shrink(&self, mut input: S::Value) -> S::Value {
+        while let Some(simpler) = self
+            .strategy
+            .shrink(&input)
+            .into_iter()
+            .find(|x| Self::disagree(&self.outputs(x)))
+        {
+            input = simpler;
+        }
+        input
+    }
+
+    /// Panics with the minimal counterexample if the implementations disagree
+    pub fn assert_agree(&self) {
+        if let Err(disagreement) = self.run() {
+            panic!("{}", disagreement);
+        }
+    }
+}
+
+impl<I: fmt::Debug, O: fmt::Debug> fmt::Display for Disagreement<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "implementations disagree on {:?}", self.input)?;
+        for (name, output) in &self.outputs {
+            writeln!(f, "  {}: {:?}", name, output)?;
+        }
+        write!(f, "shrunk from {:?} (seed {})", self.original, self.seed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[allow(clippy::ptr_arg)]
+    fn sum(x: &Vec<i64>) -> i64 {
+        x.iter().sum()
+    }
+
+    #[test]
+    fn agreeing_implementations() {
+        Differential::new(vec_of(range(-100..100), 0..20))
+            .implementation("iterator", sum)
+            .implementation("backwards", |x| x.iter().rev().sum())
+            .assert_agree();
+    }
+
+    #[test]
+    fn shrinks_to_a_minimal_counterexample() {
+        // Wrong as soon as any item is 50 or more
+        let capped = |x: &Vec<i64>| x.iter().map(|&x| x.min(49)).sum::<i64>();
+        let disagreement = Differential::new(vec_of(range(0..100), 0..20))
+            .implementation("sum", sum)
+            .implementation("capped", capped)
+            .run()
+            .unwrap_err();
+
+        assert_eq!(disagreement.input, vec![50]);
+        assert_eq!(disagreement.outputs, vec![("sum", 50), ("capped", 49)]);
+        assert!(disagreement.to_string().contains("[50]"));
+    }
+
+    #[test]
+    fn shrinks_tuples_and_choices() {
+        let strategy = (range(0..1000), one_of(vec!['a', 'b', 'c']));
+        let disagreement = Differential::new(strategy)
+            .implementation("real", |&(n, c)| (n, c))
+            .implementation("buggy", |&(n, c)| (n, if n > 10 { 'a' } else { c }))
+            .run()
+            .unwrap_err();
+        assert_eq!(disagreement.input, (11, 'b'));
+    }
+
+    #[test]
+    fn tries_examples_first() {
+        // No random case can reach the bug, since they're all under 100
+        let disagreement = Differential::new(vec_of(range(0..100), 0..20))
+            .implementation("sum", sum)
+            .implementation("capped", |x| sum(x).min(999))
+            .example(vec![0, 1000])
+            .run()
+            .unwrap_err();
+        assert_eq!(disagreement.original, vec![0, 1000]);
+        assert_eq!(disagreement.input, vec![1000]);
+    }
+}
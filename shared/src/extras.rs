@@ -0,0 +1,14 @@
+//! Solver-specific details recorded alongside a day's answers in the JSON
+//! results from `aoc run`
+
+pub use serde_json::{json, Value};
+
+use crate::puzzle::{Answer, Puzzle};
+
+/// A puzzle with more to say about an answer than the answer itself, like
+/// how many sea monsters it found
+pub trait Extras: Puzzle {
+    /// Usually an object, or `Value::Null` for a part with nothing extra.
+    /// `answer` is the one just worked out, so there's no need to solve again.
+    fn extras(input: &Self::Input<'_>, answer: &Answer<Self>) -> anyhow::Result<Value>;
+}
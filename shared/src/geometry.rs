@@ -0,0 +1,370 @@
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+use crate::grid;
+
+/// A point or offset with `N` integer coordinates. In 2D, `y` counts down
+/// like it does in [`grid::Grid`], so north is `(0, -1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector<const N: usize>(pub [i64; N]);
+
+pub type Vector2 = Vector<2>;
+pub type Vector3 = Vector<3>;
+pub type Vector4 = Vector<4>;
+
+impl<const N: usize> Vector<N> {
+    pub const ZERO: Self = Vector([0; N]);
+
+    /// Distance from the origin moving along one axis at a time
+    pub fn manhattan(self) -> i64 {
+        self.0.iter().map(|x| x.abs()).sum()
+    }
+
+    /// Distance from the origin moving diagonally too, like a chess king
+    pub fn chebyshev(self) -> i64 {
+        self.0.iter().map(|x| x.abs()).max().unwrap_or(0)
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> i64 {
+        (self - other).manhattan()
+    }
+
+    pub fn chebyshev_distance(self, other: Self) -> i64 {
+        (self - other).chebyshev()
+    }
+
+    /// Pads with zeros, e.g. to put a 2D slice into 4D space. Panics if
+    /// `M < N`.
+    pub fn extend<const M: usize>(self) -> Vector<M> {
+        assert!(M >= N, "can't extend a {}D vector to {}D", N, M);
+        let mut extended = [0; M];
+        extended[..N].copy_from_slice(&self.0);
+        Vector(extended)
+    }
+
+    /// Every vector a chebyshev distance of 1 away, i.e. touching even at
+    /// a corner. There are `3^N - 1` of them.
+    pub fn moore_neighbors(self) -> impl Iterator<Item = Self> {
+        let count = 3usize.pow(N as u32);
+        // Count through every combination of -1, 0 and 1 in each dimension,
+        // skipping the middle one where they're all 0
+        (0..count)
+            .filter(move |&i| i != count / 2)
+            .map(move |mut i| {
+                let mut neighbor = self;
+                for x in neighbor.0.iter_mut() {
+                    *x += (i % 3) as i64 - 1;
+                    i /= 3;
+                }
+                neighbor
+            })
+    }
+}
+
+impl<const N: usize> Default for Vector<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Vector2 {
+    pub fn new(x: i64, y: i64) -> Self {
+        Vector([x, y])
+    }
+
+    pub fn x(self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn y(self) -> i64 {
+        self.0[1]
+    }
+
+    /// A quarter turn clockwise around the origin
+    pub fn clockwise(self) -> Self {
+        Vector2::new(-self.y(), self.x())
+    }
+
+    pub fn counter_clockwise(self) -> Self {
+        Vector2::new(self.y(), -self.x())
+    }
+
+    /// `quarter_turns` clockwise around the origin, or counter-clockwise if
+    /// it's negative
+    pub fn rotate(self, quarter_turns: i64) -> Self {
+        (0..quarter_turns.rem_euclid(4)).fold(self, |x, _| x.clockwise())
+    }
+
+    /// The grid cell at this position, if it isn't negative
+    pub fn to_point(self) -> Option<grid::Point> {
+        use std::convert::TryFrom;
+        Some((
+            usize::try_from(self.x()).ok()?,
+            usize::try_from(self.y()).ok()?,
+        ))
+    }
+}
+
+impl From<grid::Point> for Vector2 {
+    fn from((x, y): grid::Point) -> Self {
+        Vector2::new(x as i64, y as i64)
+    }
+}
+
+impl Vector3 {
+    pub fn new(x: i64, y: i64, z: i64) -> Self {
+        Vector([x, y, z])
+    }
+}
+
+impl Vector4 {
+    pub fn new(x: i64, y: i64, z: i64, w: i64) -> Self {
+        Vector([x, y, z, w])
+    }
+}
+
+impl<const N: usize> Add for Vector<N> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<const N: usize> AddAssign for Vector<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (x, y) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *x += y;
+        }
+    }
+}
+
+impl<const N: usize> Sub for Vector<N> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const N: usize> SubAssign for Vector<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for (x, y) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *x -= y;
+        }
+    }
+}
+
+impl<const N: usize> Mul<i64> for Vector<N> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: i64) -> Self {
+        for x in self.0.iter_mut() {
+            *x *= rhs;
+        }
+        self
+    }
+}
+
+impl<const N: usize> Neg for Vector<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * -1
+    }
+}
+
+impl<const N: usize> Index<usize> for Vector<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Vector<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        &mut self.0[axis]
+    }
+}
+
+/// A compass direction on a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Clockwise from north
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// One step this way
+    pub fn vector(self) -> Vector2 {
+        match self {
+            Direction::North => Vector2::new(0, -1),
+            Direction::East => Vector2::new(1, 0),
+            Direction::South => Vector2::new(0, 1),
+            Direction::West => Vector2::new(-1, 0),
+        }
+    }
+
+    pub fn clockwise(self) -> Direction {
+        self.turn(1)
+    }
+
+    pub fn counter_clockwise(self) -> Direction {
+        self.turn(-1)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn(2)
+    }
+
+    /// `quarter_turns` clockwise, or counter-clockwise if it's negative
+    pub fn turn(self, quarter_turns: i64) -> Direction {
+        let index = Direction::ALL.iter().position(|&x| x == self).unwrap();
+        Direction::ALL[(index as i64 + quarter_turns).rem_euclid(4) as usize]
+    }
+}
+
+/// A direction between neighboring hexagons, which have flat sides to the
+/// east and west
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl HexDirection {
+    /// Clockwise from east
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::SouthEast,
+        HexDirection::SouthWest,
+        HexDirection::West,
+        HexDirection::NorthWest,
+        HexDirection::NorthEast,
+    ];
+
+    /// One step this way in cube coordinates
+    pub fn vector(self) -> Vector3 {
+        match self {
+            HexDirection::East => Vector3::new(1, -1, 0),
+            HexDirection::SouthEast => Vector3::new(0, -1, 1),
+            HexDirection::SouthWest => Vector3::new(-1, 0, 1),
+            HexDirection::West => Vector3::new(-1, 1, 0),
+            HexDirection::NorthWest => Vector3::new(0, 1, -1),
+            HexDirection::NorthEast => Vector3::new(1, 0, -1),
+        }
+    }
+}
+
+/// A hexagon in cube coordinates, which always sum to zero
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hex(Vector3);
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex(Vector::ZERO);
+
+    /// Panics if the coordinates don't sum to zero
+    pub fn from_cube(cube: Vector3) -> Self {
+        assert_eq!(cube.0.iter().sum::<i64>(), 0, "{:?} isn't a hex", cube);
+        Hex(cube)
+    }
+
+    pub fn cube(self) -> Vector3 {
+        self.0
+    }
+
+    /// Axial coordinates `(q, r)` drop the redundant middle cube coordinate
+    pub fn from_axial(axial: Vector2) -> Self {
+        let (q, r) = (axial.x(), axial.y());
+        Hex(Vector3::new(q, -q - r, r))
+    }
+
+    pub fn axial(self) -> Vector2 {
+        Vector2::new(self.0[0], self.0[2])
+    }
+
+    pub fn neighbor(self, direction: HexDirection) -> Hex {
+        Hex(self.0 + direction.vector())
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Hex> {
+        HexDirection::ALL.iter().map(move |&x| self.neighbor(x))
+    }
+
+    /// The fewest steps between two hexes
+    pub fn distance(self, other: Hex) -> i64 {
+        self.0.manhattan_distance(other.0) / 2
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vectors() {
+        let a = Vector3::new(1, -2, 3);
+        assert_eq!(a + a, a * 2);
+        assert_eq!(a - a, Vector::ZERO);
+        assert_eq!(-a, Vector3::new(-1, 2, -3));
+        assert_eq!(a.manhattan(), 6);
+        assert_eq!(a.chebyshev(), 3);
+        assert_eq!(a.chebyshev_distance(Vector3::new(0, 0, 0)), 3);
+        assert_eq!(Vector2::new(1, 2).extend::<4>(), Vector4::new(1, 2, 0, 0));
+        assert_eq!(Vector2::new(-1, 2).to_point(), None);
+        assert_eq!(Vector2::from((3, 4)).to_point(), Some((3, 4)));
+    }
+
+    #[test]
+    fn moore_neighbors() {
+        let neighbors: Vec<_> = Vector4::ZERO.moore_neighbors().collect();
+        assert_eq!(neighbors.len(), 80);
+        assert!(neighbors.iter().all(|x| x.chebyshev() == 1));
+    }
+
+    #[test]
+    fn rotation() {
+        let v = Vector2::new(10, -4);
+        assert_eq!(v.clockwise(), Vector2::new(4, 10));
+        assert_eq!(v.rotate(-1), v.counter_clockwise());
+        assert_eq!(v.rotate(4), v);
+        assert_eq!(v.rotate(2), -v);
+
+        for direction in Direction::ALL.iter() {
+            assert_eq!(
+                direction.clockwise().vector(),
+                direction.vector().clockwise()
+            );
+            assert_eq!(direction.turn(-3), direction.clockwise());
+            assert_eq!(direction.opposite().vector(), -direction.vector());
+        }
+    }
+
+    #[test]
+    fn hexes() {
+        let hex = Hex::from_axial(Vector2::new(2, -1));
+        assert_eq!(hex.axial(), Vector2::new(2, -1));
+        assert_eq!(hex.distance(Hex::ORIGIN), 2);
+        assert!(hex.neighbors().all(|x| x.distance(hex) == 1));
+        let there_and_back = HexDirection::ALL
+            .iter()
+            .fold(hex, |hex, &direction| hex.neighbor(direction));
+        assert_eq!(there_and_back, hex);
+    }
+}
@@ -0,0 +1,380 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// `(x, y)`, with `y` counting down from the top row
+pub type Point = (usize, usize);
+
+/// Up, right, down, left
+pub const NEIGHBORS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Clockwise from up
+pub const NEIGHBORS8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A rectangle of cells stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// One of the 8 ways to rotate and flip a grid. The flips are applied
+/// first, then the rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Transform {
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// A quarter turn anticlockwise
+    pub rotated: bool,
+}
+
+impl Transform {
+    pub fn all() -> impl Iterator<Item = Transform> {
+        let booleans = || vec![true, false].into_iter();
+        booleans().flat_map(move |rotated| {
+            booleans().flat_map(move |flip_x| {
+                booleans().map(move |flip_y| Transform {
+                    flip_x,
+                    flip_y,
+                    rotated,
+                })
+            })
+        })
+    }
+
+    /// The size of a `width` by `height` grid after transforming it
+    pub fn dimensions(self, width: usize, height: usize) -> (usize, usize) {
+        if self.rotated {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Where `point` of the transformed grid comes from in a `width` by
+    /// `height` original
+    pub fn source(self, (mut x, mut y): Point, width: usize, height: usize) -> Point {
+        let (width, height) = self.dimensions(width, height);
+        if self.flip_x {
+            x = (width - 1) - x;
+        }
+        if self.flip_y {
+            y = (height - 1) - y;
+        }
+        if self.rotated {
+            let (new_x, new_y) = ((height - 1) - y, x);
+            x = new_x;
+            y = new_y;
+        }
+        (x, y)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Panics unless there are exactly `width * height` cells
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "grid is the wrong size");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Grid::new(width, height, cells)
+    }
+
+    /// Panics if the rows aren't all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        assert!(rows.iter().all(|x| x.len() == width), "rows are ragged");
+        Grid::new(width, height, rows.into_iter().flatten().collect())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.cells[self.index(point)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            let index = self.index(point);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// Treats the grid as repeating forever to the right and down. An empty
+    /// grid has nothing to repeat, so that's `None`.
+    pub fn get_wrapping(&self, (x, y): Point) -> Option<&T> {
+        if self.width == 0 || self.height == 0 {
+            None
+        } else {
+            Some(&self[(x % self.width, y % self.height)])
+        }
+    }
+
+    /// The cell at `point` of this grid as if `transform` had been applied
+    pub fn get_transformed(&self, transform: Transform, point: Point) -> Option<&T> {
+        let (width, height) = transform.dimensions(self.width, self.height);
+        if point.0 < width && point.1 < height {
+            self.get(transform.source(point, self.width, self.height))
+        } else {
+            None
+        }
+    }
+
+    fn index(&self, (x, y): Point) -> usize {
+        y * self.width + x
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid::new(
+            self.width,
+            self.height,
+            self.iter().map(|(point, x)| f(point, x)).collect(),
+        )
+    }
+
+    /// `point` moved by `(dx, dy)`, if that's still on the grid
+    pub fn offset(&self, (x, y): Point, (dx, dy): (isize, isize)) -> Option<Point> {
+        let point = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        if self.contains(point) {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        directions
+            .iter()
+            .filter_map(move |&direction| self.offset(point, direction))
+            .map(move |x| (x, &self[x]))
+    }
+
+    /// Cells sharing an edge with `point`
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbors(point, &NEIGHBORS4)
+    }
+
+    /// Cells sharing an edge or corner with `point`
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbors(point, &NEIGHBORS8)
+    }
+
+    /// Every cell from `point` in a straight line until the edge of the
+    /// grid, not including `point` itself
+    pub fn ray(
+        &self,
+        point: Point,
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = (Point, &T)> {
+        std::iter::successors(self.offset(point, direction), move |&x| {
+            self.offset(x, direction)
+        })
+        .map(move |x| (x, &self[x]))
+    }
+
+    /// Renders one character per cell
+    pub fn display_with<F: Fn(&T) -> char>(&self, f: F) -> DisplayWith<'_, T, F> {
+        DisplayWith { grid: self, f }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Grid<T> {
+        let (width, height) = transform.dimensions(self.width, self.height);
+        Grid::from_fn(width, height, |point| {
+            self[transform.source(point, self.width, self.height)].clone()
+        })
+    }
+
+    /// The `width` by `height` rectangle with its top left corner at `(x, y)`.
+    /// Panics if that goes off the grid.
+    pub fn sub_grid(&self, (x, y): Point, width: usize, height: usize) -> Grid<T> {
+        assert!(x + width <= self.width && y + height <= self.height);
+        Grid::from_fn(width, height, |(dx, dy)| self[(x + dx, y + dy)].clone())
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside a {}x{} grid",
+                point, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{:?} is outside a {}x{} grid", point, width, height))
+    }
+}
+
+pub struct DisplayWith<'a, T, F> {
+    grid: &'a Grid<T>,
+    f: F,
+}
+
+impl<T, F: Fn(&T) -> char> fmt::Display for DisplayWith<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.grid.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", (self.f)(cell))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn numbered(width: usize, height: usize) -> Grid<usize> {
+        Grid::from_fn(width, height, |(x, y)| y * width + x)
+    }
+
+    fn digits(grid: &Grid<usize>) -> String {
+        grid.display_with(|&x| std::char::from_digit(x as u32, 36).unwrap())
+            .to_string()
+    }
+
+    #[test]
+    fn access() {
+        let grid = numbered(3, 2);
+        assert_eq!(grid[(2, 1)], 5);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get_wrapping((4, 3)), Some(&4));
+        assert_eq!(digits(&grid), "012\n345");
+        assert_eq!(Grid::from_rows(vec![vec![0, 1, 2], vec![3, 4, 5]]), grid);
+    }
+
+    #[test]
+    fn empty_grids_dont_wrap() {
+        assert_eq!(numbered(0, 0).get_wrapping((1, 1)), None);
+        assert_eq!(numbered(3, 0).get_wrapping((1, 0)), None);
+        assert_eq!(
+            Grid::<usize>::from_rows(vec![vec![], vec![]]).get_wrapping((0, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = numbered(3, 3);
+        let cells = |x: Vec<(Point, &usize)>| x.into_iter().map(|(_, &x)| x).collect::<Vec<_>>();
+        assert_eq!(cells(grid.neighbors4((0, 0)).collect()), vec![1, 3]);
+        assert_eq!(
+            cells(grid.neighbors8((1, 1)).collect()),
+            vec![1, 2, 5, 8, 7, 6, 3, 0]
+        );
+        assert_eq!(cells(grid.ray((0, 0), (1, 1)).collect()), vec![4, 8]);
+        assert_eq!(grid.ray((2, 2), (1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = numbered(3, 2);
+        let rotated = Transform {
+            rotated: true,
+            ..Default::default()
+        };
+        assert_eq!(digits(&grid.transformed(rotated)), "25\n14\n03");
+        let flipped = Transform {
+            flip_x: true,
+            ..Default::default()
+        };
+        assert_eq!(digits(&grid.transformed(flipped)), "210\n543");
+
+        let all: Vec<_> = Transform::all()
+            .map(|x| digits(&grid.transformed(x)))
+            .collect();
+        assert_eq!(all.len(), 8);
+        assert!(all
+            .iter()
+            .all(|x| all.iter().filter(|&y| x == y).count() == 1));
+
+        for transform in Transform::all() {
+            let transformed = grid.transformed(transform);
+            for (point, cell) in transformed.iter() {
+                assert_eq!(grid.get_transformed(transform, point), Some(cell));
+            }
+        }
+    }
+
+    #[test]
+    fn sub_grids() {
+        let grid = numbered(4, 4);
+        assert_eq!(digits(&grid.sub_grid((1, 1), 2, 2)), "56\n9a");
+    }
+}
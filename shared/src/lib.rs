@@ -1,3 +1,16 @@
+pub mod alloc;
+pub mod automaton;
+pub mod cache;
+pub mod cancel;
+pub mod differential;
+pub mod extras;
+pub mod geometry;
+pub mod grid;
+pub mod math;
+pub mod parse_error;
 pub mod prelude;
+pub mod puzzle;
 pub mod puzzle_input;
-
+pub mod render;
+pub mod simulation;
+pub mod trace;
@@ -0,0 +1,204 @@
+use std::{collections::HashMap, convert::TryFrom};
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+/// Panics if the result doesn't fit in a `u64`
+pub fn lcm(a: u64, b: u64) -> u64 {
+    checked_lcm(a, b).unwrap_or_else(|| panic!("lcm({}, {}) overflows", a, b))
+}
+
+/// `None` if the result doesn't fit in a `u64`
+pub fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// `(g, x, y)` where `g` is the gcd of `a` and `b`, and `a * x + b * y == g`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_i128(a.into(), b.into());
+    // |x| and |y| are at most max(|a|, |b|), so only gcd(i64::MIN, 0) or
+    // gcd(i64::MIN, i64::MIN) can fail here
+    let narrow = |n: i128| i64::try_from(n).expect("extended_gcd overflows");
+    (narrow(g), narrow(x), narrow(y))
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a` reduced into `0..modulus`, even if it's negative
+pub fn rem(a: i64, modulus: u64) -> u64 {
+    (i128::from(a).rem_euclid(modulus.into())) as u64
+}
+
+pub fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(modulus)) as u64
+}
+
+/// `base` to the power of `exponent`, by squaring
+pub fn pow_mod(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// The `x` in `0..modulus` where `a * x ≡ 1`, if `a` and `modulus` are coprime
+pub fn mod_inverse(a: i64, modulus: u64) -> Option<u64> {
+    mod_inverse_i128(a.into(), modulus)
+}
+
+fn mod_inverse_i128(a: i128, modulus: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd_i128(a, modulus.into());
+    if g == 1 {
+        Some(x.rem_euclid(modulus.into()) as u64)
+    } else {
+        None
+    }
+}
+
+/// Combines congruences `x ≡ residue (mod modulus)` into a single
+/// `(residue, modulus)` that satisfies them all, with the residue in
+/// `0..modulus`. Moduli must be positive but needn't be coprime. Returns
+/// `None` if the congruences contradict each other, or if the combined
+/// modulus doesn't fit in a `u64`.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, u64)>) -> Option<(u64, u64)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(a1, m1), (a2, m2)| {
+            let a2 = rem(a2, m2);
+            let g = gcd(m1, m2);
+            let difference = i128::from(a2) - i128::from(a1);
+            if difference % i128::from(g) != 0 {
+                return None;
+            }
+
+            // x = a1 + m1 * k, where m1 * k ≡ a2 - a1 (mod m2)
+            let m2_reduced = m2 / g;
+            let inverse = mod_inverse_i128((m1 / g).into(), m2_reduced)?;
+            let reduced = (difference / i128::from(g)).rem_euclid(m2_reduced.into()) as u64;
+            let k = mul_mod(reduced, inverse, m2_reduced);
+
+            // Everything's below 2^64, so this fits in a u128
+            let modulus = checked_lcm(m1, m2)?;
+            let residue = (u128::from(a1) + u128::from(m1) * u128::from(k)) % u128::from(modulus);
+            Some((residue as u64, modulus))
+        })
+}
+
+/// The smallest `x` where `base^x ≡ target (mod modulus)`, using baby-step
+/// giant-step. Returns `None` if there isn't one, or if `base` and `modulus`
+/// aren't coprime.
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    let target = target % modulus;
+    let steps = (modulus as f64).sqrt().ceil() as u64 + 1;
+
+    // Baby steps: base^j for every j under `steps`, keeping the smallest j
+    let mut baby_steps = HashMap::new();
+    let mut power = 1 % modulus;
+    for j in 0..steps {
+        baby_steps.entry(power).or_insert(j);
+        power = mul_mod(power, base, modulus);
+    }
+
+    // Giant steps: target * base^(-steps * i), looking for a baby step
+    let giant_step = mod_inverse_i128(pow_mod(base, steps, modulus).into(), modulus)?;
+    let mut gamma = target;
+    for i in 0..steps {
+        if let Some(j) = baby_steps.get(&gamma) {
+            return Some(i * steps + j);
+        }
+        gamma = mul_mod(gamma, giant_step, modulus);
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn euclid() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(-4, 6).0, 2);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 8), None);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(rem(-1, 7), 6);
+        assert_eq!(pow_mod(7, 8, 20201227), 5764801);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+        let big = u64::MAX - 1;
+        assert_eq!(mul_mod(big, big, u64::MAX), 1);
+        assert_eq!(pow_mod(big, 1 << 40, u64::MAX), 1);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(vec![(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(vec![(0, 7), (-1, 13)]), Some((77, 91)));
+        // Non-coprime moduli
+        assert_eq!(crt(vec![(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(vec![(1, 4), (2, 6)]), None);
+        assert_eq!(crt(vec![]), Some((0, 1)));
+
+        let primes = [1_000_000_007, 998_244_353];
+        let (x, modulus) = crt(primes.iter().map(|&p| (-1, p))).unwrap();
+        assert_eq!(x, modulus - 1);
+
+        // Coprime moduli whose product needs more than 64 bits
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(crt(vec![(0, u64::MAX), (1, u64::MAX - 1)]), None);
+        let (x, modulus) = crt(vec![(3, 1 << 62), (1, 3)]).unwrap();
+        assert_eq!((x % (1 << 62), x % 3, modulus), (3, 1, 3 << 62));
+    }
+
+    #[test]
+    fn discrete_logs() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(2, 1, 11), Some(0));
+        assert_eq!(discrete_log(2, 3, 7), None);
+        assert_eq!(discrete_log(2, 3, 8), None);
+        for x in 0..100 {
+            let target = pow_mod(3, x, 1_000_003);
+            let found = discrete_log(3, target, 1_000_003).unwrap();
+            assert!(found <= x);
+            assert_eq!(pow_mod(3, found, 1_000_003), target);
+        }
+    }
+}
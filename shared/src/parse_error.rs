@@ -0,0 +1,205 @@
+use std::{fmt, str::FromStr};
+
+/// A parse failure pointing at the offending part of one line of input.
+/// Renders as a caret-underlined snippet:
+///
+/// ```text
+/// line 3, column 5: expected a signed number
+///   |
+/// 3 | acc x12
+///   |     ^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the puzzle input, once the caller knows it
+    pub line: Option<usize>,
+    /// Byte offset of the problem within `text`
+    pub column: usize,
+    /// Length in bytes of the problem
+    pub len: usize,
+    /// The whole line the problem is on
+    pub text: String,
+    /// What should have been there, e.g. "a number" or "`nop`, `acc` or `jmp`"
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Blames the whole of `text`
+    pub fn new(text: &str, expected: impl Into<String>) -> Self {
+        ParseError {
+            line: None,
+            column: 0,
+            len: text.len(),
+            text: text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Blames `part`, which must be a slice of `text`. Falls back to the
+    /// whole line if it isn't.
+    pub fn at(text: &str, part: &str, expected: impl Into<String>) -> Self {
+        match offset_of(text, part) {
+            Some(column) => ParseError {
+                column,
+                len: part.len(),
+                ..ParseError::new(text, expected)
+            },
+            None => ParseError::new(text, expected),
+        }
+    }
+
+    /// Blames `len` bytes of `text` starting at byte `column`
+    pub fn at_column(text: &str, column: usize, len: usize, expected: impl Into<String>) -> Self {
+        ParseError {
+            column: column.min(text.len()),
+            len,
+            ..ParseError::new(text, expected)
+        }
+    }
+
+    /// Blames the end of `text`, for input that stops too early
+    pub fn at_end(text: &str, expected: impl Into<String>) -> Self {
+        ParseError::at_column(text, text.len(), 1, expected)
+    }
+
+    /// Sets the line number if nothing closer to the error has already
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Re-anchors an error found while parsing `inner`, a slice of `outer`,
+    /// so it points into `outer` instead
+    pub fn within(self, outer: &str, inner: &str) -> Self {
+        match offset_of(outer, inner) {
+            Some(offset) => ParseError {
+                column: self.column + offset,
+                text: outer.to_string(),
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
+fn offset_of(text: &str, part: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let part_start = part.as_ptr() as usize;
+    if part_start >= start && part_start + part.len() <= start + text.len() {
+        Some(part_start - start)
+    } else {
+        None
+    }
+}
+
+/// Parses `part` of `text` with [`FromStr`], blaming `part` if it fails
+pub fn parse_at<T: FromStr>(text: &str, part: &str, expected: &str) -> Result<T, ParseError> {
+    part.parse()
+        .map_err(|_| ParseError::at(text, part, expected))
+}
+
+/// Parses each line, numbering any error by its position in `lines`
+pub fn parse_lines<'a, T>(
+    lines: &[&'a str],
+    parse: impl Fn(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    parse_lines_from(1, lines, parse)
+}
+
+/// Like [`parse_lines`] for a section of the input that starts on `first_line`
+pub fn parse_lines_from<'a, T>(
+    first_line: usize,
+    lines: &[&'a str],
+    parse: impl Fn(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|err| err.on_line(first_line + i)))
+        .collect()
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let before = self.text.get(..self.column).unwrap_or(&self.text);
+        let column = before.chars().count() + 1;
+        let underlined = self
+            .text
+            .get(self.column..(self.column + self.len).min(self.text.len()))
+            .map_or(0, |x| x.chars().count())
+            .max(1);
+
+        let gutter = self.line.map(|x| x.to_string()).unwrap_or_default();
+        let padding = " ".repeat(gutter.len());
+
+        match self.line {
+            Some(line) => write!(f, "line {}, column {}", line, column)?,
+            None => write!(f, "column {}", column)?,
+        }
+        writeln!(f, ": expected {}", self.expected)?;
+        writeln!(f, "{} |", padding)?;
+        writeln!(f, "{} | {}", gutter, self.text)?;
+        write!(
+            f,
+            "{} | {}{}",
+            padding,
+            " ".repeat(column - 1),
+            "^".repeat(underlined)
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_snippet() {
+        let line = "acc x12";
+        let err = ParseError::at(line, &line[4..], "a signed number").on_line(3);
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 5: expected a signed number\n  |\n3 | acc x12\n  |     ^^^"
+        );
+    }
+
+    #[test]
+    fn renders_without_line() {
+        let err = ParseError::at_end("mask =", "a 36 bit mask");
+        assert_eq!(
+            err.to_string(),
+            "column 7: expected a 36 bit mask\n |\n | mask =\n |       ^"
+        );
+    }
+
+    #[test]
+    fn foreign_slices_blame_whole_line() {
+        let err = ParseError::at("abc", "b", "x");
+        assert_eq!((err.column, err.len), (0, 3));
+    }
+
+    #[test]
+    fn within_shifts_column() {
+        let line = "light red bags contain 1 brght white bag.";
+        let inner = &line[23..];
+        let err = ParseError::at(inner, &inner[2..7], "a color").within(line, inner);
+        assert_eq!((err.column, err.len, err.text.as_str()), (25, 5, line));
+    }
+
+    #[test]
+    fn numbers_lines() {
+        let err = parse_lines(&["1", "2", "x"], |line| {
+            parse_at::<u8>(line, line, "a number")
+        })
+        .unwrap_err();
+        assert_eq!(err.line, Some(3));
+
+        let err = parse_lines_from(10, &["1", "x"], |line| {
+            parse_at::<u8>(line, line, "a number").map_err(|err| err.on_line(1))
+        })
+        .unwrap_err();
+        assert_eq!(err.line, Some(1));
+    }
+}
@@ -1,3 +1,6 @@
+pub use crate::grid::{self, Grid};
+pub use crate::parse_error::{self, ParseError};
+pub use crate::puzzle::{Part, Puzzle};
 pub use crate::puzzle_input;
 pub use ::anyhow::{self, anyhow};
 pub use lazy_static::*;
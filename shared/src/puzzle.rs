@@ -0,0 +1,300 @@
+use std::{
+    any::Any,
+    fmt::{self, Display},
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::alloc::{self, AllocStats};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn all() -> impl Iterator<Item = Part> {
+        vec![Part::One, Part::Two].into_iter()
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            other => Err(anyhow::anyhow!("part must be 1 or 2, got {}", other)),
+        }
+    }
+}
+
+/// A single day's puzzle: how to parse its input, and how to answer both parts from the parsed form.
+pub trait Puzzle {
+    const DAY: u8;
+    const TITLE: &'static str;
+    /// Day 25 only has one part
+    const PARTS: &'static [Part] = &[Part::One, Part::Two];
+    /// Bump this when a change could alter the answers, so cached ones are
+    /// worked out again. There's no default, so every day has to pick one.
+    const VERSION: &'static str;
+
+    type Input<'a>;
+    type PartOne: Display;
+    type PartTwo: Display;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input<'_>>;
+    fn part_one(input: &Self::Input<'_>) -> anyhow::Result<Self::PartOne>;
+    fn part_two(input: &Self::Input<'_>) -> anyhow::Result<Self::PartTwo>;
+}
+
+/// One part's answer as the puzzle's own type, before it's turned into text
+pub enum Answer<P: Puzzle + ?Sized> {
+    One(P::PartOne),
+    Two(P::PartTwo),
+}
+
+impl<P: Puzzle + ?Sized> Display for Answer<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::One(x) => x.fmt(f),
+            Answer::Two(x) => x.fmt(f),
+        }
+    }
+}
+
+/// A part that panicked, as an error so the other parts' answers are kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panicked(pub String);
+
+impl Display for Panicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "panicked: {}", self.0)
+    }
+}
+
+impl std::error::Error for Panicked {}
+
+/// The message a panic was raised with, if it has one
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|x| x.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(no message)".to_string())
+}
+
+/// Answers from [`Solver::solve_timed`], with how long each step took
+#[derive(Debug)]
+pub struct Timed {
+    pub parse: Duration,
+    /// Each part's answer, or why it has none
+    pub answers: Vec<(Part, anyhow::Result<String>, Duration)>,
+    /// What the parse allocated, then each part, when [`alloc::Counting`] is
+    /// installed
+    pub allocs: Vec<AllocStats>,
+}
+
+/// Object-safe view of a [`Puzzle`], so tooling can hold every day in one list.
+pub trait Solver: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn parts(&self) -> &'static [Part];
+    fn version(&self) -> &'static str;
+    /// Parses the input once, then answers each requested part in order. Only
+    /// the parse failing is an error for every part; a part that fails or
+    /// panics doesn't stop the others.
+    fn solve(&self, input: &str, parts: &[Part]) -> anyhow::Result<Vec<anyhow::Result<String>>> {
+        let timed = self.solve_timed(input, parts)?;
+        Ok(timed.answers.into_iter().map(|(_, x, _)| x).collect())
+    }
+    /// Like [`Solver::solve`], timing the parse and each part separately
+    fn solve_timed(&self, input: &str, parts: &[Part]) -> anyhow::Result<Timed>;
+}
+
+impl<P: Puzzle + Sync> Solver for P {
+    fn day(&self) -> u8 {
+        P::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        P::TITLE
+    }
+
+    fn parts(&self) -> &'static [Part] {
+        P::PARTS
+    }
+
+    fn version(&self) -> &'static str {
+        P::VERSION
+    }
+
+    fn solve_timed(&self, input: &str, parts: &[Part]) -> anyhow::Result<Timed> {
+        solve_timed_with::<P>(input, parts, |_, _| ())
+    }
+}
+
+/// Like [`Solver::solve_timed`], also showing `inspect` each answer that's
+/// worked out before it's turned into text, outside the part's timing
+pub fn solve_timed_with<P: Puzzle>(
+    input: &str,
+    parts: &[Part],
+    mut inspect: impl FnMut(&P::Input<'_>, &Answer<P>),
+) -> anyhow::Result<Timed> {
+    let start = Instant::now();
+    let (parsed, parse_allocs) = alloc::measure(|| P::parse(input));
+    let parsed = parsed?;
+    let parse = start.elapsed();
+
+    let mut allocs = vec![parse_allocs];
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let (answer, part_allocs) = alloc::measure(|| {
+                panic::catch_unwind(AssertUnwindSafe(|| match part {
+                    Part::One => P::part_one(&parsed).map(Answer::One),
+                    Part::Two => P::part_two(&parsed).map(Answer::Two),
+                }))
+                .unwrap_or_else(|payload| Err(Panicked(panic_message(&*payload)).into()))
+            });
+            let elapsed = start.elapsed();
+            allocs.push(part_allocs);
+            let answer = answer.map(|answer| {
+                inspect(&parsed, &answer);
+                answer.to_string()
+            });
+            (part, answer, elapsed)
+        })
+        .collect();
+
+    Ok(Timed {
+        parse,
+        answers,
+        allocs,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+
+    struct Sum;
+
+    impl Puzzle for Sum {
+        const DAY: u8 = 0;
+        const TITLE: &'static str = "Sum";
+        const VERSION: &'static str = "1";
+        type Input<'a> = Vec<&'a str>;
+        type PartOne = i32;
+        type PartTwo = usize;
+
+        fn parse(input: &str) -> anyhow::Result<Self::Input<'_>> {
+            Ok(puzzle_input::lines(input))
+        }
+
+        fn part_one(input: &Self::Input<'_>) -> anyhow::Result<i32> {
+            input
+                .iter()
+                .map(|x| x.parse::<i32>().map_err(anyhow::Error::from))
+                .sum()
+        }
+
+        fn part_two(input: &Self::Input<'_>) -> anyhow::Result<usize> {
+            Ok(input.len())
+        }
+    }
+
+    struct HalfDone;
+
+    impl Puzzle for HalfDone {
+        const DAY: u8 = 0;
+        const TITLE: &'static str = "Half done";
+        const VERSION: &'static str = "1";
+        type Input<'a> = ();
+        type PartOne = &'static str;
+        type PartTwo = &'static str;
+
+        fn parse(_: &str) -> anyhow::Result<Self::Input<'_>> {
+            Ok(())
+        }
+
+        fn part_one(_: &Self::Input<'_>) -> anyhow::Result<&'static str> {
+            Ok("done")
+        }
+
+        fn part_two(_: &Self::Input<'_>) -> anyhow::Result<&'static str> {
+            panic!("not done yet")
+        }
+    }
+
+    fn answers(solver: &dyn Solver, input: &str, parts: &[Part]) -> Vec<String> {
+        let answers = solver.solve(input, parts).unwrap();
+        answers.into_iter().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn solver_answers_requested_parts() {
+        let solver: &dyn Solver = &Sum;
+        assert_eq!(solver.day(), 0);
+        assert_eq!(solver.parts(), &[Part::One, Part::Two]);
+        assert_eq!(
+            answers(solver, "1\n2\n3\n", &[Part::Two, Part::One]),
+            vec!["3", "6"]
+        );
+    }
+
+    #[test]
+    fn counts_each_phases_allocations() {
+        // The tests in `alloc` install its allocator
+        let timed = Sum.solve_timed("1\n2\n3\n", &[Part::Two]).unwrap();
+        assert_eq!(timed.allocs.len(), 2);
+        assert!(timed.allocs[0].allocations > 0);
+    }
+
+    #[test]
+    fn parse_part() {
+        assert_eq!("2".parse::<Part>().unwrap(), Part::Two);
+        assert!("3".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn solver_surfaces_errors() {
+        let answers = Sum.solve("1\nx\n", &[Part::One, Part::Two]).unwrap();
+        assert!(answers[0].is_err());
+        assert_eq!(answers[1].as_ref().unwrap(), "2");
+    }
+
+    #[test]
+    fn shows_answers_before_theyre_text() {
+        let mut seen = Vec::new();
+        let timed = solve_timed_with::<Sum>("1\nx\n", &[Part::One, Part::Two], |input, answer| {
+            seen.push((input.len(), matches!(answer, Answer::Two(2))))
+        })
+        .unwrap();
+        assert!(timed.answers[0].1.is_err());
+        assert_eq!(seen, vec![(2, true)]);
+    }
+
+    #[test]
+    fn solver_keeps_answers_from_before_a_panic() {
+        let answers = HalfDone.solve("", &[Part::One, Part::Two]).unwrap();
+        assert_eq!(answers[0].as_ref().unwrap(), "done");
+        let err = answers[1].as_ref().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Panicked>(),
+            Some(&Panicked("not done yet".to_string()))
+        );
+    }
+}
@@ -1,3 +1,258 @@
+use std::{
+    env, fmt, fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+
+mod stream;
+mod structure;
+
+pub use stream::*;
+pub use structure::*;
+
+/// Points at a directory of `dayNN.txt` files to use instead of `inputs/`
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// The input's lines, without trailing whitespace. Leading blank lines are
+/// kept, so indices still give the right line numbers.
 pub fn lines(puzzle_input: &str) -> Vec<&str> {
-    puzzle_input.trim().lines().collect()
+    puzzle_input.trim_end().lines().collect()
+}
+
+/// Where to read a puzzle input from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Path(PathBuf),
+    Stdin,
+    /// Search the conventional locations for a given day, see [`search_paths`]
+    Day(u8),
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing { day: u8, searched: Vec<PathBuf> },
+    Unreadable { path: PathBuf, source: io::Error },
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing { day, searched } => {
+                write!(f, "No puzzle input for day {}, looked in ", day)?;
+                let searched: Vec<_> = searched.iter().map(|x| x.display().to_string()).collect();
+                write!(f, "{}", searched.join(", "))
+            }
+            InputError::Unreadable { path, source } => {
+                write!(f, "Couldn't read {}: {}", path.display(), source)
+            }
+            InputError::Stdin(source) => write!(f, "Couldn't read stdin: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Missing { .. } => None,
+            InputError::Unreadable { source, .. } | InputError::Stdin(source) => Some(source),
+        }
+    }
+}
+
+impl InputError {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, InputError::Missing { .. })
+    }
+}
+
+pub fn read(source: &Source) -> Result<String, InputError> {
+    match source {
+        Source::Path(path) => read_path(path),
+        Source::Stdin => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(InputError::Stdin)?;
+            Ok(buffer)
+        }
+        Source::Day(day) => load(*day),
+    }
+}
+
+/// Like [`read`], but leaves the reading to the caller, for inputs too big to
+/// hold in memory
+pub fn open(source: &Source) -> Result<Box<dyn BufRead>, InputError> {
+    let path = match source {
+        Source::Path(path) => path.clone(),
+        Source::Stdin => return Ok(Box::new(io::stdin().lock())),
+        Source::Day(day) => {
+            let searched = search_paths(*day);
+            match searched.iter().find(|path| path.is_file()) {
+                Some(path) => path.clone(),
+                None => {
+                    return Err(InputError::Missing {
+                        day: *day,
+                        searched,
+                    })
+                }
+            }
+        }
+    };
+    match fs::File::open(&path) {
+        Ok(file) => Ok(Box::new(BufReader::new(file))),
+        Err(source) => Err(InputError::Unreadable { path, source }),
+    }
+}
+
+/// Reads the first input for `day` that exists in [`search_paths`]
+pub fn load(day: u8) -> Result<String, InputError> {
+    let searched = search_paths(day);
+    match searched.iter().find(|path| path.is_file()) {
+        Some(path) => read_path(path),
+        None => Err(InputError::Missing { day, searched }),
+    }
+}
+
+/// Whether `day` has a personal input anywhere in [`search_paths`]
+pub fn has_input(day: u8) -> bool {
+    search_paths(day).iter().any(|path| path.is_file())
+}
+
+/// For tests that need a day's personal input, which isn't checked in.
+/// Returns from the test early when there isn't one, and says it was skipped.
+///
+/// ```ignore
+/// #[test]
+/// fn answer() {
+///     shared::skip_without_input!(7);
+///     assert_eq!(solve(&PUZZLE_INPUT), 155);
+/// }
+/// ```
+#[macro_export]
+macro_rules! skip_without_input {
+    ($day:expr) => {
+        if $crate::puzzle_input::skip_without_input($day, module_path!()) {
+            return;
+        }
+    };
+}
+
+#[doc(hidden)]
+pub fn skip_without_input(day: u8, test: &str) -> bool {
+    if has_input(day) {
+        return false;
+    }
+    // Straight to stderr, since the test harness hides what tests print
+    let _ = writeln!(
+        io::stderr(),
+        "skipped a test in {}: no puzzle input for day {}",
+        test,
+        day
+    );
+    true
+}
+
+/// `$AOC_INPUT_DIR/dayNN.txt` if set, then `inputs/dayNN.txt` and the older
+/// `puzzles/dayNN/src/puzzle_input.txt` under the workspace root
+pub fn search_paths(day: u8) -> Vec<PathBuf> {
+    let file_name = format!("day{:02}.txt", day);
+    let root = workspace_root();
+
+    env::var_os(INPUT_DIR_VAR)
+        .map(|dir| PathBuf::from(dir).join(&file_name))
+        .into_iter()
+        .chain(vec![
+            root.join("inputs").join(&file_name),
+            root.join("puzzles")
+                .join(format!("day{:02}", day))
+                .join("src")
+                .join("puzzle_input.txt"),
+        ])
+        .collect()
+}
+
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("shared lives inside the workspace")
+}
+
+/// Parses `text`, prints it with `Display` and parses that again, panicking
+/// unless both parses agree. Returns the printed text so tests can also check
+/// it matches the original.
+pub fn assert_round_trip<T, E>(text: &str, parse: impl Fn(&str) -> Result<T, E>) -> String
+where
+    T: fmt::Display + fmt::Debug + PartialEq,
+    E: fmt::Display,
+{
+    let parsed = parse(text).unwrap_or_else(|err| panic!("couldn't parse:\n{}", err));
+    let printed = parsed.to_string();
+    let reparsed =
+        parse(&printed).unwrap_or_else(|err| panic!("couldn't parse what was printed:\n{}", err));
+    assert_eq!(parsed, reparsed, "printed as:\n{}", printed);
+    printed
+}
+
+fn read_path(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| InputError::Unreadable {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn missing_input() {
+        let err = load(99).unwrap_err();
+        assert!(err.is_missing());
+        assert!(err.to_string().contains("day99.txt"));
+        assert!(!has_input(99));
+    }
+
+    #[test]
+    fn skips_without_input() {
+        let mut reached = false;
+        let mut needs_day_99 = || {
+            crate::skip_without_input!(99);
+            reached = true;
+        };
+        needs_day_99();
+        assert!(!reached);
+    }
+
+    #[test]
+    fn explicit_path() {
+        let path = workspace_root().join("puzzles/day08/src/test_input.txt");
+        assert!(read(&Source::Path(path)).unwrap().starts_with("nop +0"));
+
+        let err = read(&Source::Path(PathBuf::from("does/not/exist.txt"))).unwrap_err();
+        assert!(matches!(err, InputError::Unreadable { .. }));
+
+        let path = workspace_root().join("puzzles/day08/src/test_input.txt");
+        let mut first = String::new();
+        open(&Source::Path(path))
+            .unwrap()
+            .read_line(&mut first)
+            .unwrap();
+        assert_eq!(first, "nop +0\n");
+        assert!(open(&Source::Day(99)).err().unwrap().is_missing());
+    }
+
+    #[test]
+    fn search_order() {
+        let paths = search_paths(7);
+        let conventional = paths
+            .iter()
+            .position(|x| x.ends_with("inputs/day07.txt"))
+            .unwrap();
+        let legacy = paths
+            .iter()
+            .position(|x| x.ends_with("day07/src/puzzle_input.txt"))
+            .unwrap();
+        assert!(conventional < legacy);
+    }
 }
@@ -0,0 +1,116 @@
+use std::io::BufRead;
+
+use crate::{
+    parse_error::ParseError,
+    puzzle::{Part, Puzzle},
+};
+
+/// Parses each line of `reader` as it's read, reusing one buffer, so memory
+/// doesn't grow with the input. Blank lines are skipped, errors are numbered
+/// by their line in the whole input, and nothing more is read after one.
+pub fn stream_lines<R, T, F>(reader: R, parse: F) -> StreamLines<R, F>
+where
+    R: BufRead,
+    F: FnMut(&str) -> Result<T, ParseError>,
+{
+    StreamLines {
+        reader,
+        parse,
+        buffer: String::new(),
+        line: 0,
+        failed: false,
+    }
+}
+
+/// The iterator from [`stream_lines`]
+#[derive(Debug)]
+pub struct StreamLines<R, F> {
+    reader: R,
+    parse: F,
+    buffer: String,
+    line: usize,
+    failed: bool,
+}
+
+impl<R, T, F> Iterator for StreamLines<R, F>
+where
+    R: BufRead,
+    F: FnMut(&str) -> Result<T, ParseError>,
+{
+    type Item = anyhow::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err.into()));
+                }
+            }
+
+            let line = self.buffer.trim_end_matches(&['\n', '\r'][..]);
+            if line.trim().is_empty() {
+                continue;
+            }
+            return match (self.parse)(line) {
+                Ok(parsed) => Some(Ok(parsed)),
+                Err(err) => {
+                    self.failed = true;
+                    Some(Err(err.on_line(self.line).into()))
+                }
+            };
+        }
+        None
+    }
+}
+
+/// Opens an input from the start each time it's called, for solvers that
+/// need more than one pass
+pub type Open<'a> = dyn FnMut() -> anyhow::Result<Box<dyn BufRead + 'a>> + 'a;
+
+/// A puzzle that can answer straight from a reader, a line at a time, in
+/// memory that doesn't grow with the input
+pub trait Stream: Puzzle {
+    fn stream_part_one(open: &mut Open<'_>) -> anyhow::Result<Self::PartOne>;
+    fn stream_part_two(open: &mut Open<'_>) -> anyhow::Result<Self::PartTwo>;
+
+    fn stream(open: &mut Open<'_>, part: Part) -> anyhow::Result<String> {
+        match part {
+            Part::One => Self::stream_part_one(open).map(|x| x.to_string()),
+            Part::Two => Self::stream_part_two(open).map(|x| x.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse_error;
+
+    fn numbers(input: &str) -> Vec<anyhow::Result<u32>> {
+        stream_lines(input.as_bytes(), |x| {
+            parse_error::parse_at(x, x, "a number")
+        })
+        .collect()
+    }
+
+    #[test]
+    fn parses_as_it_reads() {
+        let parsed: Vec<u32> = numbers("1\r\n2\n\n3")
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(parsed, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn stops_at_the_first_error() {
+        let parsed = numbers("1\n\nx\n4\n");
+        assert_eq!(parsed.len(), 2);
+        let err = parsed[1].as_ref().unwrap_err();
+        assert_eq!(err.downcast_ref::<ParseError>().unwrap().line, Some(3));
+    }
+}
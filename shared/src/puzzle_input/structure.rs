@@ -0,0 +1,310 @@
+use std::{convert::TryInto, str::FromStr};
+
+use crate::{
+    grid::Grid,
+    parse_error::{self, ParseError},
+};
+
+/// A run of lines between blank lines, remembering where it started so
+/// errors inside it get the right line number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'s, 'a> {
+    /// 1-based line number of `lines[0]` in the whole input
+    pub first_line: usize,
+    pub lines: &'s [&'a str],
+}
+
+fn leading_blank_lines(lines: &[&str]) -> usize {
+    lines.iter().take_while(|x| x.trim().is_empty()).count()
+}
+
+/// Splits `lines` on blank lines, ignoring any before the first section
+pub fn sections<'s, 'a>(lines: &'s [&'a str]) -> Vec<Section<'s, 'a>> {
+    let skipped = leading_blank_lines(lines);
+    let mut first_line = skipped + 1;
+    lines[skipped..]
+        .split(|x| x.trim().is_empty())
+        .map(|lines| {
+            let section = Section { first_line, lines };
+            first_line += lines.len() + 1;
+            section
+        })
+        .collect()
+}
+
+/// Like [`sections`] for inputs with a fixed number of sections, e.g.
+/// `let [rules, messages] = exact_sections(lines, "rules and messages")?;`
+pub fn exact_sections<'s, 'a, const N: usize>(
+    lines: &'s [&'a str],
+    expected: &str,
+) -> Result<[Section<'s, 'a>; N], ParseError> {
+    let sections = sections(lines);
+    let found = sections.len();
+    sections.try_into().map_err(|_| {
+        let expected = format!("{} sections separated by blank lines: {}", N, expected);
+        match lines
+            .iter()
+            .enumerate()
+            .skip(leading_blank_lines(lines))
+            .filter(|(_, x)| x.trim().is_empty())
+            .nth(N - 1)
+        {
+            Some((i, line)) if found > N => ParseError::new(line, expected).on_line(i + 1),
+            _ => ParseError::at_end(lines.last().copied().unwrap_or_default(), expected)
+                .on_line(lines.len().max(1)),
+        }
+    })
+}
+
+impl<'s, 'a> Section<'s, 'a> {
+    /// The whole input as one section
+    pub fn new(lines: &'s [&'a str]) -> Self {
+        Section {
+            first_line: 1,
+            lines,
+        }
+    }
+
+    /// Parses the first line with `parse`, returning it and the rest of the
+    /// section
+    pub fn parse_header<T>(
+        self,
+        expected: &str,
+        parse: impl FnOnce(&'a str) -> Result<T, ParseError>,
+    ) -> Result<(T, Section<'s, 'a>), ParseError> {
+        let (first, rest) = self
+            .lines
+            .split_first()
+            .ok_or_else(|| ParseError::new("", expected).on_line(self.first_line))?;
+        let header = parse(first).map_err(|err| err.on_line(self.first_line))?;
+
+        Ok((
+            header,
+            Section {
+                first_line: self.first_line + 1,
+                lines: rest,
+            },
+        ))
+    }
+
+    /// Checks the first line is exactly `header`, returning the rest of the
+    /// section
+    pub fn header(self, header: &str) -> Result<Section<'s, 'a>, ParseError> {
+        let expected = format!("`{}`", header);
+        let (_, rest) = self.parse_header(&expected, |line| {
+            if line == header {
+                Ok(())
+            } else {
+                Err(ParseError::new(line, expected.as_str()))
+            }
+        })?;
+        Ok(rest)
+    }
+
+    /// Parses each line, numbering errors by their place in the whole input
+    pub fn parse_lines<T>(
+        &self,
+        parse: impl Fn(&'a str) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        parse_error::parse_lines_from(self.first_line, self.lines, parse)
+    }
+
+    /// Parses a section that should only have one line
+    pub fn parse_single<T>(
+        &self,
+        expected: &str,
+        parse: impl FnOnce(&'a str) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        match self.lines {
+            [line] => parse(line).map_err(|err| err.on_line(self.first_line)),
+            [] => Err(ParseError::new("", expected).on_line(self.first_line)),
+            [_, extra, ..] => {
+                Err(ParseError::new(extra, format!("only {}", expected))
+                    .on_line(self.first_line + 1))
+            }
+        }
+    }
+
+    /// `key:value` fields separated by whitespace, possibly across several lines
+    pub fn key_values(&self) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+        Ok(self
+            .parse_lines(key_values)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    pub fn char_grid<T>(
+        &self,
+        expected: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        char_grid_from(self.first_line, self.lines, expected, cell)
+    }
+}
+
+/// Parses each piece of `line` between `separator`s
+pub fn delimited<'a, T>(
+    line: &'a str,
+    separator: &str,
+    parse: impl Fn(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    line.split(separator).map(parse).collect()
+}
+
+/// A list of numbers like `0,3,6`
+pub fn number_list<T: FromStr>(line: &str, separator: &str) -> Result<Vec<T>, ParseError> {
+    delimited(line, separator, |x| {
+        parse_error::parse_at(line, x, "a number")
+    })
+}
+
+/// Whitespace-separated `key:value` fields, like `ecl:gry pid:860033327`
+pub fn key_values(line: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    line.split_whitespace()
+        .map(|field| {
+            field
+                .split_once(':')
+                .ok_or_else(|| ParseError::at(line, field, "a `key:value` field"))
+        })
+        .collect()
+}
+
+/// A rectangle of characters, each turned into a cell by `cell`. Every row
+/// must be as wide as the first, and blank lines before it are skipped.
+pub fn char_grid<T>(
+    lines: &[&str],
+    expected: &str,
+    cell: impl Fn(char) -> Option<T>,
+) -> Result<Grid<T>, ParseError> {
+    char_grid_from(1, lines, expected, cell)
+}
+
+fn char_grid_from<T>(
+    first_line: usize,
+    lines: &[&str],
+    expected: &str,
+    cell: impl Fn(char) -> Option<T>,
+) -> Result<Grid<T>, ParseError> {
+    let skipped = leading_blank_lines(lines);
+    let (first_line, lines) = (first_line + skipped, &lines[skipped..]);
+    let width = lines
+        .first()
+        .map(|x| x.chars().count())
+        .ok_or_else(|| ParseError::at_end("", "a grid").on_line(first_line))?;
+
+    let rows = parse_error::parse_lines_from(first_line, lines, |line| {
+        if line.chars().count() != width {
+            return Err(ParseError::new(line, format!("a row {} long", width)));
+        }
+        line.char_indices()
+            .map(|(i, x)| {
+                cell(x).ok_or_else(|| ParseError::at_column(line, i, x.len_utf8(), expected))
+            })
+            .collect()
+    })?;
+
+    Ok(Grid::from_rows(rows))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn numbers_sections() {
+        let lines = ["a", "b", "", "c", "", "d", "e"];
+        let found: Vec<_> = sections(&lines)
+            .into_iter()
+            .map(|x| (x.first_line, x.lines.len()))
+            .collect();
+        assert_eq!(found, vec![(1, 2), (4, 1), (6, 2)]);
+
+        // Blank lines at the start still count towards line numbers
+        let padded = crate::puzzle_input::lines("\n\na\n\nb\nc\n");
+        let found: Vec<_> = sections(&padded)
+            .into_iter()
+            .map(|x| x.first_line)
+            .collect();
+        assert_eq!(found, vec![3, 5]);
+        let [_, second]: [Section; 2] = exact_sections(&padded, "x and y").unwrap();
+        let err = second
+            .parse_lines(|x| parse_error::parse_at::<u8>(x, x, "a number"))
+            .unwrap_err();
+        assert_eq!(err.line, Some(5));
+
+        let [_, second]: [Section; 2] = exact_sections(&lines[..4], "x and y").unwrap();
+        assert_eq!(second.lines, ["c"]);
+        assert_eq!(
+            exact_sections::<2>(&lines, "x and y").unwrap_err().line,
+            Some(5)
+        );
+        assert_eq!(
+            exact_sections::<3>(&lines[..4], "x, y and z")
+                .unwrap_err()
+                .line,
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn headers() {
+        let lines = ["Player 1:", "9", "x"];
+        let cards = Section::new(&lines).header("Player 1:").unwrap();
+        assert_eq!(cards.first_line, 2);
+        let err = cards
+            .parse_lines(|x| parse_error::parse_at::<u8>(x, x, "a card"))
+            .unwrap_err();
+        assert_eq!(err.line, Some(3));
+
+        let err = Section::new(&lines).header("Player 2:").unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (Some(1), "`Player 2:`"));
+    }
+
+    #[test]
+    fn lists_and_fields() {
+        assert_eq!(number_list::<u8>("0,3,6", ",").unwrap(), vec![0, 3, 6]);
+        let err = number_list::<u8>("0,x,6", ",").unwrap_err();
+        assert_eq!((err.column, err.len), (2, 1));
+
+        let lines = ["ecl:gry pid:1", "hcl:#fff"];
+        assert_eq!(
+            Section::new(&lines).key_values().unwrap(),
+            vec![("ecl", "gry"), ("pid", "1"), ("hcl", "#fff")]
+        );
+        let err = Section::new(&["ecl:gry", "oops"]).key_values().unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn grids() {
+        let cell = |x| match x {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        assert_eq!(
+            char_grid(&[".#", "#."], "`.` or `#`", cell).unwrap(),
+            Grid::from_rows(vec![vec![false, true], vec![true, false]])
+        );
+        let err = char_grid(&[".#", "#x"], "`.` or `#`", cell).unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), 1));
+        let err = char_grid(&[".#", "#"], "`.` or `#`", cell).unwrap_err();
+        assert_eq!(err.line, Some(2));
+        let err = char_grid(&["", ".#", "#"], "`.` or `#`", cell).unwrap_err();
+        assert_eq!(err.line, Some(3));
+
+        // Widths are in characters, however many bytes each one takes
+        let cell = |x| match x {
+            'é' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        assert_eq!(
+            char_grid(&["é.", ".."], "`.` or `é`", cell).unwrap(),
+            Grid::from_rows(vec![vec![true, false], vec![false, false]])
+        );
+        let err = char_grid(&["..", "é"], "`.` or `é`", cell).unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+}
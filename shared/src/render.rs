@@ -0,0 +1,346 @@
+//! Draws puzzle states as PPM or PNG images, one cell per pixel, and writes
+//! simulations out as numbered frames
+
+use std::{
+    fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    geometry::{Hex, Vector2},
+    grid::Grid,
+    puzzle::{Part, Puzzle},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// From a hex code like `0x1e90ff`
+    pub const fn hex(code: u32) -> Rgb {
+        Rgb((code >> 16) as u8, (code >> 8) as u8, code as u8)
+    }
+}
+
+pub type Image = Grid<Rgb>;
+
+/// Colours for each kind of cell, and one for anything else
+#[derive(Debug, Clone)]
+pub struct Palette<T> {
+    colors: Vec<(T, Rgb)>,
+    default: Rgb,
+}
+
+impl<T: PartialEq> Palette<T> {
+    pub fn new(default: Rgb) -> Self {
+        Palette {
+            colors: Vec::new(),
+            default,
+        }
+    }
+
+    pub fn with(mut self, cell: T, color: Rgb) -> Self {
+        self.colors.push((cell, color));
+        self
+    }
+
+    pub fn color(&self, cell: &T) -> Rgb {
+        self.colors
+            .iter()
+            .find(|(x, _)| x == cell)
+            .map_or(self.default, |&(_, color)| color)
+    }
+
+    pub fn paint(&self, grid: &Grid<T>) -> Image {
+        grid.map(|_, cell| self.color(cell))
+    }
+}
+
+/// Blows each pixel up into a `factor` by `factor` square
+pub fn scale(image: &Image, factor: usize) -> Image {
+    Grid::from_fn(image.width() * factor, image.height() * factor, |(x, y)| {
+        image[(x / factor, y / factor)]
+    })
+}
+
+/// Lays images out left to right in rows of `columns`, `gap` pixels apart.
+/// Every image gets a space the size of the largest one.
+pub fn tiles(images: &[Image], columns: usize, gap: usize, background: Rgb) -> Image {
+    let tile_width = images.iter().map(Grid::width).max().unwrap_or(0);
+    let tile_height = images.iter().map(Grid::height).max().unwrap_or(0);
+    let columns = columns.clamp(1, images.len().max(1));
+    let rows = images.len().div_ceil(columns);
+
+    let size = |count: usize, tile: usize| (count * (tile + gap)).saturating_sub(gap);
+    let mut tiled = Grid::filled(
+        size(columns, tile_width),
+        size(rows, tile_height),
+        background,
+    );
+    for (i, image) in images.iter().enumerate() {
+        let left = (i % columns) * (tile_width + gap);
+        let top = (i / columns) * (tile_height + gap);
+        for ((x, y), &pixel) in image.iter() {
+            tiled[(left + x, top + y)] = pixel;
+        }
+    }
+    tiled
+}
+
+/// Lays hexes out like bricks, each two pixels square and each row shifted
+/// half a hex from the one above, so every hex touches its six neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexLayout {
+    min: Vector2,
+    max: Vector2,
+}
+
+impl HexLayout {
+    /// Big enough for every hex in `hexes`, so a whole animation can share
+    /// one layout and not jump around
+    pub fn around(hexes: impl IntoIterator<Item = Hex>) -> Self {
+        let mut positions = hexes.into_iter().map(Self::position);
+        let first = positions.next().unwrap_or_default();
+        let (min, max) = positions.fold((first, first), |(min, max), x| {
+            (
+                Vector2::new(min.x().min(x.x()), min.y().min(x.y())),
+                Vector2::new(max.x().max(x.x()), max.y().max(x.y())),
+            )
+        });
+        HexLayout { min, max }
+    }
+
+    /// Doubled coordinates: east is two to the right, and south east one to
+    /// the right and one down
+    fn position(hex: Hex) -> Vector2 {
+        let axial = hex.axial();
+        Vector2::new(2 * axial.x() + axial.y(), axial.y())
+    }
+
+    pub fn draw(&self, hexes: impl IntoIterator<Item = (Hex, Rgb)>, background: Rgb) -> Image {
+        let size = self.max - self.min;
+        let mut image = Grid::filled(
+            size.x() as usize + 2,
+            2 * (size.y() as usize + 1),
+            background,
+        );
+        for (hex, color) in hexes {
+            let offset = Self::position(hex) - self.min;
+            if let Some((x, y)) = Vector2::new(offset.x(), offset.y() * 2).to_point() {
+                for point in [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)] {
+                    if let Some(pixel) = image.get_mut(point) {
+                        *pixel = color;
+                    }
+                }
+            }
+        }
+        image
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(Format::Ppm),
+            "png" => Ok(Format::Png),
+            other => Err(anyhow::anyhow!("format must be ppm or png, got {}", other)),
+        }
+    }
+}
+
+fn bytes(image: &Image) -> Vec<u8> {
+    image
+        .cells()
+        .flat_map(|&Rgb(r, g, b)| vec![r, g, b])
+        .collect()
+}
+
+/// Binary PPM, with no compression
+pub fn write_ppm(image: &Image, mut writer: impl Write) -> io::Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    writer.write_all(&bytes(image))
+}
+
+pub fn write_png(image: &Image, writer: impl Write) -> anyhow::Result<()> {
+    let mut encoder = png::Encoder::new(writer, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&bytes(image))?;
+    Ok(())
+}
+
+pub fn write(image: &Image, format: Format, writer: impl Write) -> anyhow::Result<()> {
+    match format {
+        Format::Ppm => Ok(write_ppm(image, writer)?),
+        Format::Png => write_png(image, writer),
+    }
+}
+
+/// Somewhere to send each step of a simulation as it's drawn
+pub trait Frames {
+    fn push(&mut self, frame: Image) -> anyhow::Result<()>;
+}
+
+impl Frames for Vec<Image> {
+    fn push(&mut self, frame: Image) -> anyhow::Result<()> {
+        Vec::push(self, frame);
+        Ok(())
+    }
+}
+
+/// Writes frames to `name-0000.png`, `name-0001.png` and so on. A single
+/// frame is a still, so it's renamed to `name.png` by [`FrameWriter::finish`].
+#[derive(Debug)]
+pub struct FrameWriter {
+    dir: PathBuf,
+    name: String,
+    format: Format,
+    scale: usize,
+    written: Vec<PathBuf>,
+}
+
+impl FrameWriter {
+    pub fn new(dir: &Path, name: &str, format: Format) -> Self {
+        FrameWriter {
+            dir: dir.to_owned(),
+            name: name.to_string(),
+            format,
+            scale: 1,
+            written: Vec::new(),
+        }
+    }
+
+    /// Draws each cell as a `scale` by `scale` square
+    pub fn scale(self, scale: usize) -> Self {
+        FrameWriter { scale, ..self }
+    }
+
+    fn path(&self, frame: Option<usize>) -> PathBuf {
+        let name = match frame {
+            Some(i) => format!("{}-{:04}.{}", self.name, i, self.format.extension()),
+            None => format!("{}.{}", self.name, self.format.extension()),
+        };
+        self.dir.join(name)
+    }
+
+    /// Every file written, in order
+    pub fn finish(mut self) -> anyhow::Result<Vec<PathBuf>> {
+        if let [frame] = self.written.as_slice() {
+            let still = self.path(None);
+            fs::rename(frame, &still)?;
+            self.written = vec![still];
+        }
+        Ok(self.written)
+    }
+}
+
+impl Frames for FrameWriter {
+    fn push(&mut self, frame: Image) -> anyhow::Result<()> {
+        if self.written.is_empty() {
+            fs::create_dir_all(&self.dir)?;
+        }
+        let path = self.path(Some(self.written.len()));
+        let mut file = BufWriter::new(fs::File::create(&path)?);
+        write(&scale(&frame, self.scale), self.format, &mut file)?;
+        file.flush()?;
+        self.written.push(path);
+        Ok(())
+    }
+}
+
+/// A puzzle that can draw its state while it works towards each part
+pub trait Render: Puzzle {
+    fn render(input: &Self::Input<'_>, part: Part, frames: &mut dyn Frames) -> anyhow::Result<()>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::HexDirection;
+
+    #[test]
+    fn palettes() {
+        let palette = Palette::new(Rgb::BLACK).with('#', Rgb::hex(0x00ff80));
+        let image = palette.paint(&Grid::from_rows(vec![vec!['#', '.']]));
+        assert_eq!(
+            image.cells().copied().collect::<Vec<_>>(),
+            vec![Rgb(0, 255, 128), Rgb::BLACK]
+        );
+        assert_eq!(scale(&image, 3).width(), 6);
+        assert_eq!(scale(&image, 3)[(2, 2)], Rgb(0, 255, 128));
+    }
+
+    #[test]
+    fn ppm() {
+        let image = Grid::from_rows(vec![vec![Rgb(1, 2, 3), Rgb::WHITE]]);
+        let mut out = Vec::new();
+        write_ppm(&image, &mut out).unwrap();
+        assert_eq!(out, b"P6\n2 1\n255\n\x01\x02\x03\xff\xff\xff");
+    }
+
+    #[test]
+    fn png() {
+        let mut out = Vec::new();
+        write_png(&Grid::filled(3, 2, Rgb::WHITE), &mut out).unwrap();
+        assert_eq!(&out[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn tiling() {
+        let images = vec![
+            Grid::filled(2, 2, Rgb::WHITE),
+            Grid::filled(1, 3, Rgb::WHITE),
+        ];
+        let tiled = tiles(&images, 2, 1, Rgb::BLACK);
+        assert_eq!((tiled.width(), tiled.height()), (5, 3));
+        assert_eq!(tiled[(2, 0)], Rgb::BLACK);
+        assert_eq!(tiled[(3, 2)], Rgb::WHITE);
+        assert_eq!(tiled[(0, 2)], Rgb::BLACK);
+    }
+
+    #[test]
+    fn hexes() {
+        let hexes: Vec<_> = HexDirection::ALL
+            .iter()
+            .map(|&x| Hex::ORIGIN.neighbor(x))
+            .collect();
+        let layout = HexLayout::around(hexes.iter().copied());
+        let image = layout.draw(hexes.iter().map(|&x| (x, Rgb::WHITE)), Rgb::BLACK);
+        // Three rows of bricks, two hexes wide in the middle row
+        assert_eq!((image.width(), image.height()), (6, 6));
+        let row = |y| {
+            image
+                .rows()
+                .nth(y)
+                .unwrap()
+                .iter()
+                .map(|&x| x == Rgb::WHITE)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(row(0), vec![false, true, true, true, true, false]);
+        assert_eq!(row(2), vec![true, true, false, false, true, true]);
+    }
+}
@@ -0,0 +1,237 @@
+//! A stepping interface for puzzles that run a simulation, and a player that
+//! drives one for `aoc play`
+
+use std::time::Duration;
+
+use crate::puzzle::{Part, Puzzle};
+
+/// Something that changes one generation at a time, like a cellular
+/// automaton or a card game
+pub trait Simulation {
+    /// Moves on one generation, or returns `false` if it's already finished
+    fn step(&mut self) -> bool;
+
+    /// The current state as text
+    fn draw(&self) -> String;
+
+    /// Numbers to show alongside, like how many cells are alive
+    fn counters(&self) -> Vec<(&'static str, String)>;
+
+    /// The generation the puzzle asks about, for simulations that don't stop
+    /// by themselves
+    fn target(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A puzzle that can be watched as it works towards each part
+pub trait Animate: Puzzle {
+    fn simulation<'a>(
+        input: &'a Self::Input<'_>,
+        part: Part,
+    ) -> anyhow::Result<Box<dyn Simulation + 'a>>;
+}
+
+type Start<'a> = Box<dyn Fn() -> anyhow::Result<Box<dyn Simulation + 'a>> + 'a>;
+
+/// Steps per second that [`Player::faster`] and [`Player::slower`] move between
+const SPEEDS: [u32; 10] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000];
+
+/// The most play time [`Player::advance`] catches up on at once, so a
+/// simulation that can't keep up doesn't stop the player responding
+const MAX_CATCH_UP: Duration = Duration::from_millis(250);
+
+/// Plays a simulation forwards in real time, and can jump to any generation
+/// by starting again if it has to
+pub struct Player<'a> {
+    start: Start<'a>,
+    simulation: Box<dyn Simulation + 'a>,
+    generation: usize,
+    finished: bool,
+    playing: bool,
+    speed: usize,
+    /// Time played that hasn't added up to a whole step yet
+    pending: Duration,
+}
+
+impl<'a> Player<'a> {
+    /// Paused at generation 0. `start` is called again to go backwards.
+    pub fn new(
+        start: impl Fn() -> anyhow::Result<Box<dyn Simulation + 'a>> + 'a,
+    ) -> anyhow::Result<Self> {
+        Ok(Player {
+            simulation: start()?,
+            start: Box::new(start),
+            generation: 0,
+            finished: false,
+            playing: false,
+            speed: 3,
+            pending: Duration::ZERO,
+        })
+    }
+
+    pub fn simulation(&self) -> &dyn Simulation {
+        self.simulation.as_ref()
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn steps_per_second(&self) -> u32 {
+        SPEEDS[self.speed]
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    pub fn toggle(&mut self) {
+        self.playing = !self.playing && !self.finished;
+        self.pending = Duration::ZERO;
+    }
+
+    /// One generation on, if it isn't finished
+    pub fn step(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        if self.simulation.step() {
+            self.generation += 1;
+            true
+        } else {
+            self.finished = true;
+            self.playing = false;
+            false
+        }
+    }
+
+    /// Takes as many steps as fit in `elapsed` at the current speed. Pauses
+    /// when the simulation finishes or reaches its target generation.
+    pub fn advance(&mut self, elapsed: Duration) {
+        if !self.playing {
+            return;
+        }
+        let step_time = Duration::from_secs(1) / self.steps_per_second();
+        self.pending = (self.pending + elapsed).min(MAX_CATCH_UP.max(step_time));
+        while self.playing && self.pending >= step_time {
+            self.pending -= step_time;
+            self.step();
+            if self.simulation.target() == Some(self.generation) {
+                self.playing = false;
+            }
+        }
+    }
+
+    /// Pauses at `generation`, or wherever the simulation finishes before it
+    pub fn jump(&mut self, generation: usize) -> anyhow::Result<()> {
+        self.playing = false;
+        if generation < self.generation {
+            self.simulation = (self.start)()?;
+            self.generation = 0;
+            self.finished = false;
+        }
+        while self.generation < generation && self.step() {}
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Counts down to zero
+    struct Countdown(u32);
+
+    impl Simulation for Countdown {
+        fn step(&mut self) -> bool {
+            match self.0 {
+                0 => false,
+                _ => {
+                    self.0 -= 1;
+                    true
+                }
+            }
+        }
+
+        fn draw(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn counters(&self) -> Vec<(&'static str, String)> {
+            vec![("left", self.0.to_string())]
+        }
+
+        fn target(&self) -> Option<usize> {
+            Some(4)
+        }
+    }
+
+    fn player() -> Player<'static> {
+        Player::new(|| Ok(Box::new(Countdown(10)))).unwrap()
+    }
+
+    #[test]
+    fn stepping_and_jumping() {
+        let mut player = player();
+        assert!(player.step());
+        assert_eq!(player.simulation().draw(), "9");
+
+        player.jump(7).unwrap();
+        assert_eq!(
+            (player.generation(), player.simulation().draw()),
+            (7, "3".into())
+        );
+        player.jump(2).unwrap();
+        assert_eq!(
+            (player.generation(), player.simulation().draw()),
+            (2, "8".into())
+        );
+
+        player.jump(50).unwrap();
+        assert_eq!(player.generation(), 10);
+        assert!(player.finished());
+        assert!(!player.step());
+        player.toggle();
+        assert!(!player.playing());
+    }
+
+    #[test]
+    fn playing_in_real_time() {
+        let mut player = player();
+        player.advance(Duration::from_secs(1));
+        assert_eq!(player.generation(), 0, "paused");
+
+        player.toggle();
+        assert_eq!(player.steps_per_second(), 10);
+        player.advance(Duration::from_millis(250));
+        assert_eq!(player.generation(), 2);
+        player.advance(Duration::from_millis(50));
+        assert_eq!(player.generation(), 3);
+
+        // Stops at the target
+        player.advance(Duration::from_secs(1));
+        assert_eq!(player.generation(), 4);
+        assert!(!player.playing());
+
+        player.toggle();
+        player.faster();
+        player.advance(Duration::from_secs(10));
+        assert_eq!(player.generation(), 9, "only catches up a quarter second");
+        player.advance(Duration::from_secs(10));
+        assert_eq!(player.generation(), 10);
+        assert!(player.finished() && !player.playing());
+    }
+}
@@ -0,0 +1,160 @@
+//! Structured events from solvers that deduce their answers step by step, so
+//! `aoc explain` can show the working. A traced solver takes `&mut T` where
+//! `T: Tracer + ?Sized`, and its untraced version passes [`NoopTracer`], for
+//! which [`emit`] compiles away.
+
+use std::{fmt, io::Write};
+
+use crate::puzzle::{Part, Puzzle};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// `subject` can now only be one of `candidates`
+    Narrowed {
+        subject: String,
+        candidates: Vec<String>,
+    },
+    /// `subject` is settled as `value`
+    Resolved { subject: String, value: String },
+    /// Any other step along the way
+    Note(String),
+}
+
+impl Event {
+    /// Sorts the candidates, which often come from a set
+    pub fn narrowed(
+        subject: impl fmt::Display,
+        candidates: impl IntoIterator<Item = impl fmt::Display>,
+    ) -> Self {
+        let mut candidates: Vec<String> = candidates.into_iter().map(|x| x.to_string()).collect();
+        candidates.sort();
+        Event::Narrowed {
+            subject: subject.to_string(),
+            candidates,
+        }
+    }
+
+    pub fn resolved(subject: impl fmt::Display, value: impl fmt::Display) -> Self {
+        Event::Resolved {
+            subject: subject.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Narrowed {
+                subject,
+                candidates,
+            } => write!(f, "{} narrowed to {{{}}}", subject, candidates.join(", ")),
+            Event::Resolved { subject, value } => write!(f, "{} resolved to {}", subject, value),
+            Event::Note(note) => write!(f, "{}", note),
+        }
+    }
+}
+
+pub trait Tracer {
+    /// Whether anyone wants events. If not, [`emit`] doesn't build them.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn event(&mut self, event: Event);
+}
+
+/// Hands `tracer` an event, only building it if it's wanted
+#[inline]
+pub fn emit<T: Tracer + ?Sized>(tracer: &mut T, event: impl FnOnce() -> Event) {
+    if tracer.enabled() {
+        tracer.event(event());
+    }
+}
+
+/// Ignores everything, for solving without tracing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {
+    #[inline]
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn event(&mut self, _: Event) {}
+}
+
+/// Keeps every event, mostly for tests
+impl Tracer for Vec<Event> {
+    fn event(&mut self, event: Event) {
+        self.push(event);
+    }
+}
+
+/// Writes events out as a numbered derivation
+#[derive(Debug)]
+pub struct Printer<W> {
+    out: W,
+    steps: usize,
+}
+
+impl<W: Write> Printer<W> {
+    pub fn new(out: W) -> Self {
+        Printer { out, steps: 0 }
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+}
+
+impl<W: Write> Tracer for Printer<W> {
+    fn event(&mut self, event: Event) {
+        self.steps += 1;
+        // Failing to print the working is no reason to stop solving
+        let _ = writeln!(self.out, "{:>4}. {}", self.steps, event);
+    }
+}
+
+/// A puzzle that can show how it worked out an answer
+pub trait Explain: Puzzle {
+    /// Answers `part`, telling `tracer` each step of the way
+    fn explain(
+        input: &Self::Input<'_>,
+        part: Part,
+        tracer: &mut dyn Tracer,
+    ) -> anyhow::Result<String>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn noop_never_builds_events() {
+        emit(&mut NoopTracer, || panic!("built an event nobody wanted"));
+    }
+
+    #[test]
+    fn prints_a_derivation() {
+        let mut events = Vec::new();
+        emit(&mut events, || {
+            Event::narrowed("column 3", vec!["seat", "row"])
+        });
+        emit(&mut events, || Event::resolved("dairy", "mxmxvkd"));
+        emit(&mut events, || Event::Note("done".to_string()));
+
+        let mut printer = Printer::new(Vec::new());
+        let tracer: &mut dyn Tracer = &mut printer;
+        for event in events {
+            emit(tracer, || event);
+        }
+        assert_eq!(printer.steps(), 3);
+        assert_eq!(
+            String::from_utf8(printer.out).unwrap(),
+            "   1. column 3 narrowed to {row, seat}\n   2. dairy resolved to mxmxvkd\n   3. done\n"
+        );
+    }
+}
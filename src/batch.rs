@@ -0,0 +1,342 @@
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use shared::{
+    prelude::*,
+    puzzle::{self, Panicked},
+};
+
+use crate::{bench::format_nanos, days};
+
+/// Longest a cell gets in the printed matrix before it's cut short
+const CELL_WIDTH: usize = 40;
+
+/// How one part went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(String),
+    Error(String),
+    /// The solver panicked, with the panic's message
+    Panic(String),
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, Outcome::Solved(_))
+    }
+
+    fn summary(&self) -> String {
+        match self {
+            Outcome::Solved(answer) => answer.clone(),
+            Outcome::Error(err) => format!("ERROR {}", err.lines().next().unwrap_or_default()),
+            Outcome::Panic(message) => {
+                format!("PANIC {}", message.lines().next().unwrap_or_default())
+            }
+        }
+    }
+
+    /// The full text of what went wrong, if anything did
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Outcome::Solved(_) => None,
+            Outcome::Error(message) | Outcome::Panic(message) => Some(message),
+        }
+    }
+}
+
+/// How each part of one day went for one input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayOutcome {
+    pub parts: Vec<(Part, Outcome)>,
+    /// Parsing and solving, all told
+    pub elapsed: Duration,
+}
+
+impl DayOutcome {
+    /// Every part ends up the same way when the parse fails
+    fn each_part(day: u8, outcome: Outcome) -> Self {
+        let solver = days::solver(day).expect("days are validated by the caller");
+        DayOutcome {
+            parts: solver
+                .parts()
+                .iter()
+                .map(|&part| (part, outcome.clone()))
+                .collect(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.parts.iter().any(|(_, x)| x.is_failure())
+    }
+
+    pub fn part(&self, part: Part) -> Option<&Outcome> {
+        self.parts
+            .iter()
+            .find(|(x, _)| *x == part)
+            .map(|(_, outcome)| outcome)
+    }
+
+    /// One line, for tables
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = self.parts.iter().map(|(_, x)| x.summary()).collect();
+        let summary = format!(
+            "{} ({})",
+            parts.join(", "),
+            format_nanos(self.elapsed.as_nanos() as u64)
+        );
+        match summary.char_indices().nth(CELL_WIDTH - 1) {
+            Some((end, _)) => format!("{}…", &summary[..end]),
+            None => summary,
+        }
+    }
+}
+
+/// Every input under a batch directory, as `(person, day, path)`
+pub fn find_inputs(dir: &Path, days: &[u8]) -> anyhow::Result<Vec<(String, u8, PathBuf)>> {
+    let read_dir = |dir: &Path| {
+        fs::read_dir(dir).map_err(|err| anyhow!("Couldn't read {}: {}", dir.display(), err))
+    };
+
+    let mut inputs = Vec::new();
+    for person in read_dir(dir)? {
+        let person = person?;
+        if !person.file_type()?.is_dir() {
+            continue;
+        }
+        for file in read_dir(&person.path())? {
+            let path = file?.path();
+            let day = path
+                .file_name()
+                .and_then(|x| x.to_str())
+                .and_then(|x| x.strip_prefix("day")?.strip_suffix(".txt"))
+                .filter(|x| x.len() == 2)
+                .and_then(|x| x.parse().ok())
+                .filter(|x| days.contains(x));
+            if let Some(day) = day {
+                inputs.push((person.file_name().to_string_lossy().into_owned(), day, path));
+            }
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Solves every part of `day`, turning a panic into [`Outcome::Panic`] so one
+/// bad input doesn't stop the batch. A part that fails doesn't stop the
+/// others.
+pub fn solve(day: u8, input: &str) -> DayOutcome {
+    let solver = days::solver(day).expect("days are validated by the caller");
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        solver.solve_timed(input, solver.parts())
+    }));
+
+    let timed = match result {
+        Ok(Ok(timed)) => timed,
+        Ok(Err(err)) => return DayOutcome::each_part(day, Outcome::Error(format!("{:#}", err))),
+        Err(payload) => {
+            return DayOutcome::each_part(day, Outcome::Panic(puzzle::panic_message(&*payload)))
+        }
+    };
+
+    let elapsed = timed.parse + timed.answers.iter().map(|(_, _, x)| *x).sum::<Duration>();
+    let parts = timed
+        .answers
+        .into_iter()
+        .map(|(part, answer, _)| {
+            let outcome = match answer {
+                Ok(answer) => Outcome::Solved(answer),
+                Err(err) => match err.downcast_ref::<Panicked>() {
+                    Some(Panicked(message)) => Outcome::Panic(message.clone()),
+                    None => Outcome::Error(format!("{:#}", err)),
+                },
+            };
+            (part, outcome)
+        })
+        .collect();
+    DayOutcome { parts, elapsed }
+}
+
+#[derive(Debug, Clone)]
+pub struct Batch {
+    pub people: Vec<String>,
+    pub results: Vec<(String, u8, DayOutcome)>,
+}
+
+impl Batch {
+    pub fn outcome(&self, person: &str, day: u8) -> Option<&DayOutcome> {
+        self.results
+            .iter()
+            .find(|(p, d, _)| p == person && *d == day)
+            .map(|(_, _, outcome)| outcome)
+    }
+
+    /// The inputs that any part failed on
+    pub fn failures(&self) -> impl Iterator<Item = &(String, u8, DayOutcome)> {
+        self.results.iter().filter(|(_, _, x)| x.is_failure())
+    }
+}
+
+/// Runs every day against every person's input for it
+pub fn run(dir: &Path, days: &[u8]) -> anyhow::Result<Batch> {
+    let mut people = Vec::new();
+    let mut results = Vec::new();
+    for (person, day, path) in find_inputs(dir, days)? {
+        if !people.contains(&person) {
+            people.push(person.clone());
+        }
+        let outcome = match fs::read_to_string(&path) {
+            Ok(input) => solve(day, &input),
+            Err(err) => DayOutcome::each_part(
+                day,
+                Outcome::Error(format!("Couldn't read {}: {}", path.display(), err)),
+            ),
+        };
+        results.push((person, day, outcome));
+    }
+    Ok(Batch { people, results })
+}
+
+/// A row per day and a column per person, then the full text of anything
+/// that went wrong
+pub fn print_matrix(batch: &Batch) {
+    let mut days: Vec<u8> = batch.results.iter().map(|(_, day, _)| *day).collect();
+    days.sort_unstable();
+    days.dedup();
+
+    let cells: Vec<Vec<String>> = days
+        .iter()
+        .map(|&day| {
+            batch
+                .people
+                .iter()
+                .map(|person| {
+                    batch
+                        .outcome(person, day)
+                        .map_or("-".to_string(), DayOutcome::summary)
+                })
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = batch
+        .people
+        .iter()
+        .enumerate()
+        .map(|(i, person)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(person.chars().count()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let pad =
+        |text: &str, width: usize| format!("{}{}", text, " ".repeat(width - text.chars().count()));
+    let header: Vec<String> = batch
+        .people
+        .iter()
+        .zip(&widths)
+        .map(|(person, &width)| pad(person, width))
+        .collect();
+    println!("Day  {}", header.join("  ").trim_end());
+    for (day, row) in days.iter().zip(&cells) {
+        let row: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| pad(cell, width))
+            .collect();
+        println!("{:02}   {}", day, row.join("  ").trim_end());
+    }
+
+    for (person, day, outcome) in batch.failures() {
+        for (part, outcome) in &outcome.parts {
+            if let Some(message) = outcome.message() {
+                println!(
+                    "\n{} day {:02} part {}:\n{}",
+                    person,
+                    day,
+                    part.number(),
+                    message
+                );
+            }
+        }
+    }
+
+    println!(
+        "\n{} solved, {} failed",
+        batch.results.len() - batch.failures().count(),
+        batch.failures().count()
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn runs_every_input() {
+        let dir = std::env::temp_dir().join(format!("aoc-batch-{}", std::process::id()));
+        let write = |path: &str, contents: &str| {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        };
+        write("ana/day01.txt", "1721\n979\n366\n299\n675\n1456\n");
+        write("ana/notes.txt", "not an input");
+        write("bob/day01.txt", "1010\n");
+        write(
+            "bob/day16.txt",
+            "a: 0-10 or 20-30\nb: 0-10 or 20-30\n\nyour ticket:\n1,2\n\nnearby tickets:\n3,4\n",
+        );
+
+        let batch = run(&dir, &(1..=25).collect::<Vec<_>>()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(batch.people, vec!["ana", "bob"]);
+        assert_eq!(batch.results.len(), 3);
+        let ana = batch.outcome("ana", 1).unwrap();
+        assert_eq!(
+            ana.parts,
+            vec![
+                (Part::One, Outcome::Solved("514579".to_string())),
+                (Part::Two, Outcome::Solved("241861950".to_string())),
+            ]
+        );
+        assert!(matches!(
+            batch.outcome("bob", 1).unwrap().part(Part::One),
+            Some(Outcome::Error(_))
+        ));
+        // Part one still has its answer when part two panics
+        let bob = batch.outcome("bob", 16).unwrap();
+        assert_eq!(bob.part(Part::One), Some(&Outcome::Solved("0".to_string())));
+        assert!(matches!(
+            bob.part(Part::Two),
+            Some(Outcome::Panic(message)) if message.starts_with("No solutions found!")
+        ));
+        assert_eq!(batch.outcome("ana", 16), None);
+        assert_eq!(batch.failures().count(), 2);
+    }
+
+    #[test]
+    fn summaries_fit_on_one_line() {
+        let outcome = DayOutcome {
+            parts: vec![
+                (Part::One, Outcome::Solved("1".to_string())),
+                (
+                    Part::Two,
+                    Outcome::Panic(format!("{}\nmore", "x".repeat(100))),
+                ),
+            ],
+            elapsed: Duration::from_millis(1),
+        };
+        assert!(outcome.summary().starts_with("1, PANIC xxx"));
+        assert_eq!(outcome.summary().chars().count(), CELL_WIDTH);
+        assert!(!outcome.summary().contains('\n'));
+    }
+}
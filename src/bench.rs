@@ -0,0 +1,324 @@
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use shared::{
+    alloc::{self, AllocStats},
+    prelude::*,
+    puzzle_input::InputError,
+};
+
+use crate::days;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub runs: usize,
+    pub days: Vec<DayReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayReport {
+    pub day: u8,
+    pub phases: Vec<PhaseStats>,
+}
+
+/// Timings for one phase (`parse`, `part_one` or `part_two`), in nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub phase: String,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub max_ns: u64,
+    /// Allocations from the last run, when built with `--features count-alloc`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocated_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_bytes: Option<u64>,
+}
+
+impl PhaseStats {
+    fn new(phase: &str, samples: &[Duration], allocs: Option<AllocStats>) -> Self {
+        let mut nanos: Vec<u64> = samples.iter().map(|x| x.as_nanos() as u64).collect();
+        nanos.sort_unstable();
+        let median_ns = match nanos.len() {
+            0 => 0,
+            n if n % 2 == 0 => (nanos[n / 2 - 1] + nanos[n / 2]) / 2,
+            n => nanos[n / 2],
+        };
+
+        PhaseStats {
+            phase: phase.to_string(),
+            min_ns: nanos.first().copied().unwrap_or_default(),
+            median_ns,
+            mean_ns: nanos.iter().sum::<u64>() / nanos.len().max(1) as u64,
+            max_ns: nanos.last().copied().unwrap_or_default(),
+            allocations: allocs.map(|x| x.allocations),
+            allocated_bytes: allocs.map(|x| x.bytes),
+            peak_bytes: allocs.map(|x| x.peak_bytes),
+        }
+    }
+}
+
+fn phase_name(part: Part) -> &'static str {
+    match part {
+        Part::One => "part_one",
+        Part::Two => "part_two",
+    }
+}
+
+/// Runs a day `runs` times against its default input. Returns `None` if
+/// there's no input to run against.
+pub fn bench_day(day: u8, runs: usize) -> anyhow::Result<Option<DayReport>> {
+    let solver = days::solver(day).ok_or(anyhow!("There is no day {}", day))?;
+    let input = match crate::read_input(day, None) {
+        Ok(input) => input,
+        Err(err) if matches!(err.downcast_ref::<InputError>(), Some(x) if x.is_missing()) => {
+            return Ok(None)
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut parse = Vec::with_capacity(runs);
+    let mut parts: Vec<Vec<Duration>> = vec![Vec::with_capacity(runs); solver.parts().len()];
+    // The last run's, once anything lazily initialised is out of the way
+    let mut allocs = Vec::new();
+    for _ in 0..runs {
+        let timed = solver.solve_timed(&input, solver.parts())?;
+        parse.push(timed.parse);
+        for (samples, (part, answer, elapsed)) in parts.iter_mut().zip(timed.answers) {
+            answer.map_err(|err| err.context(format!("Day {} part {}", day, part.number())))?;
+            samples.push(elapsed);
+        }
+        allocs = timed.allocs;
+    }
+    let allocs = |i: usize| Some(allocs[i]).filter(|_| alloc::is_counting());
+
+    let phases = std::iter::once(PhaseStats::new("parse", &parse, allocs(0)))
+        .chain(
+            solver
+                .parts()
+                .iter()
+                .zip(&parts)
+                .enumerate()
+                .map(|(i, (&part, samples))| {
+                    PhaseStats::new(phase_name(part), samples, allocs(i + 1))
+                }),
+        )
+        .collect();
+
+    Ok(Some(DayReport { day, phases }))
+}
+
+/// A phase whose median got slower than the baseline's by more than the threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub phase: String,
+    pub ratio: f64,
+}
+
+/// Median time relative to the baseline for each phase present in both
+/// reports
+pub fn compare(report: &Report, baseline: &Report) -> Vec<(u8, String, f64)> {
+    report
+        .days
+        .iter()
+        .flat_map(|day| day.phases.iter().map(move |phase| (day.day, phase)))
+        .filter_map(|(day, phase)| {
+            let old = baseline
+                .days
+                .iter()
+                .find(|x| x.day == day)?
+                .phases
+                .iter()
+                .find(|x| x.phase == phase.phase)?;
+            let ratio = phase.median_ns as f64 / old.median_ns.max(1) as f64;
+            Some((day, phase.phase.clone(), ratio))
+        })
+        .collect()
+}
+
+pub fn regressions(report: &Report, baseline: &Report, threshold: f64) -> Vec<Regression> {
+    compare(report, baseline)
+        .into_iter()
+        .filter(|(_, _, ratio)| *ratio > 1.0 + threshold / 100.0)
+        .map(|(day, phase, ratio)| Regression { day, phase, ratio })
+        .collect()
+}
+
+pub fn format_nanos(nanos: u64) -> String {
+    match nanos {
+        0..=999 => format!("{}ns", nanos),
+        1_000..=999_999 => format!("{:.1}µs", nanos as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1}ms", nanos as f64 / 1e6),
+        _ => format!("{:.2}s", nanos as f64 / 1e9),
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{}B", bytes),
+        1_024..=1_048_575 => format!("{:.1}KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MiB", bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+pub fn print_table(report: &Report, baseline: Option<&Report>, threshold: f64) {
+    let comparison = baseline.map(|baseline| compare(report, baseline));
+    let counted = report
+        .days
+        .iter()
+        .flat_map(|x| &x.phases)
+        .any(|x| x.allocations.is_some());
+
+    println!(
+        "Day  Phase     {:>9}  {:>9}  {:>9}  {:>9}{}{}",
+        "median",
+        "mean",
+        "min",
+        "max",
+        if counted {
+            format!("  {:>9}  {:>9}  {:>9}", "allocs", "allocated", "peak")
+        } else {
+            String::new()
+        },
+        if baseline.is_some() {
+            "  vs baseline"
+        } else {
+            ""
+        }
+    );
+    for day in &report.days {
+        for phase in &day.phases {
+            let versus = comparison
+                .as_ref()
+                .and_then(|x| {
+                    x.iter()
+                        .find(|(d, p, _)| *d == day.day && *p == phase.phase)
+                })
+                .map(|(_, _, ratio)| {
+                    let flag = if *ratio > 1.0 + threshold / 100.0 {
+                        "  REGRESSED"
+                    } else {
+                        ""
+                    };
+                    format!("  {:>+10.1}%{}", (ratio - 1.0) * 100.0, flag)
+                })
+                .unwrap_or_default();
+
+            let allocs = match (phase.allocations, phase.allocated_bytes, phase.peak_bytes) {
+                (Some(allocations), Some(bytes), Some(peak)) => format!(
+                    "  {:>9}  {:>9}  {:>9}",
+                    allocations,
+                    format_bytes(bytes),
+                    format_bytes(peak)
+                ),
+                _ if counted => format!("  {:>9}  {:>9}  {:>9}", "-", "-", "-"),
+                _ => String::new(),
+            };
+
+            println!(
+                "{:02}   {:8}  {:>9}  {:>9}  {:>9}  {:>9}{}{}",
+                day.day,
+                phase.phase,
+                format_nanos(phase.median_ns),
+                format_nanos(phase.mean_ns),
+                format_nanos(phase.min_ns),
+                format_nanos(phase.max_ns),
+                allocs,
+                versus
+            );
+        }
+    }
+}
+
+pub fn load(path: &Path) -> anyhow::Result<Report> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("Couldn't read {}: {}", path.display(), err))?;
+    serde_json::from_str(&contents).map_err(|err| anyhow!("{}: {}", path.display(), err))
+}
+
+pub fn save(report: &Report, path: &Path) -> anyhow::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(report)? + "\n")
+        .map_err(|err| anyhow!("Couldn't write {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn report(day: u8, median_ns: u64) -> Report {
+        Report {
+            runs: 1,
+            days: vec![DayReport {
+                day,
+                phases: vec![PhaseStats {
+                    phase: "parse".to_string(),
+                    min_ns: median_ns,
+                    median_ns,
+                    mean_ns: median_ns,
+                    max_ns: median_ns,
+                    allocations: None,
+                    allocated_bytes: None,
+                    peak_bytes: None,
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let samples: Vec<Duration> = [4, 1, 3, 2]
+            .iter()
+            .map(|&x| Duration::from_nanos(x))
+            .collect();
+        let stats = PhaseStats::new("parse", &samples, None);
+        assert_eq!(
+            (stats.min_ns, stats.median_ns, stats.mean_ns, stats.max_ns),
+            (1, 2, 2, 4)
+        );
+    }
+
+    #[test]
+    fn flags_regressions() {
+        let baseline = report(8, 1000);
+        assert!(regressions(&report(8, 1050), &baseline, 10.0).is_empty());
+        assert_eq!(
+            regressions(&report(8, 1200), &baseline, 10.0),
+            vec![Regression {
+                day: 8,
+                phase: "parse".to_string(),
+                ratio: 1.2
+            }]
+        );
+        assert!(regressions(&report(9, 5000), &baseline, 10.0).is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let json = serde_json::to_string(&report(8, 1000)).unwrap();
+        let parsed: Report = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.days[0].phases, report(8, 1000).days[0].phases);
+    }
+
+    #[test]
+    fn benches_builtin_inputs() {
+        let day = bench_day(25, 2).unwrap().unwrap();
+        let phases: Vec<_> = day.phases.iter().map(|x| x.phase.as_str()).collect();
+        assert_eq!(phases, vec!["parse", "part_one"]);
+        // Only `--features count-alloc` builds count
+        assert_eq!(
+            day.phases[0].allocations.is_some(),
+            cfg!(feature = "count-alloc")
+        );
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(3 << 20), "3.0MiB");
+    }
+}
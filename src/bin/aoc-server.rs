@@ -0,0 +1,62 @@
+use std::{sync::Arc, thread, time::Duration};
+
+use advent_of_code_2020::server;
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(
+    name = "aoc-server",
+    about = "Serve the Advent of Code 2020 solutions over HTTP"
+)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:2020")]
+    address: String,
+    /// Seconds to wait for an answer before giving up on a request
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: u64,
+    /// How many requests to handle at once. Later ones wait their turn
+    #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u64).range(1..))]
+    connections: u64,
+    /// How many puzzles to solve at once, turning away requests with a 503
+    /// beyond that; defaults to the number of CPUs
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    solves: Option<u64>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let limit = Duration::from_secs(cli.timeout);
+    let solves = cli.solves.map_or_else(
+        || thread::available_parallelism().map_or(1, |x| x.get()),
+        |x| x as usize,
+    );
+    let slots = server::SolveSlots::new(solves);
+
+    let listener = match tiny_http::Server::http(&cli.address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Error: couldn't listen on {}: {}", cli.address, err);
+            std::process::exit(1);
+        }
+    };
+    eprintln!("Listening on http://{}", cli.address);
+
+    let listener = Arc::new(listener);
+    let workers: Vec<_> = (0..cli.connections)
+        .map(|_| {
+            let listener = Arc::clone(&listener);
+            let slots = slots.clone();
+            thread::spawn(move || {
+                for request in listener.incoming_requests() {
+                    if let Err(err) = server::serve(request, limit, &slots) {
+                        eprintln!("Error: {}", err);
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+}
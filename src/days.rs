@@ -0,0 +1,260 @@
+use shared::{
+    extras::{Extras, Value},
+    prelude::*,
+    puzzle::{self, Answer, Solver, Timed},
+    puzzle_input::{Open, Stream},
+    render::{Frames, Render},
+    simulation::{Animate, Player},
+    trace::{Explain, Tracer},
+};
+
+/// Every day's solver, indexed by `day - 1`
+static DAYS: [&dyn Solver; 25] = [
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+pub fn solver(day: u8) -> Option<&'static dyn Solver> {
+    DAYS.get(usize::from(day).checked_sub(1)?).copied()
+}
+
+/// Parses a day's input and draws it with [`Render`]
+pub type Renderer = fn(&str, Part, &mut dyn Frames) -> anyhow::Result<()>;
+
+fn render<P: Render>(input: &str, part: Part, frames: &mut dyn Frames) -> anyhow::Result<()> {
+    P::render(&P::parse(input)?, part, frames)
+}
+
+/// The days that can draw themselves
+pub fn renderer(day: u8) -> Option<Renderer> {
+    match day {
+        3 => Some(render::<day03::Day03>),
+        11 => Some(render::<day11::Day11>),
+        17 => Some(render::<day17::Day17>),
+        20 => Some(render::<day20::Day20>),
+        24 => Some(render::<day24::Day24>),
+        _ => None,
+    }
+}
+
+/// Parses a day's input and hands a [`Player`] for it to the callback
+pub type Animation =
+    fn(&str, Part, &mut dyn FnMut(&mut Player) -> anyhow::Result<()>) -> anyhow::Result<()>;
+
+fn animate<P: Animate>(
+    input: &str,
+    part: Part,
+    play: &mut dyn FnMut(&mut Player) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let input = P::parse(input)?;
+    let mut player = Player::new(|| P::simulation(&input, part))?;
+    play(&mut player)
+}
+
+/// The days with a simulation to watch
+pub fn animation(day: u8) -> Option<Animation> {
+    match day {
+        11 => Some(animate::<day11::Day11>),
+        17 => Some(animate::<day17::Day17>),
+        22 => Some(animate::<day22::Day22>),
+        23 => Some(animate::<day23::Day23>),
+        24 => Some(animate::<day24::Day24>),
+        _ => None,
+    }
+}
+
+/// Solves a day like [`Solver::solve_timed`], describing each part's answer
+/// with [`Extras`] as it's worked out
+pub type Describer = fn(&str, &[Part]) -> anyhow::Result<(Timed, Vec<(Part, Value)>)>;
+
+fn describe<P: Extras>(input: &str, parts: &[Part]) -> anyhow::Result<(Timed, Vec<(Part, Value)>)> {
+    let mut extras = Vec::new();
+    let timed = puzzle::solve_timed_with::<P>(input, parts, |input, answer| {
+        let part = match answer {
+            Answer::One(_) => Part::One,
+            Answer::Two(_) => Part::Two,
+        };
+        extras.push((part, P::extras(input, answer)));
+    })?;
+    let extras = extras
+        .into_iter()
+        .map(|(part, x)| Ok((part, x?)))
+        .collect::<anyhow::Result<_>>()?;
+    Ok((timed, extras))
+}
+
+/// The days that record more than their answers
+pub fn describer(day: u8) -> Option<Describer> {
+    match day {
+        10 => Some(describe::<day10::Day10>),
+        20 => Some(describe::<day20::Day20>),
+        21 => Some(describe::<day21::Day21>),
+        22 => Some(describe::<day22::Day22>),
+        _ => None,
+    }
+}
+
+/// Parses a day's input and answers one part, showing the working with
+/// [`Explain`]
+pub type Explainer = fn(&str, Part, &mut dyn Tracer) -> anyhow::Result<String>;
+
+fn explain<P: Explain>(input: &str, part: Part, tracer: &mut dyn Tracer) -> anyhow::Result<String> {
+    P::explain(&P::parse(input)?, part, tracer)
+}
+
+/// The days that deduce their answers a step at a time
+pub fn explainer(day: u8) -> Option<Explainer> {
+    match day {
+        7 => Some(explain::<day07::Day07>),
+        13 => Some(explain::<day13::Day13>),
+        16 => Some(explain::<day16::Day16>),
+        21 => Some(explain::<day21::Day21>),
+        _ => None,
+    }
+}
+
+/// Answers one part straight from a reader, see [`Stream`]
+pub type Streamer = fn(&mut Open<'_>, Part) -> anyhow::Result<String>;
+
+/// The days that can solve inputs too big to hold in memory
+pub fn streamer(day: u8) -> Option<Streamer> {
+    match day {
+        1 => Some(day01::Day01::stream),
+        2 => Some(day02::Day02::stream),
+        5 => Some(day05::Day05::stream),
+        9 => Some(day09::Day09::stream),
+        _ => None,
+    }
+}
+
+/// Inputs that are checked in as constants rather than loaded at runtime
+pub fn builtin_input(day: u8) -> Option<String> {
+    match day {
+        15 => Some(day15::PUZZLE_INPUT.to_string()),
+        23 => Some(day23::PUZZLE_INPUT.to_string()),
+        25 => Some(day25::PUZZLE_INPUT.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn solve(day: u8, part: Part, input: &str) -> anyhow::Result<String> {
+        solver(day).unwrap().solve(input, &[part])?.remove(0)
+    }
+
+    #[test]
+    fn registry_is_in_day_order() {
+        for (i, solver) in DAYS.iter().enumerate() {
+            assert_eq!(usize::from(solver.day()), i + 1);
+        }
+        assert!(solver(0).is_none());
+        assert!(solver(26).is_none());
+        assert_eq!(solver(25).unwrap().parts(), &[Part::One]);
+    }
+
+    #[test]
+    fn solves_example_inputs() {
+        let day08 = include_str!("../puzzles/day08/src/test_input.txt");
+        let answers = solver(8)
+            .unwrap()
+            .solve(day08, &[Part::One, Part::Two])
+            .unwrap();
+        let answers: Vec<String> = answers.into_iter().map(Result::unwrap).collect();
+        assert_eq!(answers, vec!["5", "8"]);
+
+        let day22 = include_str!("../puzzles/day22/src/test_input.txt");
+        assert_eq!(solve(22, Part::Two, day22).unwrap(), "291");
+    }
+
+    #[test]
+    fn builtin_inputs() {
+        assert_eq!(
+            solve(23, Part::One, &builtin_input(23).unwrap()).unwrap(),
+            "52937846"
+        );
+        assert!(builtin_input(1).is_none());
+    }
+
+    #[test]
+    fn explains_example_inputs() {
+        let day21 = include_str!("../puzzles/day21/src/test_input.txt");
+        let mut events = Vec::new();
+        let answer = explainer(21).unwrap()(day21, Part::Two, &mut events).unwrap();
+        assert_eq!(answer, solve(21, Part::Two, day21).unwrap());
+        assert_eq!(events.len(), 5);
+        assert!(explainer(1).is_none());
+    }
+
+    #[test]
+    fn streams_example_inputs() {
+        let day01 = "1721\n979\n366\n299\n675\n1456\n";
+        let mut open =
+            || -> anyhow::Result<Box<dyn std::io::BufRead>> { Ok(Box::new(day01.as_bytes())) };
+        for (part, answer) in [(Part::One, "514579"), (Part::Two, "241861950")] {
+            assert_eq!(streamer(1).unwrap()(&mut open, part).unwrap(), answer);
+            assert_eq!(solve(1, part, day01).unwrap(), answer);
+        }
+        assert!(streamer(3).is_none());
+    }
+
+    #[test]
+    fn renders_example_inputs() {
+        let day24 = include_str!("../puzzles/day24/src/test_input.txt");
+        let mut frames = Vec::new();
+        renderer(24).unwrap()(day24, Part::One, &mut frames).unwrap();
+        assert_eq!(frames.len(), 1);
+
+        assert!(renderer(24).unwrap()("sx", Part::One, &mut frames).is_err());
+        assert!(renderer(1).is_none());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(solve(14, Part::One, "mask = nope").is_err());
+        assert!(solve(25, Part::Two, "1\n2").is_err());
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_line() {
+        for (day, input, line) in [
+            (8, "nop +0\nacc x12\n", 2),
+            (
+                16,
+                "class: 1-3 or 5-7\n\nyour ticket:\n7,1,x\n\nnearby tickets:\n",
+                4,
+            ),
+            (19, "0: 1\n1: \"a\"\n2: 1 | b\n\na\n", 3),
+            (24, "esew\nnwwswee\nsx\n", 3),
+        ] {
+            let err = solve(day, Part::One, input).unwrap_err();
+            let err = err.downcast_ref::<ParseError>().unwrap();
+            assert_eq!(err.line, Some(line), "day {}: {}", day, err);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Solves several days at once, giving each one a wall-clock budget
+
+use std::{
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use shared::cancel::{self, Token};
+
+use crate::batch::{self, DayOutcome};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Finished(DayOutcome),
+    /// Ran out of budget and had its token cancelled. `stopped` says whether
+    /// the solver noticed within [`STOP_GRACE`]; one that never checks is
+    /// left running in the background.
+    TimedOut {
+        budget: Duration,
+        stopped: bool,
+    },
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        match self {
+            Status::Finished(outcome) => outcome.is_failure(),
+            Status::TimedOut { .. } => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayRun {
+    pub day: u8,
+    pub status: Status,
+}
+
+/// How long a cancelled day gets to wind down before we stop waiting for it
+pub const STOP_GRACE: Duration = Duration::from_secs(1);
+
+struct Running {
+    job: usize,
+    day: u8,
+    token: Token,
+    deadline: Instant,
+}
+
+/// A day that timed out, waiting to hear that it's stopped
+struct Stopping {
+    job: usize,
+    deadline: Instant,
+}
+
+/// Solves each `(day, input)` on its own thread, at most `workers` at a time,
+/// and gives up on any that take longer than `budget`. Results come back in
+/// the order of `jobs`, once every cancelled day has stopped or had
+/// [`STOP_GRACE`] to do so.
+pub fn run(jobs: Vec<(u8, String)>, workers: usize, budget: Duration) -> Vec<DayRun> {
+    let (sender, receiver) = mpsc::channel();
+    let mut waiting = jobs.into_iter().enumerate();
+    let mut running: Vec<Running> = Vec::new();
+    let mut stopping: Vec<Stopping> = Vec::new();
+    let mut runs: Vec<(usize, DayRun)> = Vec::new();
+
+    loop {
+        while running.len() < workers.max(1) {
+            let (job, (day, input)) = match waiting.next() {
+                Some(next) => next,
+                None => break,
+            };
+            let token = Token::new();
+            running.push(Running {
+                job,
+                day,
+                token: token.clone(),
+                deadline: Instant::now() + budget,
+            });
+            let sender = sender.clone();
+            thread::spawn(move || {
+                let outcome = cancel::with_token(&token, || batch::solve(day, &input));
+                // The receiver has stopped listening if everything else finished
+                let _ = sender.send((job, outcome));
+            });
+        }
+
+        let deadlines = running.iter().map(|x| x.deadline);
+        let next_deadline = match deadlines.chain(stopping.iter().map(|x| x.deadline)).min() {
+            Some(deadline) => deadline,
+            None => break,
+        };
+        match receiver.recv_timeout(next_deadline.saturating_duration_since(Instant::now())) {
+            Ok((job, outcome)) => {
+                if let Some(i) = stopping.iter().position(|x| x.job == job) {
+                    stopping.remove(i);
+                    if let Some((_, run)) = runs.iter_mut().find(|(x, _)| *x == job) {
+                        run.status = Status::TimedOut {
+                            budget,
+                            stopped: true,
+                        };
+                    }
+                } else if let Some(i) = running.iter().position(|x| x.job == job) {
+                    let finished = running.remove(i);
+                    runs.push((
+                        job,
+                        DayRun {
+                            day: finished.day,
+                            status: Status::Finished(outcome),
+                        },
+                    ));
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let now = Instant::now();
+                // Anything still stopping past its grace is left as it is
+                stopping.retain(|x| x.deadline > now);
+                let (expired, still_running) = running.into_iter().partition(|x| x.deadline <= now);
+                running = still_running;
+                for Running {
+                    job, day, token, ..
+                } in expired
+                {
+                    token.cancel();
+                    stopping.push(Stopping {
+                        job,
+                        deadline: now + STOP_GRACE,
+                    });
+                    runs.push((
+                        job,
+                        DayRun {
+                            day,
+                            status: Status::TimedOut {
+                                budget,
+                                stopped: false,
+                            },
+                        },
+                    ));
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => unreachable!("we hold a sender"),
+        }
+    }
+
+    runs.sort_by_key(|(job, _)| *job);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use shared::puzzle::Part;
+
+    use super::*;
+    use crate::batch::Outcome;
+
+    #[test]
+    fn runs_days_side_by_side() {
+        let day22 = include_str!("../puzzles/day22/src/test_input.txt");
+        let jobs = vec![
+            (22, day22.to_string()),
+            (8, "nop +0\nacc x\n".to_string()),
+            (25, "5764801\n17807724\n".to_string()),
+        ];
+        let runs = run(jobs, 2, Duration::from_secs(60));
+
+        let days: Vec<_> = runs.iter().map(|x| x.day).collect();
+        assert_eq!(days, vec![22, 8, 25]);
+        let solved = |status: &Status, part, answer: &str| match status {
+            Status::Finished(outcome) => {
+                outcome.part(part) == Some(&Outcome::Solved(answer.to_string()))
+            }
+            Status::TimedOut { .. } => false,
+        };
+        assert!(solved(&runs[0].status, Part::One, "306"));
+        assert!(solved(&runs[0].status, Part::Two, "291"));
+        assert!(matches!(
+            &runs[1].status,
+            Status::Finished(outcome) if matches!(outcome.part(Part::One), Some(Outcome::Error(_)))
+        ));
+        assert!(!runs[2].status.is_failure());
+    }
+
+    #[test]
+    fn cancels_days_that_run_too_long() {
+        // This game never ends without recursion's repeat rule
+        let endless = "Player 1:\n1\n2\n4\n\nPlayer 2:\n3\n5\n".to_string();
+        let started = Instant::now();
+        let runs = run(vec![(22, endless)], 1, Duration::from_millis(100));
+        assert_eq!(
+            runs[0].status,
+            Status::TimedOut {
+                budget: Duration::from_millis(100),
+                stopped: true
+            }
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn cancelled_days_stop_working() {
+        // Both of these take seconds, even in a release build
+        let jobs = vec![(15, "0,3,6\n".to_string()), (23, "389125467\n".to_string())];
+        let runs = run(jobs, 2, Duration::from_millis(1));
+        for run in runs {
+            assert_eq!(
+                run.status,
+                Status::TimedOut {
+                    budget: Duration::from_millis(1),
+                    stopped: true
+                },
+                "day {}",
+                run.day
+            );
+        }
+    }
+}
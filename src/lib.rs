@@ -0,0 +1,41 @@
+//! The day registry and tooling shared by the `aoc` and `aoc-server` binaries
+
+use std::path::Path;
+
+use shared::{prelude::*, puzzle_input::Source};
+
+pub mod batch;
+pub mod bench;
+pub mod days;
+pub mod executor;
+pub mod manifest;
+pub mod play;
+pub mod results;
+pub mod scaffold;
+pub mod server;
+pub mod verify;
+
+/// Counts every allocation, for `aoc bench`. It adds some overhead to each
+/// one, so it's off by default.
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOC: shared::alloc::Counting = shared::alloc::Counting;
+
+/// Where `--input` points, or the day's default input if it's `None`. `-`
+/// means stdin.
+pub fn input_source(day: u8, input: Option<&Path>) -> Source {
+    match input {
+        Some(path) if path == Path::new("-") => Source::Stdin,
+        Some(path) => Source::Path(path.to_owned()),
+        None => Source::Day(day),
+    }
+}
+
+/// Reads `input`, or the day's default input if it's `None`, falling back to
+/// a built-in input
+pub fn read_input(day: u8, input: Option<&Path>) -> anyhow::Result<String> {
+    match puzzle_input::read(&input_source(day, input)) {
+        Err(err) if err.is_missing() => days::builtin_input(day).ok_or_else(|| err.into()),
+        result => Ok(result?),
+    }
+}
@@ -1 +1,519 @@
-fn main() {}
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+use advent_of_code_2020::{
+    batch, bench, days, executor, input_source, manifest, play, read_input, results, scaffold,
+    verify,
+};
+use clap::{Parser, Subcommand};
+use shared::{
+    cache::Cache,
+    prelude::*,
+    puzzle_input::{InputError, Source},
+    render::{Format, FrameWriter},
+    trace,
+};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2020 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a day's puzzle and print the answers
+    Run {
+        /// Day to solve (1-25)
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Only solve one part (1 or 2)
+        #[arg(long, value_parser = Part::from_str)]
+        part: Option<Part>,
+        /// Read the puzzle input from this file instead; `-` reads from stdin
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Where to write `dayNN.json` with the answers, timings and extras. This always
+        /// solves, so the timings are real. Streaming runs don't write one.
+        #[arg(long, value_name = "DIR", default_value = "results")]
+        json: PathBuf,
+        /// Don't write the JSON results, and answer from the cache where possible
+        #[arg(long)]
+        no_json: bool,
+        /// With --no-json, solve even if the answers are cached, without saving them
+        #[arg(long)]
+        no_cache: bool,
+        /// Read the input a line at a time rather than all at once, for inputs too
+        /// big to hold in memory (days 1, 2, 5 and 9)
+        #[arg(long)]
+        stream: bool,
+    },
+    /// Solve every day with an input, several at once, giving up on any that run too long
+    All {
+        /// How many days to solve at once; defaults to the number of CPUs
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        jobs: Option<u64>,
+        /// Seconds each day gets before it's cancelled
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: u64,
+    },
+    /// Check solvers against the expected answers in the manifest
+    Verify {
+        /// Only check this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// Manifest of expected answers; defaults to `answers.txt` in the repository root
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Time parsing and each part separately for every day with an input
+    Bench {
+        /// Only benchmark this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// How many times to solve each day
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        runs: u64,
+        /// Write the report as JSON to this file
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Compare against a report saved with `--output`
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Percentage slowdown of a median, relative to the baseline, that counts as a regression
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Solve every day for everyone's inputs in `<dir>/<person>/dayNN.txt`
+    Batch {
+        /// Directory with a subdirectory of inputs for each person
+        #[arg(default_value = "inputs")]
+        dir: PathBuf,
+        /// Only solve this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+    },
+    /// Draw a day's puzzle as images, with a numbered frame for each step of a simulation
+    Render {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Only draw one part (1 or 2)
+        #[arg(long, value_parser = Part::from_str)]
+        part: Option<Part>,
+        /// Read the puzzle input from this file instead; `-` reads from stdin
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Directory to write the images to
+        #[arg(long, default_value = "renders")]
+        output: PathBuf,
+        /// Image format, `png` or `ppm`
+        #[arg(long, default_value = "png", value_parser = Format::from_str)]
+        format: Format,
+        /// Width and height of each cell in pixels
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..))]
+        scale: u64,
+    },
+    /// Solve a day and print each step of how the answer was worked out
+    Explain {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Only explain one part (1 or 2)
+        #[arg(long, value_parser = Part::from_str)]
+        part: Option<Part>,
+        /// Read the puzzle input from this file instead; `-` reads from stdin
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+    /// Watch a day's simulation step by step in the terminal
+    Play {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Which part's simulation to watch (1 or 2)
+        #[arg(long, default_value = "1", value_parser = Part::from_str)]
+        part: Part,
+        /// Read the puzzle input from this file instead
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+    /// Manage the answers `aoc run` saves in `target/aoc-cache`
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Create a new day's crate from the `puzzles/day00` template
+    New {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// The puzzle's title, e.g. "Report Repair"
+        title: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Forget saved answers, so they're solved again
+    Clear {
+        /// Only forget this day's
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+    },
+}
+
+fn selected_days(day: Option<u8>) -> Vec<u8> {
+    match day {
+        Some(day) => vec![day],
+        None => (1..=25).collect(),
+    }
+}
+
+fn run(
+    day: u8,
+    part: Option<Part>,
+    input: Option<&Path>,
+    json: Option<&Path>,
+    no_cache: bool,
+) -> anyhow::Result<()> {
+    let solver = days::solver(day).ok_or(anyhow!("There is no day {}", day))?;
+    let input = read_input(day, input)?;
+    let parts = match part {
+        Some(part) => vec![part],
+        None => solver.parts().to_vec(),
+    };
+
+    // Cached answers have no timings to record
+    let answers = match json {
+        Some(dir) => {
+            let results = results::solve(day, &input, &parts)?;
+            eprintln!("Wrote {}", results::save(&results, dir)?.display());
+            results.parts.into_iter().map(|x| Ok(x.answer)).collect()
+        }
+        None if no_cache => solver.solve(&input, &parts)?,
+        None => {
+            let cache = Cache::in_workspace();
+            let (answers, hits) = results::solve_cached(day, &input, &parts, &cache)?;
+            if hits > 0 {
+                eprintln!(
+                    "{} answer(s) from {}; pass --no-cache to solve again",
+                    hits,
+                    cache.dir().display()
+                );
+            }
+            answers
+        }
+    };
+    // A part that failed doesn't hide the others' answers
+    let mut failed = None;
+    for (part, answer) in parts.iter().zip(answers) {
+        match answer {
+            Ok(answer) => println!("Day {:02} part {}: {}", day, part.number(), answer),
+            Err(err) => {
+                failed.get_or_insert(err.context(format!("Day {:02} part {}", day, part.number())));
+            }
+        }
+    }
+
+    match failed {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+fn run_streaming(day: u8, part: Option<Part>, input: Option<&Path>) -> anyhow::Result<()> {
+    let solver = days::solver(day).ok_or(anyhow!("There is no day {}", day))?;
+    let streamer = days::streamer(day).ok_or(anyhow!("Day {} can't stream its input", day))?;
+    let parts = match part {
+        Some(part) => vec![part],
+        None => solver.parts().to_vec(),
+    };
+
+    let source = input_source(day, input);
+    let mut opened = false;
+    let mut open = || -> anyhow::Result<Box<dyn BufRead>> {
+        // Stdin can't be rewound for a second pass
+        if opened && matches!(source, Source::Stdin) {
+            return Err(anyhow!(
+                "This needs to read its input more than once, which stdin can't do"
+            ));
+        }
+        opened = true;
+        Ok(puzzle_input::open(&source)?)
+    };
+    for part in parts {
+        let answer = streamer(&mut open, part)?;
+        println!("Day {:02} part {}: {}", day, part.number(), answer);
+    }
+
+    Ok(())
+}
+
+fn all(jobs: Option<usize>, timeout: Duration) -> anyhow::Result<()> {
+    let mut inputs = Vec::new();
+    for day in 1..=25 {
+        match read_input(day, None) {
+            Ok(input) => inputs.push((day, input)),
+            Err(err) if matches!(err.downcast_ref::<InputError>(), Some(x) if x.is_missing()) => {
+                eprintln!("Skipping day {}, no input", day)
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    let jobs = jobs.unwrap_or_else(|| thread::available_parallelism().map_or(1, |x| x.get()));
+
+    // Panics are reported with the rest of the results
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let runs = executor::run(inputs, jobs, timeout);
+    std::panic::set_hook(hook);
+
+    for run in &runs {
+        let status = match &run.status {
+            executor::Status::Finished(outcome) => outcome.summary(),
+            executor::Status::TimedOut {
+                budget,
+                stopped: true,
+            } => format!("TIMED OUT after {:?}", budget),
+            executor::Status::TimedOut {
+                budget,
+                stopped: false,
+            } => format!("TIMED OUT after {:?}, still running", budget),
+        };
+        println!("Day {:02}: {}", run.day, status);
+    }
+
+    match runs.iter().filter(|x| x.status.is_failure()).count() {
+        0 => Ok(()),
+        failures => Err(anyhow!("{} day(s) failed or timed out", failures)),
+    }
+}
+
+fn verify(day: Option<u8>, manifest: Option<&Path>) -> anyhow::Result<()> {
+    let default_manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("answers.txt");
+    let manifest = manifest::load(manifest.unwrap_or(&default_manifest))?;
+    let rows = verify::verify(&manifest, &selected_days(day));
+    verify::print_table(&rows);
+
+    if rows.iter().any(|x| x.status.is_failure()) {
+        Err(anyhow!("Some answers didn't match"))
+    } else {
+        Ok(())
+    }
+}
+
+fn bench(
+    day: Option<u8>,
+    runs: usize,
+    output: Option<&Path>,
+    baseline: Option<&Path>,
+    threshold: f64,
+) -> anyhow::Result<()> {
+    let baseline = baseline.map(bench::load).transpose()?;
+
+    let mut report = bench::Report {
+        runs,
+        days: Vec::new(),
+    };
+    for day in selected_days(day) {
+        match bench::bench_day(day, runs)? {
+            Some(day_report) => report.days.push(day_report),
+            None => eprintln!("Skipping day {}, no input", day),
+        }
+    }
+
+    bench::print_table(&report, baseline.as_ref(), threshold);
+    if let Some(output) = output {
+        bench::save(&report, output)?;
+    }
+
+    let regressions = baseline
+        .map(|baseline| bench::regressions(&report, &baseline, threshold))
+        .unwrap_or_default();
+    if regressions.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} phase(s) regressed by more than {}%",
+            regressions.len(),
+            threshold
+        ))
+    }
+}
+
+fn batch(dir: &Path, day: Option<u8>) -> anyhow::Result<()> {
+    // Panics are reported in the matrix, so keep their messages out of the way
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let batch = batch::run(dir, &selected_days(day));
+    std::panic::set_hook(hook);
+
+    let batch = batch?;
+    if batch.results.is_empty() {
+        return Err(anyhow!(
+            "No inputs found; expected {}",
+            dir.join("<person>/dayNN.txt").display()
+        ));
+    }
+    batch::print_matrix(&batch);
+
+    match batch.failures().count() {
+        0 => Ok(()),
+        failures => Err(anyhow!("{} input(s) failed", failures)),
+    }
+}
+
+fn render(
+    day: u8,
+    part: Option<Part>,
+    input: Option<&Path>,
+    output: &Path,
+    format: Format,
+    scale: usize,
+) -> anyhow::Result<()> {
+    let renderer = days::renderer(day).ok_or(anyhow!(
+        "Day {} can't be rendered; try day 3, 11, 17, 20 or 24",
+        day
+    ))?;
+    let input = read_input(day, input)?;
+    let parts = match part {
+        Some(part) => vec![part],
+        None => days::solver(day).unwrap().parts().to_vec(),
+    };
+
+    for part in parts {
+        let name = format!("day{:02}-part{}", day, part.number());
+        let mut frames = FrameWriter::new(output, &name, format).scale(scale);
+        renderer(&input, part, &mut frames)?;
+        match frames.finish()?.as_slice() {
+            [still] => println!("Wrote {}", still.display()),
+            written => println!(
+                "Wrote {} frames to {}",
+                written.len(),
+                output
+                    .join(format!("{}-*.{}", name, format.extension()))
+                    .display()
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+fn explain(day: u8, part: Option<Part>, input: Option<&Path>) -> anyhow::Result<()> {
+    let explainer = days::explainer(day).ok_or(anyhow!(
+        "Day {} has nothing to explain; try day 7, 13, 16 or 21",
+        day
+    ))?;
+    let input = read_input(day, input)?;
+    let parts = match part {
+        Some(part) => vec![part],
+        None => days::solver(day).unwrap().parts().to_vec(),
+    };
+
+    for part in parts {
+        println!("Day {:02} part {}:", day, part.number());
+        let mut printer = trace::Printer::new(std::io::stdout());
+        let answer = explainer(&input, part, &mut printer)?;
+        if printer.steps() == 0 {
+            println!("   (no steps to show)");
+        }
+        println!("Answer: {}\n", answer);
+    }
+    Ok(())
+}
+
+fn play(day: u8, part: Part, input: Option<&Path>) -> anyhow::Result<()> {
+    let animation = days::animation(day).ok_or(anyhow!(
+        "Day {} has no simulation to play; try day 11, 17, 22, 23 or 24",
+        day
+    ))?;
+    let input = read_input(day, input)?;
+    let title = format!("Day {:02} part {}", day, part.number());
+    animation(&input, part, &mut |player| play::run(&title, player))
+}
+
+fn clear_cache(day: Option<u8>) -> anyhow::Result<()> {
+    let cache = Cache::in_workspace();
+    let removed = cache
+        .clear(day)
+        .map_err(|err| anyhow!("Couldn't clear {}: {}", cache.dir().display(), err))?;
+    println!("Removed {} cached answer(s)", removed);
+    Ok(())
+}
+
+fn new_day(day: u8, title: &str) -> anyhow::Result<()> {
+    let created = scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), day, title)?;
+    println!("Created {}", created.display());
+    println!(
+        "Add &day{:02}::Day{:02} to DAYS in src/days.rs to run it with `aoc run`",
+        day, day
+    );
+    Ok(())
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            json,
+            no_json,
+            no_cache,
+            stream: false,
+        } => {
+            let json = (!no_json).then_some(json.as_path());
+            run(day, part, input.as_deref(), json, no_cache)
+        }
+        Command::Run {
+            day,
+            part,
+            input,
+            stream: true,
+            ..
+        } => run_streaming(day, part, input.as_deref()),
+        Command::All { jobs, timeout } => {
+            all(jobs.map(|x| x as usize), Duration::from_secs(timeout))
+        }
+        Command::Verify { day, manifest } => verify(day, manifest.as_deref()),
+        Command::Bench {
+            day,
+            runs,
+            output,
+            baseline,
+            threshold,
+        } => bench(
+            day,
+            runs as usize,
+            output.as_deref(),
+            baseline.as_deref(),
+            threshold,
+        ),
+        Command::Batch { dir, day } => batch(&dir, day),
+        Command::Render {
+            day,
+            part,
+            input,
+            output,
+            format,
+            scale,
+        } => render(day, part, input.as_deref(), &output, format, scale as usize),
+        Command::Explain { day, part, input } => explain(day, part, input.as_deref()),
+        Command::Play { day, part, input } => play(day, part, input.as_deref()),
+        Command::Cache {
+            action: CacheAction::Clear { day },
+        } => clear_cache(day),
+        Command::New { day, title } => new_day(day, &title),
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: {:#}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+
+use shared::prelude::*;
+
+/// Which input an expected answer belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InputRef {
+    /// Whatever `aoc run` would pick up without `--input`
+    Default,
+    Path(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: Part,
+    pub input: InputRef,
+    pub answer: String,
+}
+
+/// Parses lines of `day part input answer`. Blank lines and `#` comments are
+/// skipped, and relative input paths are resolved against `base`.
+pub fn parse(s: &str, base: &Path) -> anyhow::Result<Vec<Expected>> {
+    s.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            parse_line(line, base).map_err(|err| anyhow!("Line {}: {}", line_number, err))
+        })
+        .collect()
+}
+
+fn parse_line(line: &str, base: &Path) -> anyhow::Result<Expected> {
+    let mut fields = line.splitn(4, char::is_whitespace).map(str::trim);
+    let mut next = |name| fields.next().ok_or(anyhow!("Missing {}", name));
+
+    let day = next("day")?;
+    let day = day
+        .parse()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+        .ok_or(anyhow!("Invalid day {}", day))?;
+    let part = next("part")?.parse()?;
+    let input = match next("input")? {
+        "default" => InputRef::Default,
+        path => InputRef::Path(base.join(path)),
+    };
+    let answer = next("answer")?.to_string();
+
+    Ok(Expected {
+        day,
+        part,
+        input,
+        answer,
+    })
+}
+
+pub fn load(path: &Path) -> anyhow::Result<Vec<Expected>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("Couldn't read {}: {}", path.display(), err))?;
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    parse(&contents, base).map_err(|err| anyhow!("{}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_entries() {
+        let manifest =
+            "# day part input answer\n\n1 2 default 214486272\n21 2 people/ana/day21.txt a,b c\n";
+        let entries = parse(manifest, Path::new("base")).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                Expected {
+                    day: 1,
+                    part: Part::Two,
+                    input: InputRef::Default,
+                    answer: "214486272".to_string(),
+                },
+                Expected {
+                    day: 21,
+                    part: Part::Two,
+                    input: InputRef::Path(PathBuf::from("base/people/ana/day21.txt")),
+                    answer: "a,b c".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let err = parse("1 1 default 5\n26 1 default 5", Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "Line 2: Invalid day 26");
+        assert!(parse("1 3 default 5", Path::new("")).is_err());
+        assert!(parse("1 1 default", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn checked_in_manifest_parses() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("answers.txt");
+        assert!(!load(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+use shared::{prelude::*, simulation::Player};
+
+/// How often the screen is redrawn
+const FRAME: Duration = Duration::from_millis(33);
+
+const HELP: &str = "space play/pause | right step | +/- speed | g jump | r restart | q quit";
+
+/// Switches the terminal to a blank screen that takes key presses one at a
+/// time, and switches it back when dropped, even after an error
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// What to show above the simulation itself
+fn header(title: &str, player: &Player) -> Vec<String> {
+    let status = if player.finished() {
+        "finished"
+    } else if player.playing() {
+        "playing"
+    } else {
+        "paused"
+    };
+    let mut title = format!(
+        "{} | generation {} | {} at {}/s",
+        title,
+        player.generation(),
+        status,
+        player.steps_per_second()
+    );
+    if let Some(target) = player.simulation().target() {
+        title += &format!(" | target {}", target);
+    }
+
+    let counters: Vec<_> = player
+        .simulation()
+        .counters()
+        .into_iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect();
+    vec![title, counters.join("  "), String::new()]
+}
+
+fn draw(out: &mut impl Write, title: &str, player: &Player, jump: Option<&str>) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    let drawn = player.simulation().draw();
+    let lines = header(title, player)
+        .into_iter()
+        .chain(drawn.lines().map(String::from))
+        .take(usize::from(height.saturating_sub(1)));
+
+    for (row, line) in lines.enumerate() {
+        let line: String = line.chars().take(usize::from(width)).collect();
+        queue!(
+            out,
+            cursor::MoveTo(0, row as u16),
+            Print(line),
+            terminal::Clear(ClearType::UntilNewLine)
+        )?;
+    }
+    queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+
+    let footer = match jump {
+        Some(digits) => format!("jump to generation: {}_", digits),
+        None => HELP.to_string(),
+    };
+    queue!(
+        out,
+        cursor::MoveTo(0, height.saturating_sub(1)),
+        Print(footer)
+    )?;
+    out.flush()
+}
+
+/// Returns `false` to quit. `jump` holds the digits typed so far after `g`.
+fn handle(key: KeyEvent, player: &mut Player, jump: &mut Option<String>) -> anyhow::Result<bool> {
+    if let Some(digits) = jump {
+        match key.code {
+            KeyCode::Char(digit) if digit.is_ascii_digit() => digits.push(digit),
+            KeyCode::Backspace => {
+                digits.pop();
+            }
+            KeyCode::Enter => {
+                if let Ok(generation) = digits.parse() {
+                    player.jump(generation)?;
+                }
+                *jump = None;
+            }
+            KeyCode::Esc => *jump = None,
+            _ => {}
+        }
+        return Ok(true);
+    }
+
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(false),
+        KeyCode::Char(' ') => player.toggle(),
+        KeyCode::Right | KeyCode::Char('.') => {
+            if player.playing() {
+                player.toggle();
+            }
+            player.step();
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') => player.faster(),
+        KeyCode::Char('-') => player.slower(),
+        KeyCode::Char('g') => *jump = Some(String::new()),
+        KeyCode::Char('r') => player.jump(0)?,
+        _ => {}
+    }
+    Ok(true)
+}
+
+/// Plays until the user quits
+pub fn run(title: &str, player: &mut Player) -> anyhow::Result<()> {
+    let _screen = Screen::enter()?;
+    let mut out = io::stdout();
+    let mut jump = None;
+    let mut last_frame = Instant::now();
+
+    loop {
+        draw(&mut out, title, player, jump.as_deref())?;
+        if event::poll(FRAME)? {
+            let quit = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    !handle(key, player, &mut jump)?
+                }
+                _ => false,
+            };
+            if quit {
+                return Ok(());
+            }
+        }
+
+        let now = Instant::now();
+        player.advance(now - last_frame);
+        last_frame = now;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::days;
+
+    fn press(player: &mut Player, jump: &mut Option<String>, keys: &str) -> bool {
+        keys.chars().all(|x| {
+            let code = match x {
+                '\n' => KeyCode::Enter,
+                '>' => KeyCode::Right,
+                x => KeyCode::Char(x),
+            };
+            handle(KeyEvent::new(code, KeyModifiers::NONE), player, jump).unwrap()
+        })
+    }
+
+    #[test]
+    fn keys_drive_the_player() {
+        let input = include_str!("../puzzles/day22/src/test_input.txt");
+        days::animation(22).unwrap()(input, Part::One, &mut |player| {
+            let mut jump = None;
+            assert!(press(player, &mut jump, ">>"));
+            assert_eq!(player.generation(), 2);
+
+            assert!(press(player, &mut jump, "g1"));
+            assert_eq!(jump.as_deref(), Some("1"));
+            assert!(press(player, &mut jump, "0\n"));
+            assert_eq!((player.generation(), jump.as_deref()), (10, None));
+
+            let header = header("Day 22 part 1", player);
+            assert_eq!(header[0], "Day 22 part 1 | generation 10 | paused at 10/s");
+            assert_eq!(header[1], "player 1 cards: 5  player 2 cards: 5");
+
+            assert!(press(player, &mut jump, "r +"));
+            assert_eq!(player.generation(), 0);
+            assert!(player.playing());
+            assert_eq!(player.steps_per_second(), 20);
+
+            assert!(!press(player, &mut jump, "q"));
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use shared::{
+    cache::{sha256, Cache, Key},
+    extras::Value,
+    prelude::*,
+};
+
+use crate::days;
+
+/// Everything one run of a day found out, for the JSON `aoc run` writes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayResults {
+    pub day: u8,
+    pub title: String,
+    /// Hex SHA-256 of the input, so results from different inputs can be told apart
+    pub input_sha256: String,
+    pub parse_ns: u64,
+    pub parts: Vec<PartResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: String,
+    pub solve_ns: u64,
+    /// Solver-specific details, for the days that have any
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub extras: Value,
+}
+
+/// Solves `parts`, working out the extras from each answer outside its
+/// timing. Any part failing fails the lot, since there'd be no answer to
+/// record.
+pub fn solve(day: u8, input: &str, parts: &[Part]) -> anyhow::Result<DayResults> {
+    let solver = days::solver(day).ok_or(anyhow!("There is no day {}", day))?;
+    let (timed, extras) = match days::describer(day) {
+        Some(describe) => describe(input, parts)?,
+        None => (solver.solve_timed(input, parts)?, Vec::new()),
+    };
+
+    let parts = timed
+        .answers
+        .into_iter()
+        .map(|(part, answer, elapsed)| {
+            Ok(PartResult {
+                part: part.number(),
+                answer: answer.map_err(|err| err.context(format!("Part {}", part.number())))?,
+                solve_ns: elapsed.as_nanos() as u64,
+                extras: extras
+                    .iter()
+                    .find(|(x, _)| *x == part)
+                    .map_or(Value::Null, |(_, x)| x.clone()),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(DayResults {
+        day,
+        title: solver.title().to_string(),
+        input_sha256: sha256(input),
+        parse_ns: timed.parse.as_nanos() as u64,
+        parts,
+    })
+}
+
+/// Answers `parts`, taking what it can from `cache` and saving the rest.
+/// Returns each part's answer and how many came from the cache. Only answers
+/// are saved, never errors. The cache is only a shortcut, so failing to save
+/// to it is just a warning.
+pub fn solve_cached(
+    day: u8,
+    input: &str,
+    parts: &[Part],
+    cache: &Cache,
+) -> anyhow::Result<(Vec<anyhow::Result<String>>, usize)> {
+    let solver = days::solver(day).ok_or(anyhow!("There is no day {}", day))?;
+    let key = |part| Key::new(day, part, input, solver.version());
+
+    let cached: Vec<Option<String>> = parts.iter().map(|&part| cache.get(&key(part))).collect();
+    let hits = cached.iter().flatten().count();
+    let missing: Vec<Part> = parts
+        .iter()
+        .zip(&cached)
+        .filter(|(_, answer)| answer.is_none())
+        .map(|(&part, _)| part)
+        .collect();
+    let solved = if missing.is_empty() {
+        Vec::new()
+    } else {
+        solver.solve(input, &missing)?
+    };
+
+    for (&part, answer) in missing.iter().zip(&solved) {
+        if let Ok(answer) = answer {
+            if let Err(err) = cache.put(&key(part), answer) {
+                eprintln!("Couldn't cache day {} part {}: {}", day, part.number(), err);
+            }
+        }
+    }
+    let mut solved = solved.into_iter();
+    let answers = cached
+        .into_iter()
+        .map(|answer| match answer {
+            Some(answer) => Ok(answer),
+            None => solved.next().unwrap(),
+        })
+        .collect();
+    Ok((answers, hits))
+}
+
+/// Writes `dayNN.json` into `dir`, creating it if need be
+pub fn save(results: &DayResults, dir: &Path) -> anyhow::Result<PathBuf> {
+    let path = dir.join(format!("day{:02}.json", results.day));
+    let json = serde_json::to_string_pretty(results)? + "\n";
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, json))
+        .map_err(|err| anyhow!("Couldn't write {}: {}", path.display(), err))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn caches_answers() {
+        let cache = Cache::new(
+            std::env::temp_dir().join(format!("aoc-results-cache-{}", std::process::id())),
+        );
+        let input = include_str!("../puzzles/day22/src/test_input.txt");
+
+        let answers = |(answers, hits): (Vec<anyhow::Result<String>>, usize)| {
+            let answers: Vec<String> = answers.into_iter().map(Result::unwrap).collect();
+            (answers, hits)
+        };
+
+        let solved = solve_cached(22, input, &[Part::Two], &cache).unwrap();
+        assert_eq!(answers(solved), (vec!["291".to_string()], 0));
+        let both = solve_cached(22, input, &[Part::One, Part::Two], &cache).unwrap();
+        assert_eq!(
+            answers(both),
+            (vec!["306".to_string(), "291".to_string()], 1)
+        );
+        let again = solve_cached(22, input, &[Part::One, Part::Two], &cache).unwrap();
+        assert_eq!(again.1, 2);
+
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn solves_again_for_a_new_version() {
+        let cache = Cache::new(
+            std::env::temp_dir().join(format!("aoc-results-version-{}", std::process::id())),
+        );
+        let input = include_str!("../puzzles/day22/src/test_input.txt");
+
+        // As if an older version of the solver had saved a different answer
+        let version = days::solver(22).unwrap().version();
+        assert_ne!(version, "0");
+        cache
+            .put(&Key::new(22, Part::Two, input, "0"), "stale")
+            .unwrap();
+
+        let (answers, hits) = solve_cached(22, input, &[Part::Two], &cache).unwrap();
+        assert_eq!(hits, 0);
+        assert_eq!(answers[0].as_ref().unwrap(), "291");
+        assert_eq!(
+            cache
+                .get(&Key::new(22, Part::Two, input, version))
+                .as_deref(),
+            Some("291")
+        );
+
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn hashes_the_input() {
+        assert_eq!(
+            sha256("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn records_extras() {
+        let input = include_str!("../puzzles/day22/src/test_input.txt");
+        let results = solve(22, input, &[Part::One, Part::Two]).unwrap();
+        assert_eq!(results.title, "Crab Combat");
+        assert_eq!(results.input_sha256, sha256(input));
+
+        let answers: Vec<_> = results.parts.iter().map(|x| x.answer.as_str()).collect();
+        assert_eq!(answers, vec!["306", "291"]);
+        assert_eq!(results.parts[0].extras, serde_json::json!({ "winner": 2 }));
+
+        let input = include_str!("../puzzles/day21/src/test_input.txt");
+        let results = solve(21, input, &[Part::One]).unwrap();
+        assert_eq!(
+            results.parts[0].extras,
+            serde_json::json!({ "safe_ingredients": ["kfcds", "nhms", "sbzzf", "trh"] })
+        );
+
+        let input = include_str!("../puzzles/day20/src/test_input.txt");
+        let results = solve(20, input, &[Part::Two]).unwrap();
+        assert_eq!(results.parts[0].answer, "273");
+        assert_eq!(
+            results.parts[0].extras,
+            serde_json::json!({ "monsters_found": 2 })
+        );
+    }
+
+    #[test]
+    fn leaves_out_missing_extras() {
+        let results = solve(25, "5764801\n17807724\n", &[Part::One]).unwrap();
+        let json = serde_json::to_string(&results).unwrap();
+        assert!(!json.contains("extras"));
+
+        let parsed: DayResults = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.parts, results.parts);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use shared::prelude::*;
+
+/// Creates `puzzles/dayNN` under `root` from the `puzzles/day00` template and
+/// adds it to the workspace. Returns the new crate's directory.
+pub fn new_day(root: &Path, day: u8, title: &str) -> anyhow::Result<PathBuf> {
+    let template = root.join("puzzles").join("day00");
+    let name = format!("day{:02}", day);
+    let destination = root.join("puzzles").join(&name);
+    if destination.exists() {
+        return Err(anyhow!("{} already exists", destination.display()));
+    }
+
+    copy_dir(&template.join("src"), &destination.join("src"))?;
+    fs::write(
+        destination.join("Cargo.toml"),
+        crate_manifest(&fs::read_to_string(template.join("Cargo.toml"))?, &name),
+    )?;
+    fs::write(
+        destination.join("src").join("lib.rs"),
+        crate_source(
+            &fs::read_to_string(template.join("src").join("lib.rs"))?,
+            day,
+            title,
+        ),
+    )?;
+
+    let root_manifest = root.join("Cargo.toml");
+    fs::write(
+        &root_manifest,
+        workspace_manifest(&fs::read_to_string(&root_manifest)?, &name),
+    )?;
+
+    Ok(destination)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else if entry.file_name() != "puzzle_input.txt" {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn crate_manifest(template: &str, name: &str) -> String {
+    let mut manifest = template.replace("name = \"day00\"", &format!("name = \"{}\"", name));
+    if !manifest.contains("shared = ") {
+        if !manifest.contains("[dependencies]") {
+            manifest.push_str("\n[dependencies]\n");
+        }
+        manifest = manifest.replace(
+            "[dependencies]\n",
+            "[dependencies]\nshared = { path = \"../../shared\" }\n",
+        );
+    }
+    manifest
+}
+
+fn crate_source(template: &str, day: u8, title: &str) -> String {
+    template
+        .replace(
+            "// Day 00: Template",
+            &format!("// Day {:02}: {}", day, title),
+        )
+        .replace("Day00", &format!("Day{:02}", day))
+        .replace("const DAY: u8 = 0;", &format!("const DAY: u8 = {};", day))
+        .replace(
+            "const TITLE: &'static str = \"Template\";",
+            &format!("const TITLE: &'static str = {:?};", title),
+        )
+        .replace(
+            "puzzle_input::load(0)",
+            &format!("puzzle_input::load({})", day),
+        )
+        .replace(
+            "skip_without_input!(0)",
+            &format!("skip_without_input!({})", day),
+        )
+}
+
+/// Adds the crate to `[workspace] members` unless a glob already covers it,
+/// and to the root package's dependencies so the runner can use it
+fn workspace_manifest(manifest: &str, name: &str) -> String {
+    let member = format!("\"puzzles/{}\"", name);
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+
+    if let Some(members) = lines.iter_mut().find(|x| x.starts_with("members = [")) {
+        if !members.contains("\"puzzles/*\"") && !members.contains(&member) {
+            *members = members.replacen("]", &format!(", {}]", member), 1);
+        }
+    }
+
+    let dependency = format!("{} = {{ path = \"puzzles/{}\" }}", name, name);
+    if !lines.contains(&dependency) {
+        let is_day = |x: &String| x.starts_with("day") && x.contains("path = \"puzzles/");
+        let position = match lines.iter().position(|x| is_day(x) && *x > dependency) {
+            Some(i) => i,
+            None => lines
+                .iter()
+                .rposition(is_day)
+                .or_else(|| lines.iter().position(|x| x == "[dependencies]"))
+                .map(|i| i + 1)
+                .unwrap_or(lines.len()),
+        };
+        lines.insert(position, dependency);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fills_in_template() {
+        let template = include_str!("../puzzles/day00/src/lib.rs");
+        let source = crate_source(template, 7, "Handy \"Haversacks\"");
+        assert!(source.starts_with("// Day 07: Handy \"Haversacks\"\n"));
+        assert!(source.contains("pub struct Day07;"));
+        assert!(source.contains("const DAY: u8 = 7;"));
+        assert!(source.contains("const TITLE: &'static str = \"Handy \\\"Haversacks\\\"\";"));
+        assert!(source.contains("puzzle_input::load(7)"));
+        assert!(source.contains("skip_without_input!(7)"));
+        assert!(!source.contains("Day00"));
+    }
+
+    #[test]
+    fn adds_shared_dependency() {
+        let manifest = crate_manifest("[package]\nname = \"day00\"\n", "day26");
+        assert_eq!(
+            manifest,
+            "[package]\nname = \"day26\"\n\n[dependencies]\nshared = { path = \"../../shared\" }\n"
+        );
+    }
+
+    #[test]
+    fn adds_to_workspace() {
+        let manifest = "[workspace]\nmembers = [\"shared\"]\n\n[dependencies]\nclap = \"4\"\nday01 = { path = \"puzzles/day01\" }\nday03 = { path = \"puzzles/day03\" }\n";
+        assert_eq!(
+            workspace_manifest(manifest, "day02"),
+            "[workspace]\nmembers = [\"shared\", \"puzzles/day02\"]\n\n[dependencies]\nclap = \"4\"\nday01 = { path = \"puzzles/day01\" }\nday02 = { path = \"puzzles/day02\" }\nday03 = { path = \"puzzles/day03\" }\n"
+        );
+
+        let globbed = "members = [\"shared\", \"puzzles/*\"]\n[dependencies]\n";
+        assert_eq!(
+            workspace_manifest(globbed, "day01"),
+            "members = [\"shared\", \"puzzles/*\"]\n[dependencies]\nday01 = { path = \"puzzles/day01\" }\n"
+        );
+    }
+
+    #[test]
+    fn creates_crate() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let template = root.join("puzzles").join("day00").join("src");
+        fs::create_dir_all(&template).unwrap();
+        let real_template = Path::new(env!("CARGO_MANIFEST_DIR")).join("puzzles/day00");
+        fs::copy(
+            real_template.join("Cargo.toml"),
+            template.join("../Cargo.toml"),
+        )
+        .unwrap();
+        fs::copy(real_template.join("src/lib.rs"), template.join("lib.rs")).unwrap();
+        fs::write(template.join("test_input.txt"), "").unwrap();
+        fs::write(template.join("puzzle_input.txt"), "personal").unwrap();
+        fs::write(root.join("Cargo.toml"), "[dependencies]\n").unwrap();
+
+        let created = new_day(&root, 26, "Bonus Round").unwrap();
+        assert!(created.join("src/test_input.txt").is_file());
+        assert!(!created.join("src/puzzle_input.txt").exists());
+        assert!(fs::read_to_string(created.join("Cargo.toml"))
+            .unwrap()
+            .contains("name = \"day26\""));
+        assert!(fs::read_to_string(root.join("Cargo.toml"))
+            .unwrap()
+            .contains("day26 = { path = \"puzzles/day26\" }"));
+        assert!(new_day(&root, 26, "Bonus Round").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
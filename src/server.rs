@@ -0,0 +1,334 @@
+//! A small JSON API over the solvers, for `aoc-server`. `GET /days` lists the
+//! days, and `POST /days/{n}/parts/{p}` solves the puzzle text in the body.
+
+use std::{
+    io::Read,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+use shared::{
+    cancel::{self, Token},
+    prelude::*,
+    puzzle::Solver,
+};
+
+use crate::days;
+
+/// Puzzle inputs are a few tens of kilobytes at most
+pub const MAX_BODY: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Reply { status: 200, body }
+    }
+
+    fn error(status: u16, kind: &str, message: impl Into<String>) -> Self {
+        Reply {
+            status,
+            body: json!({ "error": kind, "message": message.into() }),
+        }
+    }
+}
+
+/// Caps how many solvers run at once. A solver keeps its slot until its
+/// thread finishes, even after its request has given up on it.
+#[derive(Debug, Clone)]
+pub struct SolveSlots {
+    max: usize,
+    in_use: Arc<AtomicUsize>,
+}
+
+/// Frees its slot when dropped
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl SolveSlots {
+    pub fn new(max: usize) -> Self {
+        SolveSlots {
+            max: max.max(1),
+            in_use: Arc::default(),
+        }
+    }
+
+    fn take(&self) -> Option<Slot> {
+        self.in_use
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |x| {
+                (x < self.max).then_some(x + 1)
+            })
+            .ok()
+            .map(|_| Slot(self.in_use.clone()))
+    }
+}
+
+fn list_days() -> Reply {
+    let days: Vec<_> = (1..=25)
+        .filter_map(days::solver)
+        .map(|solver| {
+            json!({
+                "day": solver.day(),
+                "title": solver.title(),
+                "parts": solver.parts().iter().map(Part::number).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    Reply::ok(json!(days))
+}
+
+fn parse_error(err: &ParseError) -> Reply {
+    Reply {
+        status: 422,
+        body: json!({
+            "error": "parse",
+            "message": err.to_string(),
+            "line": err.line,
+            "column": err.column,
+            "len": err.len,
+            "text": err.text,
+            "expected": err.expected,
+        }),
+    }
+}
+
+/// Solves on another thread so a slow day can be abandoned after `limit`, or
+/// answers 503 if every slot is taken. An abandoned solver is cancelled, but
+/// holds on to its slot until it notices.
+fn solve(
+    solver: &'static dyn Solver,
+    part: Part,
+    input: String,
+    limit: Duration,
+    slots: &SolveSlots,
+) -> Reply {
+    let slot = match slots.take() {
+        Some(slot) => slot,
+        None => {
+            return Reply::error(
+                503,
+                "busy",
+                format!("Already solving {} puzzles, try again later", slots.max),
+            )
+        }
+    };
+    let (sender, receiver) = mpsc::channel();
+    let token = Token::new();
+    let solver_token = token.clone();
+    thread::spawn(move || {
+        let _slot = slot;
+        let answer = cancel::with_token(&solver_token, || solver.solve(&input, &[part]))
+            .and_then(|mut answers| answers.remove(0));
+        let _ = sender.send(answer);
+    });
+
+    match receiver.recv_timeout(limit) {
+        Ok(Ok(answer)) => Reply::ok(json!({
+            "day": solver.day(),
+            "part": part.number(),
+            "answer": answer,
+        })),
+        Ok(Err(err)) => match err.chain().find_map(|x| x.downcast_ref::<ParseError>()) {
+            Some(err) => parse_error(err),
+            None => Reply::error(500, "solver", format!("{:#}", err)),
+        },
+        Err(RecvTimeoutError::Timeout) => {
+            token.cancel();
+            Reply::error(504, "timeout", format!("No answer within {:?}", limit))
+        }
+        Err(RecvTimeoutError::Disconnected) => Reply::error(500, "solver", "The solver panicked"),
+    }
+}
+
+/// Routes one request. `body` is `None` if it wasn't UTF-8.
+pub fn handle(
+    method: &str,
+    url: &str,
+    body: Option<String>,
+    limit: Duration,
+    slots: &SolveSlots,
+) -> Reply {
+    let path = url.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|x| !x.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["days"]) => list_days(),
+        ("POST", ["days", day, "parts", part]) => {
+            let solver = match day.parse().ok().and_then(days::solver) {
+                Some(solver) => solver,
+                None => return Reply::error(404, "not_found", format!("There is no day {}", day)),
+            };
+            let part = match part.parse::<Part>() {
+                Ok(part) if solver.parts().contains(&part) => part,
+                _ => {
+                    return Reply::error(
+                        404,
+                        "not_found",
+                        format!("Day {} has no part {}", solver.day(), part),
+                    )
+                }
+            };
+            match body {
+                Some(input) => solve(solver, part, input, limit, slots),
+                None => Reply::error(400, "bad_request", "The puzzle input must be UTF-8"),
+            }
+        }
+        (_, ["days"]) | (_, ["days", _, "parts", _]) => Reply::error(
+            405,
+            "method_not_allowed",
+            format!("{} isn't allowed here", method),
+        ),
+        _ => Reply::error(404, "not_found", format!("Nothing at {}", path)),
+    }
+}
+
+/// Reads, handles and answers a request from the socket
+pub fn serve(
+    mut request: tiny_http::Request,
+    limit: Duration,
+    slots: &SolveSlots,
+) -> std::io::Result<()> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY as u64 + 1)
+        .read_to_end(&mut body)?;
+
+    let reply = if body.len() > MAX_BODY {
+        Reply::error(
+            413,
+            "too_large",
+            format!("Inputs are limited to {} bytes", MAX_BODY),
+        )
+    } else {
+        handle(
+            &request.method().to_string(),
+            request.url(),
+            String::from_utf8(body).ok(),
+            limit,
+            slots,
+        )
+    };
+
+    let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+    request.respond(
+        tiny_http::Response::from_string(reply.body.to_string() + "\n")
+            .with_status_code(reply.status)
+            .with_header(content_type),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIMIT: Duration = Duration::from_secs(10);
+
+    fn get(url: &str) -> Reply {
+        handle("GET", url, None, LIMIT, &SolveSlots::new(1))
+    }
+
+    fn post(url: &str, body: &str) -> Reply {
+        handle(
+            "POST",
+            url,
+            Some(body.to_string()),
+            LIMIT,
+            &SolveSlots::new(1),
+        )
+    }
+
+    #[test]
+    fn lists_days() {
+        let reply = get("/days");
+        assert_eq!(reply.status, 200);
+        let days = reply.body.as_array().unwrap();
+        assert_eq!(days.len(), 25);
+        assert_eq!(
+            days[24],
+            json!({ "day": 25, "title": "Combo Breaker", "parts": [1] })
+        );
+    }
+
+    #[test]
+    fn solves() {
+        let input = include_str!("../puzzles/day22/src/test_input.txt");
+        let reply = post("/days/22/parts/2", input);
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.body, json!({ "day": 22, "part": 2, "answer": "291" }));
+    }
+
+    #[test]
+    fn returns_parse_errors() {
+        let reply = post("/days/8/parts/1", "nop +0\nacc x12\n");
+        assert_eq!(reply.status, 422);
+        assert_eq!(reply.body["error"], "parse");
+        assert_eq!(reply.body["line"], 2);
+        assert_eq!(reply.body["text"], "acc x12");
+    }
+
+    #[test]
+    fn rejects_bad_routes() {
+        assert_eq!(post("/days/26/parts/1", "").status, 404);
+        assert_eq!(post("/days/25/parts/2", "").status, 404);
+        assert_eq!(post("/days/1/parts/x", "").status, 404);
+        assert_eq!(post("/nowhere", "").status, 404);
+        assert_eq!(get("/days/1/parts/1").status, 405);
+        let slots = SolveSlots::new(1);
+        assert_eq!(
+            handle("POST", "/days/1/parts/1", None, LIMIT, &slots).status,
+            400
+        );
+    }
+
+    #[test]
+    fn gives_up_after_the_limit() {
+        let reply = handle(
+            "POST",
+            "/days/23/parts/2",
+            Some(day23::PUZZLE_INPUT.to_string()),
+            Duration::from_millis(1),
+            &SolveSlots::new(1),
+        );
+        assert_eq!(reply.status, 504);
+        assert_eq!(reply.body["error"], "timeout");
+    }
+
+    #[test]
+    fn turns_requests_away_when_busy() {
+        let slots = SolveSlots::new(1);
+        let input = include_str!("../puzzles/day22/src/test_input.txt");
+        let solve = || {
+            handle(
+                "POST",
+                "/days/22/parts/1",
+                Some(input.to_string()),
+                LIMIT,
+                &slots,
+            )
+        };
+
+        let taken = slots.take().unwrap();
+        let reply = solve();
+        assert_eq!(reply.status, 503);
+        assert_eq!(reply.body["error"], "busy");
+
+        drop(taken);
+        assert_eq!(solve().status, 200);
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+
+use shared::{
+    prelude::*,
+    puzzle_input::{self, InputError},
+};
+
+use crate::{
+    days,
+    manifest::{Expected, InputRef},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        got: String,
+    },
+    /// No expected answer, or the input it refers to isn't on disk
+    Missing(&'static str),
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub day: u8,
+    pub part: Part,
+    pub input: String,
+    pub status: Status,
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, got } => {
+                write!(f, "FAIL     expected {}, got {}", expected, got)
+            }
+            Status::Missing(reason) => write!(f, "missing  {}", reason),
+            Status::Error(err) => write!(f, "ERROR    {}", err),
+        }
+    }
+}
+
+fn describe(input: &InputRef) -> String {
+    match input {
+        InputRef::Default => "default".to_string(),
+        InputRef::Path(path) => path
+            .strip_prefix(puzzle_input::workspace_root())
+            .unwrap_or(path)
+            .display()
+            .to_string(),
+    }
+}
+
+fn read(day: u8, input: &InputRef) -> Result<String, Status> {
+    let result = match input {
+        InputRef::Default => crate::read_input(day, None),
+        InputRef::Path(path) if !path.exists() => return Err(Status::Missing("no input file")),
+        InputRef::Path(path) => crate::read_input(day, Some(path)),
+    };
+
+    result.map_err(|err| match err.downcast_ref::<InputError>() {
+        Some(err) if err.is_missing() => Status::Missing("no input file"),
+        _ => Status::Error(format!("{:#}", err)),
+    })
+}
+
+/// Checks every expected answer for the given days, parsing each input once.
+/// Parts with nothing in the manifest are reported as missing.
+pub fn verify(manifest: &[Expected], days: &[u8]) -> Vec<Row> {
+    let mut rows = Vec::new();
+
+    for &day in days {
+        let solver = days::solver(day).expect("days are validated by the caller");
+        let entries: Vec<&Expected> = manifest.iter().filter(|x| x.day == day).collect();
+
+        let mut inputs: Vec<&InputRef> = Vec::new();
+        for entry in &entries {
+            if !inputs.contains(&&entry.input) {
+                inputs.push(&entry.input);
+            }
+        }
+
+        for input in inputs {
+            let expected: Vec<&Expected> = entries
+                .iter()
+                .copied()
+                .filter(|x| &x.input == input)
+                .collect();
+            let parts: Vec<Part> = expected.iter().map(|x| x.part).collect();
+
+            let statuses: Vec<Status> = match read(day, input) {
+                Ok(contents) => match solver.solve(&contents, &parts) {
+                    Ok(answers) => expected
+                        .iter()
+                        .zip(answers)
+                        .map(|(expected, got)| match got {
+                            Ok(got) if expected.answer == got => Status::Pass,
+                            Ok(got) => Status::Fail {
+                                expected: expected.answer.clone(),
+                                got,
+                            },
+                            Err(err) => Status::Error(format!("{:#}", err)),
+                        })
+                        .collect(),
+                    Err(err) => vec![Status::Error(format!("{:#}", err)); parts.len()],
+                },
+                Err(status) => vec![status; parts.len()],
+            };
+
+            rows.extend(parts.iter().zip(statuses).map(|(&part, status)| Row {
+                day,
+                part,
+                input: describe(input),
+                status,
+            }));
+        }
+
+        for &part in solver.parts() {
+            if !entries.iter().any(|x| x.part == part) {
+                rows.push(Row {
+                    day,
+                    part,
+                    input: "-".to_string(),
+                    status: Status::Missing("no expected answer"),
+                });
+            }
+        }
+    }
+
+    rows
+}
+
+pub fn print_table(rows: &[Row]) {
+    let width = rows
+        .iter()
+        .map(|x| x.input.len())
+        .chain(std::iter::once("Input".len()))
+        .max()
+        .unwrap_or_default();
+
+    println!("Day  Part  {:width$}  Result", "Input", width = width);
+    for row in rows {
+        println!(
+            "{:02}   {}     {:width$}  {}",
+            row.day,
+            row.part.number(),
+            row.input,
+            row.status,
+            width = width
+        );
+    }
+
+    let count = |f: fn(&Status) -> bool| rows.iter().filter(|x| f(&x.status)).count();
+    println!(
+        "\n{} passed, {} failed, {} missing",
+        count(|x| *x == Status::Pass),
+        count(Status::is_failure),
+        count(|x| matches!(x, Status::Missing(_)))
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn example(part: Part, answer: &str) -> Expected {
+        Expected {
+            day: 8,
+            part,
+            input: InputRef::Path(
+                puzzle_input::workspace_root().join("puzzles/day08/src/test_input.txt"),
+            ),
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn pass_and_fail() {
+        let rows = verify(&[example(Part::One, "5"), example(Part::Two, "9")], &[8]);
+        let statuses: Vec<_> = rows.into_iter().map(|x| x.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Status::Pass,
+                Status::Fail {
+                    expected: "9".to_string(),
+                    got: "8".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn missing() {
+        let mut entry = example(Part::One, "5");
+        entry.input = InputRef::Path(PathBuf::from("nobody/day08.txt"));
+        let rows = verify(&[entry], &[8]);
+        assert_eq!(rows[0].status, Status::Missing("no input file"));
+        assert_eq!(rows[1].part, Part::Two);
+        assert_eq!(rows[1].status, Status::Missing("no expected answer"));
+
+        let rows = verify(&[], &[25]);
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn builtin_input() {
+        let entry = Expected {
+            day: 25,
+            part: Part::One,
+            input: InputRef::Default,
+            answer: "8740494".to_string(),
+        };
+        assert_eq!(verify(&[entry], &[25])[0].status, Status::Pass);
+    }
+}
@@ -1 +0,0 @@
-cargo test --release --workspace -- -Z unstable-options --report-time=colored
\ No newline at end of file